@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+use crate::enums::AuditAction;
+use crate::unsigned::DbU64;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+	#[sea_orm(primary_key, auto_increment = true)]
+	pub id: DbU64,
+	pub guild_id: DbU64,
+	pub actor_id: Option<DbU64>,
+	pub target_id: Option<DbU64>,
+	pub action: AuditAction,
+	#[sea_orm(column_type = "Text")]
+	pub details: String,
+	pub created_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
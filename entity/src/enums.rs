@@ -1,14 +1,73 @@
 use std::fmt::Display;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{ArrayType, Nullable, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, TryFromU64, TryGetError, TryGetable};
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
-#[sea_orm(rs_type = "u8", db_type = "TinyUnsigned")]
+use crate::unsigned::DbU8;
+
+// PostgreSQLではu8を読み込めないので、DeriveActiveEnumを使わずDbU8経由で読み書きする
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter)]
 #[repr(u8)]
 pub enum AccountType {
 	Main = 1,
 	Sub = 2,
 }
 
+impl AccountType {
+	fn from_u8(value: u8) -> Option<Self> {
+		match value {
+			1 => Some(AccountType::Main),
+			2 => Some(AccountType::Sub),
+			_ => None,
+		}
+	}
+}
+
+impl From<AccountType> for Value {
+	fn from(value: AccountType) -> Self {
+		DbU8(value as u8).into()
+	}
+}
+
+impl Nullable for AccountType {
+	fn null() -> Value {
+		DbU8::null()
+	}
+}
+
+impl ValueType for AccountType {
+	fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+		<DbU8 as ValueType>::try_from(v).and_then(|v| AccountType::from_u8(v.get()).ok_or(ValueTypeErr))
+	}
+
+	fn type_name() -> String {
+		"AccountType".to_string()
+	}
+
+	fn array_type() -> ArrayType {
+		DbU8::array_type()
+	}
+
+	fn column_type() -> ColumnType {
+		DbU8::column_type()
+	}
+}
+
+impl TryGetable for AccountType {
+	fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+		let value = DbU8::try_get_by(res, index)?;
+		AccountType::from_u8(value.get())
+			.ok_or_else(|| TryGetError::DbErr(DbErr::Type(format!("unknown account type: {}", value))))
+	}
+}
+
+// 複合主キー (onboarding_role) に使うため
+impl TryFromU64 for AccountType {
+	fn try_from_u64(_: u64) -> Result<Self, DbErr> {
+		Err(DbErr::ConvertFromU64("AccountType"))
+	}
+}
+
 impl Display for AccountType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(f, "{}", match self {
@@ -35,3 +94,45 @@ impl Display for Gender {
 		}
 	}
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+pub enum AuditAction {
+	#[sea_orm(string_value = "config_update")]
+	ConfigUpdate,
+	#[sea_orm(string_value = "member_admit")]
+	MemberAdmit,
+	#[sea_orm(string_value = "member_kick")]
+	MemberKick,
+	#[sea_orm(string_value = "member_ban")]
+	MemberBan,
+	#[sea_orm(string_value = "member_remove")]
+	MemberRemove,
+	#[sea_orm(string_value = "vote_approve")]
+	VoteApprove,
+	#[sea_orm(string_value = "vote_reject")]
+	VoteReject,
+	#[sea_orm(string_value = "sub_confirm")]
+	SubConfirm,
+	#[sea_orm(string_value = "sub_approve")]
+	SubApprove,
+	#[sea_orm(string_value = "rules_accept")]
+	RulesAccept,
+}
+
+impl Display for AuditAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", match self {
+			AuditAction::ConfigUpdate => "設定変更",
+			AuditAction::MemberAdmit => "入鯖許可",
+			AuditAction::MemberKick => "キック",
+			AuditAction::MemberBan => "BAN",
+			AuditAction::MemberRemove => "削除",
+			AuditAction::VoteApprove => "投票承認",
+			AuditAction::VoteReject => "申請却下",
+			AuditAction::SubConfirm => "サブ垢承認",
+			AuditAction::SubApprove => "サブ垢承認完了",
+			AuditAction::RulesAccept => "ルール同意",
+		})
+	}
+}
@@ -2,17 +2,28 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::unsigned::DbU64;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "guild_config")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false)]
-	pub uid: u64,
+	pub uid: DbU64,
 	pub white_list: bool,
 	pub leave_ban: bool,
-	pub log_channel_id: Option<u64>,
-	pub auth_role_id: Option<u64>,
-	pub bot_role_id: Option<u64>,
-	pub send_ai_chat_channel_id: Option<u64>,
+	pub log_channel_id: Option<DbU64>,
+	pub auth_role_id: Option<DbU64>,
+	pub bot_role_id: Option<DbU64>,
+	pub send_ai_chat_channel_id: Option<DbU64>,
+	pub locale: Option<String>,
+	pub welcome_channel_id: Option<DbU64>,
+	pub welcome_dm: bool,
+	pub rules_accept: bool,
+	#[sea_orm(column_type = "Text", nullable)]
+	pub voice_wake_words: Option<String>,
+	pub voice_interjection_rate: i32,
+	pub voice_idle_topic_seconds: i32,
+	pub voice_style_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -21,10 +32,18 @@ pub enum Relation {
 	ConfirmedAccount,
 	#[sea_orm(has_many = "super::main_account::Entity")]
 	MainAccount,
+	#[sea_orm(has_many = "super::message_template::Entity")]
+	MessageTemplate,
+	#[sea_orm(has_many = "super::onboarding_role::Entity")]
+	OnboardingRole,
 	#[sea_orm(has_many = "super::pending_account::Entity")]
 	PendingAccount,
 	#[sea_orm(has_many = "super::sub_account::Entity")]
 	SubAccount,
+	#[sea_orm(has_many = "super::voice_persona::Entity")]
+	VoicePersona,
+	#[sea_orm(has_many = "super::tts_dictionary::Entity")]
+	TtsDictionary,
 }
 
 impl Related<super::confirmed_account::Entity> for Entity {
@@ -39,6 +58,18 @@ impl Related<super::main_account::Entity> for Entity {
 	}
 }
 
+impl Related<super::message_template::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::MessageTemplate.def()
+	}
+}
+
+impl Related<super::onboarding_role::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::OnboardingRole.def()
+	}
+}
+
 impl Related<super::pending_account::Entity> for Entity {
 	fn to() -> RelationDef {
 		Relation::PendingAccount.def()
@@ -51,4 +82,16 @@ impl Related<super::sub_account::Entity> for Entity {
 	}
 }
 
+impl Related<super::voice_persona::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::VoicePersona.def()
+	}
+}
+
+impl Related<super::tts_dictionary::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::TtsDictionary.def()
+	}
+}
+
 impl ActiveModelBehavior for ActiveModel {}
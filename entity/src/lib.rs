@@ -1,14 +1,20 @@
 pub mod enums;
+pub mod unsigned;
 
+pub mod audit_log;
 pub mod confirmed_account;
 pub mod guild_config;
 pub mod main_account;
+pub mod message_template;
+pub mod onboarding_role;
 pub mod pending_account;
 pub mod remind;
 pub mod remind_assignee;
 pub mod sub_account;
 pub mod user_data;
 pub mod talk_history;
+pub mod voice_persona;
+pub mod tts_dictionary;
 
 pub type ConfirmedAccount = confirmed_account::Model;
 pub type ConfirmedAccountBehavior = confirmed_account::Entity;
@@ -19,6 +25,12 @@ pub type GuildConfigBehavior = guild_config::Entity;
 pub type MainAccount = main_account::Model;
 pub type MainAccountBehavior = main_account::Entity;
 
+pub type MessageTemplate = message_template::Model;
+pub type MessageTemplateBehavior = message_template::Entity;
+
+pub type OnboardingRole = onboarding_role::Model;
+pub type OnboardingRoleBehavior = onboarding_role::Entity;
+
 pub type PendingAccount = pending_account::Model;
 pub type PendingAccountBehavior = pending_account::Entity;
 
@@ -36,3 +48,12 @@ pub type RemindAssigneeBehavior = remind_assignee::Entity;
 
 pub type TalkHistory = talk_history::Model;
 pub type TalkHistoryBehavior = talk_history::Entity;
+
+pub type AuditLog = audit_log::Model;
+pub type AuditLogBehavior = audit_log::Entity;
+
+pub type VoicePersona = voice_persona::Model;
+pub type VoicePersonaBehavior = voice_persona::Entity;
+
+pub type TtsDictionary = tts_dictionary::Model;
+pub type TtsDictionaryBehavior = tts_dictionary::Entity;
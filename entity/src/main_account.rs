@@ -2,14 +2,16 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::unsigned::{DbU64, DbU8};
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "main_account")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false)]
-	pub uid: u64,
+	pub uid: DbU64,
 	pub name: String,
-	pub guild_id: u64,
-	pub version: u8,
+	pub guild_id: DbU64,
+	pub version: DbU8,
 	pub join_date: ChronoDateTimeUtc,
 	pub is_server_creator: bool,
 	pub is_leaved: bool,
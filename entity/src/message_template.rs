@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+use crate::unsigned::DbU64;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "message_template")]
+pub struct Model {
+	#[sea_orm(primary_key, auto_increment = false)]
+	pub guild_id: DbU64,
+	#[sea_orm(primary_key, auto_increment = false)]
+	pub key: String,
+	#[sea_orm(column_type = "Text")]
+	pub template: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::guild_config::Entity",
+		from = "Column::GuildId",
+		to = "super::guild_config::Column::Uid",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	GuildConfig,
+}
+
+impl Related<super::guild_config::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::GuildConfig.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -3,19 +3,20 @@
 use sea_orm::entity::prelude::*;
 
 use crate::enums::AccountType;
+use crate::unsigned::DbU64;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "pending_account")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false)]
-	pub uid: u64,
+	pub uid: DbU64,
 	pub name: Option<String>,
-	pub guild_id: u64,
+	pub guild_id: DbU64,
 	pub account_type: AccountType,
-	pub message_id: u64,
+	pub message_id: DbU64,
 	pub end_voting: Option<ChronoDateTimeUtc>,
-	pub main_uid: Option<u64>,
-	pub first_cert: Option<u64>,
+	pub main_uid: Option<DbU64>,
+	pub first_cert: Option<DbU64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
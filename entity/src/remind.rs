@@ -2,15 +2,17 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::unsigned::{DbU32, DbU64};
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "remind")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = true)]
-	pub id: u32,
+	pub id: DbU32,
 	#[sea_orm(column_type = "Text")]
 	pub task_name: String,
-	pub author_id: u64,
-	pub assignees_id: Option<u32>,
+	pub author_id: DbU64,
+	pub assignees_id: Option<DbU32>,
 	pub remind_date: ChronoDateTimeUtc,
 }
 
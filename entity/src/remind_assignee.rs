@@ -2,12 +2,14 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::unsigned::{DbU32, DbU64};
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "remind_assignee")]
 pub struct Model {
 	#[sea_orm(primary_key)]
-	pub id: u32,
-	pub user_id: u64,
+	pub id: DbU32,
+	pub user_id: DbU64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -2,17 +2,19 @@
 
 use sea_orm::entity::prelude::*;
 
+use crate::unsigned::DbU64;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "sub_account")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false)]
-	pub uid: u64,
+	pub uid: DbU64,
 	pub name: String,
-	pub guild_id: u64,
+	pub guild_id: DbU64,
 	pub join_date: ChronoDateTimeUtc,
-	pub main_uid: u64,
-	pub first_cert: u64,
-	pub second_cert: Option<u64>,
+	pub main_uid: DbU64,
+	pub first_cert: DbU64,
+	pub second_cert: Option<DbU64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
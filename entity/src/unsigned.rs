@@ -0,0 +1,118 @@
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, DbErr, QueryResult, TryFromU64, TryGetError, TryGetable, Value};
+use std::fmt::Display;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/*
+MySQLのunsigned列をSQLite/PostgreSQLでも読み書きするための型
+sea-orm 0.12はu8/u32/u64をMySQL以外で読み込めない場合があるので、読み込みに失敗したら符号付きの値として読み直す
+(Discordのsnowflakeはi64の範囲に収まるので、どのDBでも同じ値になる)
+*/
+macro_rules! unsigned_value {
+	($name:ident, $unsigned:ty, $signed:ty, $variant:ident, $column_type:ident) => {
+		#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+		pub struct $name(pub $unsigned);
+
+		impl $name {
+			pub fn get(self) -> $unsigned {
+				self.0
+			}
+		}
+
+		impl Deref for $name {
+			type Target = $unsigned;
+
+			fn deref(&self) -> &Self::Target {
+				&self.0
+			}
+		}
+
+		impl From<$unsigned> for $name {
+			fn from(value: $unsigned) -> Self {
+				Self(value)
+			}
+		}
+
+		impl From<$name> for $unsigned {
+			fn from(value: $name) -> Self {
+				value.0
+			}
+		}
+
+		impl PartialEq<$unsigned> for $name {
+			fn eq(&self, other: &$unsigned) -> bool {
+				self.0 == *other
+			}
+		}
+
+		impl FromStr for $name {
+			type Err = <$unsigned as FromStr>::Err;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				s.parse().map(Self)
+			}
+		}
+
+		impl Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				self.0.fmt(f)
+			}
+		}
+
+		impl From<$name> for Value {
+			fn from(value: $name) -> Self {
+				Value::$variant(Some(value.0))
+			}
+		}
+
+		impl Nullable for $name {
+			fn null() -> Value {
+				Value::$variant(None)
+			}
+		}
+
+		impl ValueType for $name {
+			fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+				<$unsigned as ValueType>::try_from(v).map(Self)
+			}
+
+			fn type_name() -> String {
+				stringify!($name).to_string()
+			}
+
+			fn array_type() -> ArrayType {
+				<$unsigned as ValueType>::array_type()
+			}
+
+			fn column_type() -> ColumnType {
+				ColumnType::$column_type
+			}
+		}
+
+		impl TryGetable for $name {
+			fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+				match <$unsigned as TryGetable>::try_get_by(res, index) {
+					Ok(value) => Ok(Self(value)),
+					Err(TryGetError::Null(error)) => Err(TryGetError::Null(error)),
+					Err(TryGetError::DbErr(_)) => {
+						let value = <$signed as TryGetable>::try_get_by(res, index)?;
+						<$unsigned as TryFrom<$signed>>::try_from(value)
+							.map(Self)
+							.map_err(|error| TryGetError::DbErr(DbErr::Type(error.to_string())))
+					},
+				}
+			}
+		}
+
+		impl TryFromU64 for $name {
+			fn try_from_u64(n: u64) -> Result<Self, DbErr> {
+				<$unsigned as TryFromU64>::try_from_u64(n).map(Self)
+			}
+		}
+	};
+}
+
+unsigned_value!(DbU8, u8, i16, TinyUnsigned, TinyUnsigned);
+unsigned_value!(DbU32, u32, i32, Unsigned, Unsigned);
+unsigned_value!(DbU64, u64, i64, BigUnsigned, BigUnsigned);
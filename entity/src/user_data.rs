@@ -3,16 +3,17 @@
 use sea_orm::entity::prelude::*;
 
 use crate::enums::Gender;
+use crate::unsigned::{DbU32, DbU64};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "user_data")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false)]
-	pub uid: u64,
+	pub uid: DbU64,
 	pub glacialeur: Option<String>,
 	pub call_name: Option<String>,
 	pub gender: Option<Gender>,
-	pub chat_message_count: Option<u32>,
+	pub chat_message_count: Option<DbU32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
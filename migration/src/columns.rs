@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+/*
+DBによって型を変える必要がある列
+MySQLでは今までと同じ型になるので、既存のDBには影響しません
+*/
+
+// 自動採番の主キー (PostgreSQLのserialは符号付きしか無い)
+pub fn auto_increment_id<T: IntoIden>(manager: &SchemaManager, column: T, big: bool) -> ColumnDef {
+	let mut column = ColumnDef::new(column);
+	match (manager.get_database_backend(), big) {
+		(DbBackend::Postgres, false) => column.integer(),
+		(DbBackend::Postgres, true) => column.big_integer(),
+		(_, false) => column.unsigned(),
+		(_, true) => column.big_unsigned(),
+	};
+	column.primary_key().not_null().auto_increment();
+	column
+}
+
+// UTCの日時 (PostgreSQLはtimestamp with time zoneでないとDateTime<Utc>として読めない)
+pub fn date_time_utc<T: IntoIden>(manager: &SchemaManager, column: T) -> ColumnDef {
+	let mut column = ColumnDef::new(column);
+	match manager.get_database_backend() {
+		DbBackend::Postgres => column.timestamp_with_time_zone(),
+		_ => column.date_time(),
+	};
+	column
+}
@@ -13,6 +13,18 @@ mod m20250601_164835_modify_user_data;
 mod m20250604_151741_modify_guild_config;
 mod m20250606_155740_modify_level_to_message_counter;
 mod m20250606_160312_add_talk_history;
+mod m20251019_120000_add_audit_log;
+mod m20251019_130000_add_guild_config_locale;
+mod m20251019_130100_add_message_template;
+mod m20251019_140000_add_guild_config_onboarding;
+mod m20251019_140100_add_onboarding_role;
+mod m20251019_150000_add_user_data_glacialeur_index;
+mod m20251019_160000_add_guild_config_voice;
+mod m20251019_170000_add_guild_config_voice_idle_topic;
+mod m20251019_180000_add_guild_config_voice_style;
+mod m20251019_180100_add_voice_persona;
+mod m20251019_190000_add_tts_dictionary;
+mod columns;
 mod tables;
 
 pub struct Migrator;
@@ -34,6 +46,17 @@ impl MigratorTrait for Migrator {
             Box::new(m20250604_151741_modify_guild_config::Migration),
             Box::new(m20250606_155740_modify_level_to_message_counter::Migration),
             Box::new(m20250606_160312_add_talk_history::Migration),
+            Box::new(m20251019_120000_add_audit_log::Migration),
+            Box::new(m20251019_130000_add_guild_config_locale::Migration),
+            Box::new(m20251019_130100_add_message_template::Migration),
+            Box::new(m20251019_140000_add_guild_config_onboarding::Migration),
+            Box::new(m20251019_140100_add_onboarding_role::Migration),
+            Box::new(m20251019_150000_add_user_data_glacialeur_index::Migration),
+            Box::new(m20251019_160000_add_guild_config_voice::Migration),
+            Box::new(m20251019_170000_add_guild_config_voice_idle_topic::Migration),
+            Box::new(m20251019_180000_add_guild_config_voice_style::Migration),
+            Box::new(m20251019_180100_add_voice_persona::Migration),
+            Box::new(m20251019_190000_add_tts_dictionary::Migration),
         ]
     }
 }
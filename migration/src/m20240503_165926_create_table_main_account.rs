@@ -1,5 +1,6 @@
 use sea_orm_migration::prelude::*;
 use crate::tables::{GuildConfig, MainAccount};
+use crate::columns::date_time_utc;
 
 const FK_GUILD_ID: &str = "fk_guild_id";
 
@@ -35,8 +36,7 @@ impl MigrationTrait for Migration {
                         .not_null()
                 )
                 .col(
-                    ColumnDef::new(MainAccount::JoinDate)
-                        .date_time()
+                    date_time_utc(manager, MainAccount::JoinDate)
                         .not_null()
                 )
                 .col(
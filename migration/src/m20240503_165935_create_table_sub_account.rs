@@ -1,5 +1,6 @@
 use sea_orm_migration::prelude::*;
 use crate::tables::{GuildConfig, MainAccount, SubAccount};
+use crate::columns::date_time_utc;
 
 const FK_MAIN_UID: &str = "sub_fk_main_uid";
 const FK_FIRST_CERT: &str = "sub_fk_first_cert";
@@ -33,8 +34,7 @@ impl MigrationTrait for Migration {
                         .not_null()
                 )
                 .col(
-                    ColumnDef::new(SubAccount::JoinDate)
-                        .date_time()
+                    date_time_utc(manager, SubAccount::JoinDate)
                         .not_null()
                 )
                 .col(
@@ -1,5 +1,6 @@
 use sea_orm_migration::prelude::*;
 use crate::tables::{PendingAccount, GuildConfig, MainAccount};
+use crate::columns::date_time_utc;
 
 const FK_MAIN_UID: &str = "pending_fk_main_uid";
 const FK_FIRST_CERT: &str = "pending_fk_first_cert";
@@ -42,8 +43,7 @@ impl MigrationTrait for Migration {
                         .not_null()
                 )
                 .col(
-                    ColumnDef::new(PendingAccount::EndVoting)
-                        .date_time()
+                    date_time_utc(manager, PendingAccount::EndVoting)
                         .null()
                 )
                 .col(
@@ -1,6 +1,7 @@
 use sea_orm_migration::prelude::*;
 
 use crate::tables::{RemindAssignee, UserData};
+use crate::columns::auto_increment_id;
 
 const FK_USER_ID: &str = "assignee_fk_user_id";
 
@@ -13,13 +14,7 @@ impl MigrationTrait for Migration {
 		let table = Table::create()
 			.table(RemindAssignee::Table)
 			.if_not_exists()
-			.col(
-				ColumnDef::new(RemindAssignee::Id)
-					.unsigned()
-					.primary_key()
-					.not_null()
-					.auto_increment(),
-			)
+			.col(&mut auto_increment_id(manager, RemindAssignee::Id, false))
 			.col(ColumnDef::new(RemindAssignee::UserId).big_unsigned().not_null())
 			.foreign_key(
 				ForeignKey::create()
@@ -1,6 +1,7 @@
 use sea_orm_migration::{prelude::*};
 
 use crate::tables::{Remind, RemindAssignee, UserData};
+use crate::columns::{auto_increment_id, date_time_utc};
 
 const FK_AUTHOR_ID: &str = "remind_fk_author_id";
 const FK_ASSIGNEE_ID: &str = "remind_fk_assignee_id";
@@ -14,13 +15,7 @@ impl MigrationTrait for Migration {
         let table = Table::create()
 			.table(Remind::Table)
 			.if_not_exists()
-			.col(
-				ColumnDef::new(Remind::Id)
-					.unsigned()
-					.primary_key()
-					.not_null()
-					.auto_increment(),
-			)
+			.col(&mut auto_increment_id(manager, Remind::Id, false))
             .col(
 				ColumnDef::new(Remind::TaskName)
 					.text()
@@ -37,8 +32,7 @@ impl MigrationTrait for Migration {
 					.unsigned(),
 			)
             .col(
-				ColumnDef::new(Remind::RemindDate)
-                    .date_time()
+				date_time_utc(manager, Remind::RemindDate)
 					.not_null(),
 			)
 			.foreign_key(
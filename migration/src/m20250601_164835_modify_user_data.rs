@@ -7,44 +7,52 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        let table =
-            Table::alter()
-                .table(UserData::Table)
-                .add_column(
-                    ColumnDef::new(UserData::CallName)
-                        .string_len(32)
-                        .null()
-                        .default("")
-                )
-                .add_column(
-                    ColumnDef::new(UserData::Gender)
-                        .char_len(1)
-                        .null()
-                )
-                .add_column(
-                    ColumnDef::new(UserData::LikabilityLevel)
-                        .unsigned()
-                        .null()
-                        .default(0)
-                )
-                .to_owned();
-
-        manager
-            .alter_table(table)
-            .await
+        // SQLiteは1つのALTER TABLEで1つの変更しかできないので列ごとに分ける
+        let columns = [
+            ColumnDef::new(UserData::CallName)
+                .string_len(32)
+                .null()
+                .default("")
+                .to_owned(),
+            ColumnDef::new(UserData::Gender)
+                .char_len(1)
+                .null()
+                .to_owned(),
+            ColumnDef::new(UserData::LikabilityLevel)
+                .unsigned()
+                .null()
+                .default(0)
+                .to_owned(),
+        ];
+
+        for mut column in columns {
+            let table =
+                Table::alter()
+                    .table(UserData::Table)
+                    .add_column(&mut column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
     }
 
     async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        let table =
-            Table::alter()
-                .table(UserData::Table)
-                .drop_column(UserData::CallName)
-                .drop_column(UserData::Gender)
-                .drop_column(UserData::LikabilityLevel)
-                .to_owned();
-
-        manager
-            .alter_table(table)
-            .await
+        for column in [UserData::CallName, UserData::Gender, UserData::LikabilityLevel] {
+            let table =
+                Table::alter()
+                    .table(UserData::Table)
+                    .drop_column(column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
     }
 }
@@ -8,10 +8,20 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLiteは1つのALTER TABLEで1つの変更しかできないので分ける
         let table =
             Table::alter()
                 .table(UserData::Table)
                 .drop_column(UserData::LikabilityLevel)
+                .to_owned();
+
+        manager
+            .alter_table(table)
+            .await?;
+
+        let table =
+            Table::alter()
+                .table(UserData::Table)
                 .add_column(
                     ColumnDef::new(UserData::ChatMessageCount)
                         .unsigned()
@@ -30,6 +40,15 @@ impl MigrationTrait for Migration {
             Table::alter()
                 .table(UserData::Table)
                 .drop_column(UserData::ChatMessageCount)
+                .to_owned();
+
+        manager
+            .alter_table(table)
+            .await?;
+
+        let table =
+            Table::alter()
+                .table(UserData::Table)
                 .add_column(
                     ColumnDef::new(UserData::LikabilityLevel)
                         .unsigned()
@@ -1,6 +1,7 @@
 use sea_orm_migration::prelude::*;
 
 use crate::tables::{TalkHistory, UserData};
+use crate::columns::auto_increment_id;
 
 const FK_USER_ID: &str = "talk_history_fk_user_id";
 
@@ -13,13 +14,7 @@ impl MigrationTrait for Migration {
         let table = Table::create()
 			.table(TalkHistory::Table)
 			.if_not_exists()
-			.col(
-				ColumnDef::new(TalkHistory::Id)
-					.unsigned()
-					.primary_key()
-					.not_null()
-					.auto_increment(),
-			)
+			.col(&mut auto_increment_id(manager, TalkHistory::Id, false))
             .col(
 				ColumnDef::new(TalkHistory::UserId)
 					.big_unsigned()
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::AuditLog;
+use crate::columns::{auto_increment_id, date_time_utc};
+
+const IDX_GUILD_CREATED: &str = "audit_log_idx_guild_id_created_at";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // チャンネルのログが消されても残るように、guild_configとは紐付けない
+        let table = Table::create()
+			.table(AuditLog::Table)
+			.if_not_exists()
+			.col(&mut auto_increment_id(manager, AuditLog::Id, true))
+			.col(
+				ColumnDef::new(AuditLog::GuildId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(AuditLog::ActorId)
+					.big_unsigned()
+					.null(),
+			)
+			.col(
+				ColumnDef::new(AuditLog::TargetId)
+					.big_unsigned()
+					.null(),
+			)
+			.col(
+				ColumnDef::new(AuditLog::Action)
+					.string_len(32)
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(AuditLog::Details)
+					.text()
+					.not_null(),
+			)
+			.col(
+				date_time_utc(manager, AuditLog::CreatedAt)
+					.not_null(),
+			)
+			.to_owned();
+
+		manager.create_table(table).await?;
+
+		let index = Index::create()
+			.name(IDX_GUILD_CREATED)
+			.table(AuditLog::Table)
+			.col(AuditLog::GuildId)
+			.col(AuditLog::CreatedAt)
+			.to_owned();
+
+		manager.create_index(index).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::drop().table(AuditLog::Table).to_owned();
+
+		manager.drop_table(table).await
+    }
+}
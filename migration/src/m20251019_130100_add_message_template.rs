@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::{GuildConfig, MessageTemplate};
+
+const FK_GUILD_ID: &str = "message_template_fk_guild_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::create()
+			.table(MessageTemplate::Table)
+			.if_not_exists()
+			.col(
+				ColumnDef::new(MessageTemplate::GuildId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(MessageTemplate::Key)
+					.string_len(64)
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(MessageTemplate::Template)
+					.text()
+					.not_null(),
+			)
+			.primary_key(
+				Index::create()
+					.col(MessageTemplate::GuildId)
+					.col(MessageTemplate::Key),
+			)
+			.foreign_key(
+				ForeignKey::create()
+					.name(FK_GUILD_ID)
+					.from_col(MessageTemplate::GuildId)
+					.to(GuildConfig::Table, GuildConfig::Uid)
+					.on_delete(ForeignKeyAction::Cascade)
+					.on_update(ForeignKeyAction::Cascade),
+			)
+			.to_owned();
+
+		manager.create_table(table).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::drop().table(MessageTemplate::Table).to_owned();
+
+		manager.drop_table(table).await
+    }
+}
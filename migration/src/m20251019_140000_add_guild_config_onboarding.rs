@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::GuildConfig;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLiteは1つのALTER TABLEで1つの変更しかできないので列ごとに分ける
+        let columns = [
+            ColumnDef::new(GuildConfig::WelcomeChannelId)
+                .big_unsigned()
+                .null()
+                .to_owned(),
+            ColumnDef::new(GuildConfig::WelcomeDm)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+            ColumnDef::new(GuildConfig::RulesAccept)
+                .boolean()
+                .not_null()
+                .default(false)
+                .to_owned(),
+        ];
+
+        for mut column in columns {
+            let table =
+                Table::alter()
+                    .table(GuildConfig::Table)
+                    .add_column(&mut column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [GuildConfig::WelcomeChannelId, GuildConfig::WelcomeDm, GuildConfig::RulesAccept] {
+            let table =
+                Table::alter()
+                    .table(GuildConfig::Table)
+                    .drop_column(column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::{GuildConfig, OnboardingRole};
+
+const FK_GUILD_ID: &str = "onboarding_role_fk_guild_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::create()
+			.table(OnboardingRole::Table)
+			.if_not_exists()
+			.col(
+				ColumnDef::new(OnboardingRole::GuildId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(OnboardingRole::AccountType)
+					.tiny_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(OnboardingRole::RoleId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.primary_key(
+				Index::create()
+					.col(OnboardingRole::GuildId)
+					.col(OnboardingRole::AccountType)
+					.col(OnboardingRole::RoleId),
+			)
+			.foreign_key(
+				ForeignKey::create()
+					.name(FK_GUILD_ID)
+					.from_col(OnboardingRole::GuildId)
+					.to(GuildConfig::Table, GuildConfig::Uid)
+					.on_delete(ForeignKeyAction::Cascade)
+					.on_update(ForeignKeyAction::Cascade),
+			)
+			.to_owned();
+
+		manager.create_table(table).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::drop().table(OnboardingRole::Table).to_owned();
+
+		manager.drop_table(table).await
+    }
+}
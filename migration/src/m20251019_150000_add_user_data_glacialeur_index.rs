@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::UserData;
+
+const IDX_GLACIALEUR: &str = "user_data_idx_glacialeur";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 既存のデータに重複があっても当てられるように、ユニークにはしない
+        let index = Index::create()
+			.name(IDX_GLACIALEUR)
+			.table(UserData::Table)
+			.col(UserData::Glacialeur)
+			.to_owned();
+
+		manager.create_index(index).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let index = Index::drop().name(IDX_GLACIALEUR).table(UserData::Table).to_owned();
+
+		manager.drop_index(index).await
+    }
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::GuildConfig;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLiteは1つのALTER TABLEで1つの変更しかできないので列ごとに分ける
+        let columns = [
+            // カンマ区切り (デフォルトのウェイクワードに追加される)
+            ColumnDef::new(GuildConfig::VoiceWakeWords)
+                .text()
+                .null()
+                .to_owned(),
+            // 複数人の時に、呼ばれていなくても話に入る確率 (%)
+            ColumnDef::new(GuildConfig::VoiceInterjectionRate)
+                .integer()
+                .not_null()
+                .default(20)
+                .to_owned(),
+        ];
+
+        for mut column in columns {
+            let table =
+                Table::alter()
+                    .table(GuildConfig::Table)
+                    .add_column(&mut column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [GuildConfig::VoiceWakeWords, GuildConfig::VoiceInterjectionRate] {
+            let table =
+                Table::alter()
+                    .table(GuildConfig::Table)
+                    .drop_column(column)
+                    .to_owned();
+
+            manager
+                .alter_table(table)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
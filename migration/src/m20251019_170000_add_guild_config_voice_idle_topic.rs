@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::GuildConfig;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 誰も話していない時に話題を出すまでの秒数 (0の場合は出さない)
+        let table =
+            Table::alter()
+                .table(GuildConfig::Table)
+                .add_column(
+                    ColumnDef::new(GuildConfig::VoiceIdleTopicSeconds)
+                        .integer()
+                        .not_null()
+                        .default(300)
+                )
+                .to_owned();
+
+        manager
+            .alter_table(table)
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table =
+            Table::alter()
+                .table(GuildConfig::Table)
+                .drop_column(GuildConfig::VoiceIdleTopicSeconds)
+                .to_owned();
+
+        manager
+            .alter_table(table)
+            .await
+    }
+}
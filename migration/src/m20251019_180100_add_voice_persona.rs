@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::{GuildConfig, VoicePersona};
+
+const FK_GUILD_ID: &str = "voice_persona_fk_guild_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::create()
+			.table(VoicePersona::Table)
+			.if_not_exists()
+			.col(
+				ColumnDef::new(VoicePersona::GuildId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(VoicePersona::Name)
+					.string_len(64)
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(VoicePersona::StyleId)
+					.integer()
+					.not_null(),
+			)
+			.primary_key(
+				Index::create()
+					.col(VoicePersona::GuildId)
+					.col(VoicePersona::Name),
+			)
+			.foreign_key(
+				ForeignKey::create()
+					.name(FK_GUILD_ID)
+					.from_col(VoicePersona::GuildId)
+					.to(GuildConfig::Table, GuildConfig::Uid)
+					.on_delete(ForeignKeyAction::Cascade)
+					.on_update(ForeignKeyAction::Cascade),
+			)
+			.to_owned();
+
+		manager.create_table(table).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::drop().table(VoicePersona::Table).to_owned();
+
+		manager.drop_table(table).await
+    }
+}
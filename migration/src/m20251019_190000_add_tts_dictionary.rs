@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+use crate::tables::{GuildConfig, TtsDictionary};
+
+const FK_GUILD_ID: &str = "tts_dictionary_fk_guild_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::create()
+			.table(TtsDictionary::Table)
+			.if_not_exists()
+			.col(
+				ColumnDef::new(TtsDictionary::GuildId)
+					.big_unsigned()
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(TtsDictionary::Surface)
+					.string_len(64)
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(TtsDictionary::Pronunciation)
+					.string_len(128)
+					.not_null(),
+			)
+			.col(
+				ColumnDef::new(TtsDictionary::AccentType)
+					.integer()
+					.not_null(),
+			)
+			.primary_key(
+				Index::create()
+					.col(TtsDictionary::GuildId)
+					.col(TtsDictionary::Surface),
+			)
+			.foreign_key(
+				ForeignKey::create()
+					.name(FK_GUILD_ID)
+					.from_col(TtsDictionary::GuildId)
+					.to(GuildConfig::Table, GuildConfig::Uid)
+					.on_delete(ForeignKeyAction::Cascade)
+					.on_update(ForeignKeyAction::Cascade),
+			)
+			.to_owned();
+
+		manager.create_table(table).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let table = Table::drop().table(TtsDictionary::Table).to_owned();
+
+		manager.drop_table(table).await
+    }
+}
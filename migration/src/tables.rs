@@ -14,6 +14,16 @@ pub enum GuildConfig {
 
 	// 2025-06-05 added
 	SendAiChatChannelId,
+
+	// 2025-10-19 added
+	Locale,
+	WelcomeChannelId,
+	WelcomeDm,
+	RulesAccept,
+	VoiceWakeWords,
+	VoiceInterjectionRate,
+	VoiceIdleTopicSeconds,
+	VoiceStyleId,
 }
 
 #[derive(DeriveIden)]
@@ -123,3 +133,58 @@ pub enum TalkHistory {
 	OutputText,
 	TalkDate,
 }
+
+#[derive(DeriveIden)]
+pub enum AuditLog {
+	Table,
+
+	// Column
+	Id,
+	GuildId,
+	ActorId,
+	TargetId,
+	Action,
+	Details,
+	CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum MessageTemplate {
+	Table,
+
+	// Column
+	GuildId,
+	Key,
+	Template,
+}
+
+#[derive(DeriveIden)]
+pub enum OnboardingRole {
+	Table,
+
+	// Column
+	GuildId,
+	AccountType,
+	RoleId,
+}
+
+#[derive(DeriveIden)]
+pub enum VoicePersona {
+	Table,
+
+	// Column
+	GuildId,
+	Name,
+	StyleId,
+}
+
+#[derive(DeriveIden)]
+pub enum TtsDictionary {
+	Table,
+
+	// Column
+	GuildId,
+	Surface,
+	Pronunciation,
+	AccentType,
+}
@@ -1,3 +1,4 @@
+-- MySQL用 (SQLite・PostgreSQLではmigrationを使ってください)
 create table guild_config (
     uid bigint unsigned primary key,
     white_list boolean default false,
@@ -0,0 +1,36 @@
+use crate::state::AppState;
+use chrono::Utc;
+use entity::audit_log;
+use entity::enums::AuditAction;
+use entity::unsigned::DbU64;
+use log::error;
+use sea_orm::ActiveModelTrait;
+use sea_orm::ActiveValue::Set;
+use serde_json::Value;
+
+/*
+モデレーションや設定変更などの操作をaudit_logに記録する
+ログチャンネルのメッセージが消えても追えるように、detailsには操作内容をJSONで残す
+*/
+pub async fn record(
+	state: &AppState,
+	guild_id: u64,
+	actor_id: Option<u64>,
+	target_id: Option<u64>,
+	action: AuditAction,
+	details: Value,
+) {
+	let audit_log = audit_log::ActiveModel {
+		guild_id: Set(guild_id.into()),
+		actor_id: Set(actor_id.map(DbU64)),
+		target_id: Set(target_id.map(DbU64)),
+		action: Set(action),
+		details: Set(details.to_string()),
+		created_at: Set(Utc::now()),
+		..Default::default()
+	};
+
+	if let Err(error) = audit_log.insert(state.db()).await {
+		error!("DB Error: {:?} ({:?}: {})", error, action, details);
+	}
+}
@@ -1,18 +1,106 @@
 use chrono::Utc;
-use entity::{enums::Gender, talk_history};
-use openai_dive::v1::{api::Client, error::APIError, resources::{response::{request::{ContentInput, InputMessage, ResponseInput, ResponseInputItem, ResponseParametersBuilder}, response::{OutputContent, ResponseOutput, ResponseText, Role}, shared::{ResponseFormat, ResponseTool, UserLocationType, WebSearchUserLocation}}, shared::WebSearchContextSize}};
-use param::ResponseData;
+use entity::{enums::Gender, talk_history, TalkHistory, UserData};
+use openai_dive::v1::{api::Client, error::APIError, resources::{response::{request::{ContentInput, InputMessage, ResponseInput, ResponseInputItem, ResponseParametersBuilder}, response::{OutputContent, ResponseObject, ResponseOutput, ResponseText, Role}, shared::{ResponseFormat, ResponseTool, UserLocationType, WebSearchUserLocation}}, shared::WebSearchContextSize}};
+use param::{calculate_likability_level_from_message_count, ResponseData};
 use prompt::SYSTEM_PROMPT;
 use sea_orm::Set;
 use sea_orm::ActiveModelTrait;
+use futures::StreamExt;
+use stream::{EventStreamParser, MessageExtractor};
+use tokio::sync::mpsc::UnboundedSender;
 
-use crate::STATIC_COMPONENTS;
+use crate::state::AppState;
 
 mod prompt;
 pub(crate) mod param;
+mod stream;
 
-pub(crate) async fn getchat_responce(user_id: u64, user_message: ResponseInputItem, prev_id: Option<String>) -> Result<(ResponseData, String), APIError> {
-	let input_message = if let ResponseInputItem::Message(v) = &user_message {
+// 話題を出してもらう時の指示 (会話履歴に残るので、次に話題を出す時は除く)
+const TOPIC_REQUEST: &str = "参加者と最近の会話をもとに、新しい話題を振ってください";
+
+pub(crate) async fn getchat_responce(state: &AppState, user_id: u64, user_message: ResponseInputItem, prev_id: Option<String>) -> Result<(ResponseData, String), APIError> {
+	let input_message = input_text(&user_message);
+
+	let token = state.config().get_chatgpt_token().clone();
+
+	let client = Client::new(token);
+	let responses = client.responses();
+
+	let param = create_parameters(user_message, prev_id, "");
+	let res = responses.create(param.build().unwrap()).await?;
+
+	complete_responce(state, user_id, input_message, res).await
+}
+
+// getchat_responceのストリーミング版
+// 生成途中のmessageのテキストをtext_senderに少しずつ送り、生成が終わったらactionsも含めた全体を返す
+// (talk_historyへの保存と、返す会話idはgetchat_responceと同じ)
+// instructionsはシステムプロンプトの後ろに追加される (ボイスチャットで使えるactionの説明など)
+pub(crate) async fn getchat_responce_stream(state: &AppState, user_id: u64, user_message: ResponseInputItem, prev_id: Option<String>, instructions: &str, text_sender: UnboundedSender<String>) -> Result<(ResponseData, String), APIError> {
+	let input_message = input_text(&user_message);
+
+	let token = state.config().get_chatgpt_token().clone();
+
+	let client = Client::new(token);
+
+	let mut param = create_parameters(user_message, prev_id, instructions);
+	param.stream(true);
+
+	// openai_diveはResponses APIのストリーミングに対応していないので、リクエストは直接送る
+	let response = client.http_client
+		.post(format!("{}/responses", client.base_url))
+		.bearer_auth(&client.api_key)
+		.json(&param.build().unwrap())
+		.send()
+		.await
+		.map_err(|e| APIError::StreamError(e.to_string()))?;
+
+	let status = response.status();
+	if !status.is_success() {
+		let body = response.text().await.unwrap_or_default();
+		return Err(APIError::UnknownError(status.as_u16(), body));
+	}
+
+	let mut stream = response.bytes_stream();
+	let mut parser = EventStreamParser::default();
+	let mut extractor = MessageExtractor::default();
+	while let Some(chunk) = stream.next().await {
+		let chunk = chunk.map_err(|e| APIError::StreamError(e.to_string()))?;
+
+		for data in parser.push(&chunk) {
+			let event: serde_json::Value = serde_json::from_str(&data)
+				.map_err(|e| APIError::ParseError(e.to_string()))?;
+
+			match event["type"].as_str().unwrap_or_default() {
+				"response.output_text.delta" => {
+					let text = extractor.push(event["delta"].as_str().unwrap_or_default());
+					if !text.is_empty() {
+						// 受け取る側がいなくなっても、保存のために最後まで受け取る
+						let _ = text_sender.send(text);
+					}
+				},
+				"response.completed" | "response.incomplete" => {
+					let res: ResponseObject = serde_json::from_value(event["response"].clone())
+						.map_err(|e| APIError::ParseError(e.to_string()))?;
+
+					return complete_responce(state, user_id, input_message, res).await;
+				},
+				"response.failed" => {
+					return Err(APIError::StreamError(event["response"]["error"].to_string()));
+				},
+				"error" => {
+					return Err(APIError::StreamError(event.to_string()));
+				},
+				_ => {},
+			}
+		}
+	}
+
+	Err(APIError::StreamError("stream is closed before completed".to_string()))
+}
+
+fn input_text(user_message: &ResponseInputItem) -> String {
+	if let ResponseInputItem::Message(v) = user_message {
 		if let ContentInput::Text(t) = &v.content {
 			t.clone()
 		} else {
@@ -20,20 +108,16 @@ pub(crate) async fn getchat_responce(user_id: u64, user_message: ResponseInputIt
 		}
 	} else {
 		String::new()
-	};
-
-	let comp_lock = STATIC_COMPONENTS.lock().await;
-	let token = comp_lock.get_config().get_chatgpt_token().clone();
-	std::mem::drop(comp_lock);
-
-	let client = Client::new(token);
-	let responses = client.responses();
+	}
+}
 
+fn create_parameters(user_message: ResponseInputItem, prev_id: Option<String>, instructions: &str) -> ResponseParametersBuilder {
 	let mut param = ResponseParametersBuilder::default();
 	param.model("gpt-4.1".to_string());
-	param.instructions(SYSTEM_PROMPT);
+	// instructionsはprevious_response_idで引き継がれないので、毎回全体を送る
+	param.instructions(format!("{}{}", SYSTEM_PROMPT, instructions));
 	param.input(ResponseInput::List(vec![
-		user_message.clone()
+		user_message
 	]));
 	param.text(ResponseText {
 		format: ResponseFormat::Text
@@ -59,8 +143,11 @@ pub(crate) async fn getchat_responce(user_id: u64, user_message: ResponseInputIt
 		param.previous_response_id(prev_id);
 	}
 
-	let res = responses.create(param.build().unwrap()).await?;
+	param
+}
 
+// 会話内容を保存して、返答をパースする
+async fn complete_responce(state: &AppState, user_id: u64, input_message: String, res: ResponseObject) -> Result<(ResponseData, String), APIError> {
 	let id = res.id;
 	let res_output_last = res.output.last().unwrap();
 	if let ResponseOutput::Message(message) = res_output_last {
@@ -71,17 +158,14 @@ pub(crate) async fn getchat_responce(user_id: u64, user_message: ResponseInputIt
 				chat_id: Set(id.clone()),
 				input_text: Set(input_message),
 				output_text: Set(text.clone()),
-				user_id: Set(user_id),
+				user_id: Set(user_id.into()),
 				// UTC?
 				talk_date: Set(Utc::now().naive_utc()),
 				..Default::default()
 			};
-			let lsc = STATIC_COMPONENTS.lock().await;
-			let mysql_client = lsc.get_sql_client();
-			let _ = th.insert(mysql_client).await.inspect_err(|e| {
+			let _ = th.insert(state.db()).await.inspect_err(|e| {
 				log::error!("{:?}", e);
 			});
-			std::mem::drop(lsc);
 
 			// パースできたら返す
 			if let Ok(final_res_data) = ResponseData::from_json(&text) {
@@ -115,6 +199,23 @@ pub(crate) fn create_user_message<S1: Into<String>, S2: Into<String>>(message: S
 	})
 }
 
+// サーバーに登録されている声のペルソナと、切り替える時のactionの説明 (ペルソナが無い場合は空)
+pub(crate) fn create_persona_instructions(personas: &[String]) -> String {
+	if personas.is_empty() {
+		return String::new();
+	}
+
+	let mut res = String::new();
+	res += "\n\n# 声の切り替え\n";
+	res += "使える声のペルソナ:\n";
+	for persona in personas {
+		res += &format!("- {}\n", persona);
+	}
+	res += "ペルソナの声で話す時は、actionsに {\"name\": \"change_voice\", \"params\": {\"persona\": \"<ペルソナ名>\"}} を追加してください\n";
+	res += "元の声に戻す時は、personaを空文字にしてください\n";
+	res
+}
+
 pub(crate) fn create_multi_user_message(items: Vec<&ResponseInputItem>) -> ResponseInputItem {
 	let mut text = String::new();
 	let mut first = true;
@@ -138,3 +239,56 @@ pub(crate) fn create_multi_user_message(items: Vec<&ResponseInputItem>) -> Respo
 		role: Role::User
 	})
 }
+
+// 一定時間誰も話していない時に、話題を出してもらうためのメッセージ
+// 参加者のプロフィールと最近の会話 (新しい順) から作る
+pub(crate) fn create_topic_message(users: &[UserData], histories: &[TalkHistory], time: &chrono::DateTime<chrono::Local>) -> ResponseInputItem {
+	let mut res = String::new();
+
+	res += "状況: ボイスチャンネルでしばらく誰も話していません\n";
+	res += &format!("時刻: {}\n", time.format("%H:%M"));
+
+	res += "参加者:\n";
+	for user in users {
+		let level = calculate_likability_level_from_message_count(user.chat_message_count.unwrap_or_default().get());
+		let name = user.call_name.as_deref().unwrap_or("不明");
+		let gender = user.gender.as_ref().map_or("不明".to_string(), |g| g.to_string());
+		res += &format!("- ID: {}, 名前: {}, 性別: {}, 好感度レベル: {}\n", user.uid.get(), name, gender, level);
+	}
+
+	let histories: Vec<_> = histories.iter().filter(|v| !v.input_text.ends_with(TOPIC_REQUEST)).collect();
+	if !histories.is_empty() {
+		res += "最近の会話:\n";
+		for history in histories.into_iter().rev() {
+			// ユーザーのメッセージは "####" 以降が本文
+			let input = history.input_text.split("####\n").last().unwrap_or_default();
+			let output = ResponseData::from_json(&history.output_text).map_or_else(|_| history.output_text.clone(), |v| v.message);
+			res += &format!("- {}: {}\n", history.user_id.get(), input.replace('\n', " "));
+			res += &format!("- エステラ: {}\n", output.replace('\n', " "));
+		}
+	}
+
+	res += "####\n";
+	res += TOPIC_REQUEST;
+
+	ResponseInputItem::Message(InputMessage {
+		content: ContentInput::Text(res),
+		role: Role::User
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn persona_instructions_list_personas_and_action() {
+		assert_eq!(create_persona_instructions(&[]), "");
+
+		let res = create_persona_instructions(&["ナレーター".to_string(), "子供".to_string()]);
+		assert!(res.contains("- ナレーター\n"));
+		assert!(res.contains("- 子供\n"));
+		assert!(res.contains("\"name\": \"change_voice\""));
+		assert!(res.contains("\"persona\""));
+	}
+}
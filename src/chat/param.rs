@@ -2,7 +2,7 @@ use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub(crate) struct ResponseData {
 	pub(crate) message: String,
 	pub(crate) actions: Vec<ResponseAction>,
@@ -0,0 +1,188 @@
+/*
+Responses APIのストリーミング (Server-Sent Events) の処理
+返答は {"message": "...", "actions": [...]} のJSONなので、生成途中のJSONからmessageの文字列だけを取り出して、読み上げに先に回す
+actionsは生成が終わってから全体をパースする
+*/
+
+// SSEのイベントを区切って、dataの部分を返す (チャンクの途中で文字やイベントが切れていることがある)
+#[derive(Default)]
+pub(crate) struct EventStreamParser {
+	buffer: Vec<u8>,
+}
+
+impl EventStreamParser {
+	pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+		self.buffer.extend_from_slice(chunk);
+
+		let mut events = Vec::new();
+		while let Some(pos) = self.buffer.windows(2).position(|v| v == b"\n\n") {
+			let event: Vec<u8> = self.buffer.drain(..pos + 2).collect();
+			let event = String::from_utf8_lossy(&event);
+			let data: Vec<&str> = event
+				.lines()
+				.filter_map(|line| line.strip_prefix("data:"))
+				.map(|v| v.strip_prefix(' ').unwrap_or(v))
+				.collect();
+			if !data.is_empty() {
+				events.push(data.join("\n"));
+			}
+		}
+		events
+	}
+}
+
+// 生成途中のJSONから、トップレベルのmessageの値を少しずつ取り出す
+#[derive(Default)]
+pub(crate) struct MessageExtractor {
+	depth: u32,
+	in_string: bool,
+	escape: bool,
+	// \uXXXX の読み途中
+	unicode: Option<String>,
+	// サロゲートペアの上位
+	high_surrogate: Option<u32>,
+	// トップレベルで最後に読んだキー
+	key: String,
+	// "message": の後
+	after_message_key: bool,
+	// messageの値を読んでいる
+	capturing: bool,
+	finished: bool,
+}
+
+impl MessageExtractor {
+	// 新しく取り出せたmessageのテキストを返す
+	pub(crate) fn push(&mut self, text: &str) -> String {
+		let mut output = String::new();
+		for c in text.chars() {
+			if self.finished {
+				break;
+			}
+			if self.in_string {
+				self.read_string_char(c, &mut output);
+				continue;
+			}
+
+			match c {
+				'"' => {
+					self.in_string = true;
+					self.capturing = self.after_message_key;
+					self.after_message_key = false;
+					self.key.clear();
+				},
+				'{' | '[' => {
+					self.depth += 1;
+					self.after_message_key = false;
+				},
+				'}' | ']' => self.depth = self.depth.saturating_sub(1),
+				':' if self.depth == 1 => self.after_message_key = self.key == "message",
+				c if c.is_whitespace() => {},
+				_ => self.after_message_key = false,
+			}
+		}
+		output
+	}
+
+	fn read_string_char(&mut self, c: char, output: &mut String) {
+		if let Some(unicode) = self.unicode.as_mut() {
+			unicode.push(c);
+			if unicode.len() == 4 {
+				let code = u32::from_str_radix(unicode, 16).unwrap_or(0xFFFD);
+				self.unicode = None;
+				self.push_code(code, output);
+			}
+			return;
+		}
+
+		if self.escape {
+			self.escape = false;
+			let c = match c {
+				'n' => '\n',
+				't' => '\t',
+				'r' => '\r',
+				'b' => '\u{8}',
+				'f' => '\u{c}',
+				'u' => {
+					self.unicode = Some(String::new());
+					return;
+				},
+				c => c,
+			};
+			self.push_char(c, output);
+			return;
+		}
+
+		match c {
+			'\\' => self.escape = true,
+			'"' => {
+				self.in_string = false;
+				if self.capturing {
+					self.capturing = false;
+					self.finished = true;
+				}
+			},
+			c => self.push_char(c, output),
+		}
+	}
+
+	fn push_code(&mut self, code: u32, output: &mut String) {
+		if (0xD800..0xDC00).contains(&code) {
+			self.high_surrogate = Some(code);
+			return;
+		}
+
+		let code = match self.high_surrogate.take() {
+			Some(high) if (0xDC00..0xE000).contains(&code) => 0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00),
+			_ => code,
+		};
+		self.push_char(char::from_u32(code).unwrap_or('\u{FFFD}'), output);
+	}
+
+	fn push_char(&mut self, c: char, output: &mut String) {
+		if self.capturing {
+			output.push(c);
+		} else if self.depth == 1 {
+			self.key.push(c);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn extract_all(chunks: &[&str]) -> String {
+		let mut extractor = MessageExtractor::default();
+		chunks.iter().map(|v| extractor.push(v)).collect()
+	}
+
+	#[test]
+	fn extract_message_from_chunks() {
+		let json = r#"{"message": "こんにちは。\n今日は\"いい\"天気だね", "actions": []}"#;
+		let chunks: Vec<String> = json.chars().map(|v| v.to_string()).collect();
+		let chunks: Vec<&str> = chunks.iter().map(|v| v.as_str()).collect();
+		assert_eq!(extract_all(&chunks), "こんにちは。\n今日は\"いい\"天気だね");
+	}
+
+	#[test]
+	fn extract_unicode_escape() {
+		assert_eq!(extract_all(&[r#"{"message":"あ\u30"#, r#"44 \ud83d"#, r#"\ude00"}"#]), "あい 😀");
+	}
+
+	#[test]
+	fn ignore_nested_message_key() {
+		let json = r#"{"actions": [{"name": "send_message_channel", "params": {"message": "x"}}], "message": "やっほー"}"#;
+		assert_eq!(extract_all(&[json]), "やっほー");
+	}
+
+	#[test]
+	fn parse_server_sent_events() {
+		let mut parser = EventStreamParser::default();
+		let text = "event: response.output_text.delta\ndata: {\"delta\":\"あ\"}\n\nevent: response.completed\ndata: {}\n\n";
+		let bytes = text.as_bytes();
+		// マルチバイト文字の途中で切る
+		let split = text.find('あ').unwrap() + 1;
+		assert!(parser.push(&bytes[..split]).is_empty());
+		assert_eq!(parser.push(&bytes[split..]), vec!["{\"delta\":\"あ\"}", "{}"]);
+	}
+}
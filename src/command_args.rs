@@ -0,0 +1,191 @@
+use serenity::all::{ChannelId, CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommandOption, RoleId, UserId};
+use std::error::Error;
+use std::fmt::Display;
+
+/*
+Commandの引数を構造体として定義するためのトレイト
+command_args!で定義した構造体は、オプションの生成とCommandDataOptionのパースを自動で行う
+*/
+
+#[derive(Debug)]
+pub enum ArgsError {
+	Missing(&'static str),
+	InvalidType {
+		name: &'static str,
+		expected: CommandOptionType,
+		actual: CommandOptionType,
+	},
+}
+
+impl Error for ArgsError {}
+
+impl Display for ArgsError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ArgsError::Missing(name) => write!(f, "{}が入力されていません", name),
+			ArgsError::InvalidType { name, expected, actual } => write!(
+				f,
+				"{}の記述が正しくありません (expected: {:?}, actual: {:?})",
+				name, expected, actual
+			),
+		}
+	}
+}
+
+pub trait ArgValue: Sized {
+	const KIND: CommandOptionType;
+	const REQUIRED: bool = true;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self>;
+
+	fn from_option(name: &'static str, value: Option<&CommandDataOptionValue>) -> Result<Self, ArgsError> {
+		let value = value.ok_or(ArgsError::Missing(name))?;
+
+		Self::from_value(value).ok_or_else(|| ArgsError::InvalidType {
+			name,
+			expected: Self::KIND,
+			actual: value.kind(),
+		})
+	}
+}
+
+impl<T: ArgValue> ArgValue for Option<T> {
+	const KIND: CommandOptionType = T::KIND;
+	const REQUIRED: bool = false;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		T::from_value(value).map(Some)
+	}
+
+	fn from_option(name: &'static str, value: Option<&CommandDataOptionValue>) -> Result<Self, ArgsError> {
+		match value {
+			Some(value) => T::from_option(name, Some(value)).map(Some),
+			None => Ok(None),
+		}
+	}
+}
+
+impl ArgValue for String {
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_str().map(|v| v.to_string())
+	}
+}
+
+impl ArgValue for i64 {
+	const KIND: CommandOptionType = CommandOptionType::Integer;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_i64()
+	}
+}
+
+impl ArgValue for f64 {
+	const KIND: CommandOptionType = CommandOptionType::Number;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_f64()
+	}
+}
+
+impl ArgValue for bool {
+	const KIND: CommandOptionType = CommandOptionType::Boolean;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_bool()
+	}
+}
+
+impl ArgValue for UserId {
+	const KIND: CommandOptionType = CommandOptionType::User;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_user_id()
+	}
+}
+
+impl ArgValue for ChannelId {
+	const KIND: CommandOptionType = CommandOptionType::Channel;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_channel_id()
+	}
+}
+
+impl ArgValue for RoleId {
+	const KIND: CommandOptionType = CommandOptionType::Role;
+
+	fn from_value(value: &CommandDataOptionValue) -> Option<Self> {
+		value.as_role_id()
+	}
+}
+
+pub trait CommandArgs: Sized {
+	fn options() -> Vec<CreateCommandOption>;
+	fn parse(args: &[CommandDataOption]) -> Result<Self, ArgsError>;
+}
+
+pub fn find_arg<'a>(args: &'a [CommandDataOption], name: &str) -> Option<&'a CommandDataOptionValue> {
+	args.iter().find(|v| v.name == name).map(|v| &v.value)
+}
+
+// 入力中の引数(オートコンプリートの対象)の値名と入力値を返す
+pub fn find_focused_arg(args: &[CommandDataOption]) -> Option<(&str, &str)> {
+	args.iter().find_map(|v| match &v.value {
+		CommandDataOptionValue::Autocomplete { value, .. } => Some((v.name.as_str(), value.as_str())),
+		_ => None,
+	})
+}
+
+pub fn build_arg_option<T: ArgValue>(name: &str, description: &str, autocomplete: bool) -> CreateCommandOption {
+	CreateCommandOption::new(T::KIND, name, description)
+		.required(T::REQUIRED)
+		.set_autocomplete(autocomplete)
+}
+
+/*
+フィールド名: 型 = ("値名", "説明") で構成されています
+Option<T>の場合は必須ではない引数になります
+("値名", "説明", autocomplete) とするとオートコンプリートが有効になります (String/Integer/Numberのみ)
+*/
+macro_rules! command_args {
+	(@autocomplete autocomplete) => {
+		true
+	};
+	(@autocomplete) => {
+		false
+	};
+	(
+		$vis:vis struct $name:ident {
+			$($field:ident: $ty:ty = ($arg_name:expr, $arg_desc:expr $(, $autocomplete:ident)?)),* $(,)?
+		}
+	) => {
+		$vis struct $name {
+			$(pub $field: $ty,)*
+		}
+
+		impl crate::command_args::CommandArgs for $name {
+			fn options() -> Vec<serenity::all::CreateCommandOption> {
+				vec![$(crate::command_args::build_arg_option::<$ty>(
+					$arg_name,
+					$arg_desc,
+					crate::command_args::command_args!(@autocomplete $($autocomplete)?),
+				),)*]
+			}
+
+			fn parse(
+				args: &[serenity::all::CommandDataOption],
+			) -> Result<Self, crate::command_args::ArgsError> {
+				Ok(Self {
+					$($field: <$ty as crate::command_args::ArgValue>::from_option(
+						$arg_name,
+						crate::command_args::find_arg(args, $arg_name),
+					)?,)*
+				})
+			}
+		}
+	};
+}
+
+pub(crate) use command_args;
@@ -34,22 +34,18 @@ pub trait BaseCommand {
 	}
 }
 
-/*
-Paramsは値名→説明→型定義→必須で構成されています
-*/
-const PARAMS: [(&str, &str, CommandOptionType, bool); 0] = [];
-
 #[async_trait]
 pub trait Command: BaseCommand {
-	fn args_param(&self) -> &'static [(&'static str, &'static str, CommandOptionType, bool)] {
-		&PARAMS
+	// 引数の定義はcommand_args!で作った構造体のoptions()を返す
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		vec![]
 	}
 
 	fn build_args(&self, option: CreateCommandOption) -> CreateCommandOption {
 		let mut option = option;
 
-		for (name, desc, option_type, req) in self.args_param() {
-			option = option.add_sub_option(CreateCommandOption::new(*option_type, *name, *desc).required(*req));
+		for arg_option in self.args_options() {
+			option = option.add_sub_option(arg_option);
 		}
 
 		option
@@ -62,6 +58,29 @@ pub trait Command: BaseCommand {
 		args: Vec<CommandDataOption>,
 	) -> serenity::Result<()>;
 
+	// オートコンプリートが有効な引数を入力中に呼ばれる
+	async fn autocomplete(
+		&self,
+		_ctx: Context,
+		_command: CommandInteraction,
+		_args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		Ok(())
+	}
+
+	async fn route(
+		&self,
+		kind: CommandRouteKind,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		match kind {
+			CommandRouteKind::Execute => self.execute(ctx, command, args).await,
+			CommandRouteKind::Autocomplete => self.autocomplete(ctx, command, args).await,
+		}
+	}
+
 	fn command_build(&self) -> CreateCommandOption {
 		let res = CreateCommandOption::new(CommandOptionType::SubCommand, self.get_name(), self.get_description());
 
@@ -84,6 +103,7 @@ pub trait SubCommand: BaseCommand {
 
 	async fn commands_route(
 		&self,
+		kind: CommandRouteKind,
 		ctx: Context,
 		command: CommandInteraction,
 		sub_command: CommandDataOption,
@@ -110,7 +130,7 @@ pub trait SubCommand: BaseCommand {
 				if let CommonCommandType::SubCommand(cmd) = sub_cmd {
 					if command_name == cmd.get_name() {
 						executed_command = true;
-						cmd.commands_route(ctx, command, sub_sub_command).await?;
+						cmd.commands_route(kind, ctx, command, sub_sub_command).await?;
 						break;
 					}
 				}
@@ -123,7 +143,7 @@ pub trait SubCommand: BaseCommand {
 				if let CommonCommandType::Command(cmd) = cmd {
 					if command_name == cmd.get_name() {
 						executed_command = true;
-						cmd.execute(ctx, command, sub_command_option).await?;
+						cmd.route(kind, ctx, command, sub_command_option).await?;
 						break;
 					}
 				}
@@ -152,6 +172,12 @@ pub trait SubCommand: BaseCommand {
 	}
 }
 
+#[derive(Clone, Copy)]
+pub enum CommandRouteKind {
+	Execute,
+	Autocomplete,
+}
+
 pub enum CommonCommandType {
 	Command(Box<dyn Command + Sync + Send>),
 	SubCommand(Box<dyn SubCommand + Sync + Send>),
@@ -0,0 +1,181 @@
+use crate::commands;
+use crate::configs::CommandScope;
+use log::{error, info};
+use serde_json::Value;
+use serenity::all::{Command, CommandId, CreateCommand, GuildId, Http};
+use std::fmt::Display;
+
+/*
+起動時にBotが持っているコマンド定義とDiscord側に登録されているコマンドを比較し、
+差分(作成/更新/削除)だけを反映させる
+*/
+
+#[derive(Clone, Copy)]
+enum CommandTarget {
+	Global,
+	Guild(GuildId),
+}
+
+impl Display for CommandTarget {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CommandTarget::Global => write!(f, "global"),
+			CommandTarget::Guild(guild_id) => write!(f, "guild {}", guild_id.get()),
+		}
+	}
+}
+
+impl CommandTarget {
+	async fn get_commands(&self, http: &Http) -> serenity::Result<Vec<Command>> {
+		match self {
+			CommandTarget::Global => Command::get_global_commands(http).await,
+			CommandTarget::Guild(guild_id) => guild_id.get_commands(http).await,
+		}
+	}
+
+	async fn create_command(&self, http: &Http, builder: CreateCommand) -> serenity::Result<Command> {
+		match self {
+			CommandTarget::Global => Command::create_global_command(http, builder).await,
+			CommandTarget::Guild(guild_id) => guild_id.create_command(http, builder).await,
+		}
+	}
+
+	async fn edit_command(&self, http: &Http, command_id: CommandId, builder: CreateCommand) -> serenity::Result<Command> {
+		match self {
+			CommandTarget::Global => Command::edit_global_command(http, command_id, builder).await,
+			CommandTarget::Guild(guild_id) => guild_id.edit_command(http, command_id, builder).await,
+		}
+	}
+
+	async fn delete_command(&self, http: &Http, command_id: CommandId) -> serenity::Result<()> {
+		match self {
+			CommandTarget::Global => Command::delete_global_command(http, command_id).await,
+			CommandTarget::Guild(guild_id) => guild_id.delete_command(http, command_id).await,
+		}
+	}
+}
+
+#[derive(Default)]
+struct SyncSummary {
+	created: Vec<String>,
+	updated: Vec<String>,
+	deleted: Vec<String>,
+	unchanged: Vec<String>,
+	failed: Vec<String>,
+}
+
+impl SyncSummary {
+	fn log(&self, target: CommandTarget) {
+		if self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty() && self.failed.is_empty() {
+			info!("[{}] commands are up to date ({} unchanged)", target, self.unchanged.len());
+			return;
+		}
+
+		info!(
+			"[{}] created: {:?}, updated: {:?}, deleted: {:?}, unchanged: {:?}, failed: {:?}",
+			target, self.created, self.updated, self.deleted, self.unchanged, self.failed
+		);
+	}
+}
+
+fn desired_commands() -> Vec<CreateCommand> {
+	vec![commands::app_commands_build()]
+}
+
+fn command_name(builder: &CreateCommand) -> String {
+	serde_json::to_value(builder)
+		.ok()
+		.and_then(|v| v.get("name").and_then(|n| n.as_str()).map(|n| n.to_string()))
+		.unwrap_or_default()
+}
+
+// どちらもserenityのCommandOptionを経由してシリアライズされるので、そのまま比較ができる
+fn is_same_command(builder: &CreateCommand, registered: &Command) -> bool {
+	let desired = match serde_json::to_value(builder) {
+		Ok(v) => v,
+		Err(error) => {
+			error!("Command serialize error: {:?}", error);
+			return false;
+		},
+	};
+
+	let desired_description = desired.get("description").cloned().unwrap_or(Value::Null);
+	let desired_options = desired.get("options").cloned().unwrap_or(Value::Array(vec![]));
+	let registered_options = serde_json::to_value(&registered.options).unwrap_or(Value::Null);
+
+	desired_description == Value::String(registered.description.clone()) && desired_options == registered_options
+}
+
+async fn sync_target(http: &Http, target: CommandTarget, desired: Vec<CreateCommand>) {
+	let registered = match target.get_commands(http).await {
+		Ok(v) => v,
+		Err(error) => {
+			error!("[{}] Could not get registered commands: {:?}", target, error);
+			return;
+		},
+	};
+
+	let mut summary = SyncSummary::default();
+
+	for builder in desired {
+		let name = command_name(&builder);
+		match registered.iter().find(|v| v.name == name) {
+			None => match target.create_command(http, builder).await {
+				Ok(_) => summary.created.push(name),
+				Err(error) => {
+					error!("[{}] create error ({}): {:?}", target, name, error);
+					summary.failed.push(name);
+				},
+			},
+			Some(command) if is_same_command(&builder, command) => summary.unchanged.push(name),
+			Some(command) => match target.edit_command(http, command.id, builder).await {
+				Ok(_) => summary.updated.push(name),
+				Err(error) => {
+					error!("[{}] update error ({}): {:?}", target, name, error);
+					summary.failed.push(name);
+				},
+			},
+		}
+	}
+
+	let desired_names: Vec<String> = summary
+		.created
+		.iter()
+		.chain(summary.updated.iter())
+		.chain(summary.unchanged.iter())
+		.chain(summary.failed.iter())
+		.cloned()
+		.collect();
+	for command in &registered {
+		if desired_names.contains(&command.name) {
+			continue;
+		}
+
+		match target.delete_command(http, command.id).await {
+			Ok(_) => summary.deleted.push(command.name.clone()),
+			Err(error) => {
+				error!("[{}] delete error ({}): {:?}", target, command.name, error);
+				summary.failed.push(command.name.clone());
+			},
+		}
+	}
+
+	summary.log(target);
+}
+
+pub async fn sync_commands(http: &Http, scope: CommandScope, guilds: &[GuildId]) {
+	info!("Command sync start (scope: {:?})", scope);
+
+	// 登録しない側のスコープに残っているコマンドは重複して表示されるので消しておく
+	let (global_commands, guild_commands) = match scope {
+		CommandScope::Global => (desired_commands(), vec![]),
+		CommandScope::Guild => (vec![], desired_commands()),
+	};
+
+	sync_target(http, CommandTarget::Global, global_commands).await;
+	for guild_id in guilds {
+		sync_target(http, CommandTarget::Guild(*guild_id), guild_commands.clone()).await;
+	}
+
+	info!("Command sync end");
+}
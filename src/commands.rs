@@ -1,5 +1,6 @@
-use crate::command_define::{BaseCommand, BuildCommandOption, CommonCommandType};
+use crate::command_define::{BaseCommand, BuildCommandOption, CommandRouteKind, CommonCommandType};
 use crate::commands::ping::PingCommand;
+use admin::AdminCommands;
 use config::ConfigCommand;
 use disconnect::DisconnectCommand;
 use log::{debug, error};
@@ -9,6 +10,7 @@ use serenity::client::Context;
 use talk::TalkCommand;
 use user::UserCommands;
 use version::VersionCommand;
+use voice::VoiceCommands;
 use std::convert::Into;
 use std::sync::LazyLock;
 //use serenity::model::interactions::InteractionResponseType;
@@ -32,23 +34,27 @@ macro_rules! convert_sub_command {
 	};
 }
 
+mod admin;
 mod config;
 mod ping;
 mod user;
 mod version;
 mod talk;
 mod disconnect;
+mod voice;
 
 static COMMANDS: LazyLock<Vec<CommonCommandType>> = LazyLock::new(|| vec![
 	convert_command!(PingCommand),
 	convert_command!(ConfigCommand),
 	convert_sub_command!(UserCommands),
+	convert_sub_command!(AdminCommands),
+	convert_sub_command!(VoiceCommands),
 	convert_command!(VersionCommand),
 	convert_command!(TalkCommand),
 	convert_command!(DisconnectCommand),
 ]);
 
-async fn root_commands_route(ctx: Context, command: CommandInteraction) -> serenity::Result<()> {
+async fn root_commands_route(kind: CommandRouteKind, ctx: Context, command: CommandInteraction) -> serenity::Result<()> {
 	if command.data.options.len() != 1 {
 		error!("Command option length is not 1.");
 		return Ok(());
@@ -67,7 +73,7 @@ async fn root_commands_route(ctx: Context, command: CommandInteraction) -> seren
 				if cmd.get_name() == sub_command_name {
 					let sub_command_value =
 						extract_enum!(sub_command.to_owned().value, CommandDataOptionValue::SubCommand);
-					cmd.execute(ctx, command, sub_command_value).await?;
+					cmd.route(kind, ctx, command, sub_command_value).await?;
 					executed = true;
 					break;
 				}
@@ -82,7 +88,7 @@ async fn root_commands_route(ctx: Context, command: CommandInteraction) -> seren
 				if cmd.get_name() == sub_command_name {
 					let sub_command_value =
 						extract_enum!(sub_command.to_owned().value, CommandDataOptionValue::SubCommandGroup);
-					cmd.commands_route(ctx, command, sub_command_value[0].to_owned())
+					cmd.commands_route(kind, ctx, command, sub_command_value[0].to_owned())
 						.await?;
 					executed = true;
 					break;
@@ -119,7 +125,7 @@ pub async fn interaction_route(ctx: Context, command: CommandInteraction) {
 	}
 
 	let res = match command.data.name.as_str() {
-		"estella" => root_commands_route(ctx, command).await,
+		"estella" => root_commands_route(CommandRouteKind::Execute, ctx, command).await,
 		_ => {
 			error!("No Exist Command!");
 			Ok(())
@@ -155,6 +161,25 @@ pub async fn interaction_route(ctx: Context, command: CommandInteraction) {
 	}*/
 }
 
+pub async fn autocomplete_route(ctx: Context, command: CommandInteraction) {
+	debug!("\nautocompleteID: {}\nname: {}", command.id, command.data.name);
+
+	if command.user.bot {
+		return;
+	}
+
+	let res = match command.data.name.as_str() {
+		"estella" => root_commands_route(CommandRouteKind::Autocomplete, ctx, command).await,
+		_ => {
+			error!("No Exist Command!");
+			Ok(())
+		},
+	};
+	if let Err(error) = res {
+		error!("{}", error);
+	}
+}
+
 fn root_command_build(command: CreateCommand) -> CreateCommand {
 	let mut command = command.name("estella").description("Estella Command Root");
 
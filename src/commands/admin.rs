@@ -0,0 +1,45 @@
+use audit_log::AuditLogCommand;
+use locale::LocaleCommand;
+use onboarding::OnboardingCommand;
+use template::TemplateCommand;
+use voice::VoiceCommand;
+
+use crate::command_define::{BaseCommand, CommonCommandType, SubCommand};
+
+mod audit_log;
+mod locale;
+mod onboarding;
+mod template;
+mod voice;
+
+pub struct AdminCommands {
+	sub_commands: Vec<CommonCommandType>,
+}
+
+impl BaseCommand for AdminCommands {
+	fn new() -> Self {
+		Self {
+			sub_commands: vec![
+				convert_command!(AuditLogCommand),
+				convert_command!(TemplateCommand),
+				convert_command!(LocaleCommand),
+				convert_command!(OnboardingCommand),
+				convert_command!(VoiceCommand),
+			],
+		}
+	}
+
+	fn get_name(&self) -> String {
+		"admin".into()
+	}
+
+	fn get_description(&self) -> String {
+		"Estella Admin Commands".into()
+	}
+}
+
+impl SubCommand for AdminCommands {
+	fn get_sub_commands(&self) -> &Vec<CommonCommandType> {
+		&self.sub_commands
+	}
+}
@@ -0,0 +1,290 @@
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::is_guild_owner;
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use crate::utils::convert::utc_to_local_format;
+use entity::enums::AuditAction;
+use entity::unsigned::DbU64;
+use entity::{audit_log, AuditLog, AuditLogBehavior};
+use log::error;
+use sea_orm::{ActiveEnum, ColumnTrait, EntityTrait, Iterable, QueryFilter, QueryOrder, QuerySelect};
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags, UserId,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_ACTION: &str = "action";
+const PARAM_ACTOR: &str = "actor";
+const PARAM_TARGET: &str = "target";
+const PARAM_LIMIT: &str = "limit";
+
+const DEFAULT_LIMIT: u64 = 10;
+// Embedのフィールドの最大数
+const MAX_LIMIT: u64 = 25;
+// 1件が長くなりすぎないように詳細は切り詰める
+const DETAILS_MAX_CHARS: usize = 150;
+// Embed全体 (タイトル、フィールド、フッター) の文字数制限
+const EMBED_MAX_CHARS: usize = 6000;
+// 省略した件数を表示するフッターの分
+const FOOTER_MAX_CHARS: usize = 100;
+
+command_args! {
+	struct AuditLogArgs {
+		action: Option<String> = (PARAM_ACTION, "操作の種類", autocomplete),
+		actor: Option<UserId> = (PARAM_ACTOR, "操作したユーザー"),
+		target: Option<UserId> = (PARAM_TARGET, "操作されたユーザー"),
+		limit: Option<i64> = (PARAM_LIMIT, "表示件数 (最大25件)"),
+	}
+}
+
+pub struct AuditLogCommand;
+
+impl BaseCommand for AuditLogCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"audit-log".into()
+	}
+
+	fn get_description(&self) -> String {
+		"操作の記録を表示します".into()
+	}
+}
+
+impl AuditLogCommand {
+	fn format_user(id: Option<DbU64>, none_text: String) -> String {
+		id.map(|v| format!("<@{}>", v)).unwrap_or(none_text)
+	}
+
+	// インラインコードで囲むので、バッククォートは似た文字に置き換える
+	fn format_details(details: &str) -> String {
+		let details = details.replace('`', "ˋ");
+		if details.chars().count() > DETAILS_MAX_CHARS {
+			format!("{}…", details.chars().take(DETAILS_MAX_CHARS).collect::<String>())
+		} else {
+			details
+		}
+	}
+
+	// Embed全体の文字数制限に収まるところまでのフィールドを返す (残りは省略する)
+	fn fit_fields(title: &str, fields: Vec<(String, String)>) -> Vec<(String, String)> {
+		let mut used = title.chars().count() + FOOTER_MAX_CHARS;
+		fields
+			.into_iter()
+			.take_while(|(name, value)| {
+				used += name.chars().count() + value.chars().count();
+				used <= EMBED_MAX_CHARS
+			})
+			.collect()
+	}
+
+	fn make_embed(msg: &Messages, logs: Vec<AuditLog>) -> CreateEmbed {
+		let title = msg.get("audit_log.title");
+		let mut embed = CreateEmbed::new().title(&title).color(color::normal_color());
+		if logs.is_empty() {
+			return embed.description(msg.get("audit_log.empty"));
+		}
+
+		let count = logs.len();
+		let fields: Vec<(String, String)> = logs
+			.into_iter()
+			.map(|log| {
+				(
+					format!("#{} {} ({})", log.id, log.action, utc_to_local_format(&log.created_at)),
+					msg.format(
+						"audit_log.entry",
+						&[
+							("actor", Self::format_user(log.actor_id, msg.get("audit_log.system"))),
+							("target", Self::format_user(log.target_id, msg.get("common.none"))),
+							("details", format!("`{}`", Self::format_details(&log.details))),
+						],
+					),
+				)
+			})
+			.collect();
+
+		let fields = Self::fit_fields(&title, fields);
+		let omitted = count - fields.len();
+		embed = embed.fields(fields.into_iter().map(|(name, value)| (name, value, false)));
+		if omitted > 0 {
+			let footer = msg.format("audit_log.omitted", &[("count", omitted.to_string())]);
+			embed = embed.footer(CreateEmbedFooter::new(
+				footer.chars().take(FOOTER_MAX_CHARS).collect::<String>(),
+			));
+		}
+
+		embed
+	}
+
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(
+							CreateEmbed::new()
+								.title(msg.get("common.error.title"))
+								.description(message)
+								.color(color::failed_color()),
+						)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+}
+
+#[async_trait]
+impl Command for AuditLogCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		AuditLogArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.owner_only_command"))
+					.await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let args = match AuditLogArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+
+		let mut query = AuditLogBehavior::find().filter(audit_log::Column::GuildId.eq(guild_id.get()));
+		if let Some(action) = args.action {
+			match AuditAction::try_from_value(&action) {
+				Ok(action) => query = query.filter(audit_log::Column::Action.eq(action)),
+				Err(_) => {
+					return self
+						.send_error(&ctx, &command, &msg, msg.get("audit_log.select_action"))
+						.await
+				},
+			}
+		}
+		if let Some(actor) = args.actor {
+			query = query.filter(audit_log::Column::ActorId.eq(actor.get()));
+		}
+		if let Some(target) = args.target {
+			query = query.filter(audit_log::Column::TargetId.eq(target.get()));
+		}
+		let limit = args
+			.limit
+			.map(|v| v.clamp(1, MAX_LIMIT as i64) as u64)
+			.unwrap_or(DEFAULT_LIMIT);
+
+		let logs = query
+			.order_by_desc(audit_log::Column::Id)
+			.limit(limit)
+			.all(AppState::get(&ctx).await.db())
+			.await;
+		let logs = match logs {
+			Ok(v) => v,
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(Self::make_embed(&msg, logs))
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_ACTION, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+
+		let mut response = CreateAutocompleteResponse::new();
+		for action in AuditAction::iter() {
+			let name = action.to_string();
+			let value = action.to_value();
+			if name.contains(&input) || value.contains(&input) {
+				response = response.add_string_choice(format!("{} ({})", name, value), value);
+			}
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn escape_backticks_in_details() {
+		assert_eq!(
+			AuditLogCommand::format_details(r#"{"name":"`a`"}"#),
+			r#"{"name":"ˋaˋ"}"#
+		);
+	}
+
+	#[test]
+	fn fields_fit_in_embed() {
+		let fields: Vec<(String, String)> = (0..MAX_LIMIT)
+			.map(|i| (format!("#{} {}", i, "あ".repeat(200)), "い".repeat(1000)))
+			.collect();
+		let fitted = AuditLogCommand::fit_fields("監査ログ", fields);
+
+		let total: usize = fitted
+			.iter()
+			.map(|(name, value)| name.chars().count() + value.chars().count())
+			.sum();
+		assert_eq!(fitted.len(), 4);
+		assert!("監査ログ".chars().count() + total + FOOTER_MAX_CHARS <= EMBED_MAX_CHARS);
+	}
+}
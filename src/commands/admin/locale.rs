@@ -0,0 +1,221 @@
+use crate::audit_log;
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::is_guild_owner;
+use crate::i18n::{Locale, Messages};
+use crate::state::AppState;
+use crate::utils::color;
+use entity::enums::AuditAction;
+use log::error;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde_json::json;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_LOCALE: &str = "locale";
+
+// サーバーの優先ロケールに従う
+const LOCALE_AUTO: &str = "auto";
+
+command_args! {
+	struct LocaleArgs {
+		locale: String = (PARAM_LOCALE, "メッセージの言語", autocomplete),
+	}
+}
+
+pub struct LocaleCommand;
+
+impl BaseCommand for LocaleCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"locale".into()
+	}
+
+	fn get_description(&self) -> String {
+		"ログなどに送信するメッセージの言語を設定します".into()
+	}
+}
+
+impl LocaleCommand {
+	// 言語の名前はその言語で表示する
+	fn locale_name(msg: &Messages, locale: Option<Locale>) -> String {
+		match locale {
+			None => msg.get("locale.auto"),
+			Some(Locale::Ja) => "日本語".to_string(),
+			Some(Locale::En) => "English".to_string(),
+		}
+	}
+
+	async fn send_embed(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		embed: CreateEmbed,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(message)
+				.color(color::failed_color()),
+		)
+		.await
+	}
+}
+
+#[async_trait]
+impl Command for LocaleCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		LocaleArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.owner_only_command"))
+					.await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let args = match LocaleArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+		let locale = if args.locale == LOCALE_AUTO {
+			None
+		} else {
+			match Locale::from_code(&args.locale) {
+				Some(v) => Some(v),
+				None => {
+					return self
+						.send_error(&ctx, &command, &msg, msg.get("locale.select_locale"))
+						.await
+				},
+			}
+		};
+
+		let state = AppState::get(&ctx).await;
+		let guild_configs = state.guild_configs();
+		let guild_config = match guild_configs.find(guild_id.get()).await {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.guild_not_registered"))
+					.await;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+		let before = guild_config.locale.clone();
+		let mut update_entity = guild_config.into_active_model();
+		update_entity.locale = ActiveValue::Set(locale.map(|v| v.code().to_string()));
+		if let Err(error) = guild_configs.update(update_entity).await {
+			error!("DB Error: {:?}", error);
+			return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+		}
+
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(command.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "locale",
+				"before": before,
+				"after": locale.map(|v| v.code()),
+			}),
+		)
+		.await;
+
+		self.send_embed(
+			&ctx,
+			&command,
+			CreateEmbed::new()
+				.title(msg.get("common.done.title"))
+				.description(msg.format("locale.updated", &[("locale", Self::locale_name(&msg, locale))]))
+				.color(color::success_color()),
+		)
+		.await
+	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_LOCALE, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+
+		let msg = Messages::for_interaction(&ctx, command.guild_id, &command.locale).await;
+		let choices = std::iter::once((LOCALE_AUTO, Self::locale_name(&msg, None))).chain(
+			Locale::ALL
+				.into_iter()
+				.map(|v| (v.code(), Self::locale_name(&msg, Some(v)))),
+		);
+		let mut response = CreateAutocompleteResponse::new();
+		for (value, name) in choices {
+			if value.contains(&input) || name.contains(&input) {
+				response = response.add_string_choice(name, value);
+			}
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
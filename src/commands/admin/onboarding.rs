@@ -0,0 +1,117 @@
+use crate::command_define::{BaseCommand, Command};
+use crate::components::{is_guild_owner, onboarding_summary};
+use crate::i18n::Messages;
+use crate::onboarding::guild_roles;
+use crate::state::AppState;
+use crate::utils::color;
+use log::error;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+	InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+pub struct OnboardingCommand;
+
+impl BaseCommand for OnboardingCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"onboarding".into()
+	}
+
+	fn get_description(&self) -> String {
+		"入鯖後のロール付与やウェルカムメッセージを設定します".into()
+	}
+}
+
+impl OnboardingCommand {
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(
+							CreateEmbed::new()
+								.title(msg.get("common.error.title"))
+								.description(message)
+								.color(color::failed_color()),
+						)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+}
+
+#[async_trait]
+impl Command for OnboardingCommand {
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		_: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.owner_only_command"))
+					.await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let state = AppState::get(&ctx).await;
+		let guild_config = state.guild_configs().find(guild_id.get()).await;
+		let guild_config = match guild_config {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.guild_not_registered"))
+					.await
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+
+		let roles = guild_roles(&state, guild_id.get()).await;
+
+		// 各メニュー・ボタンの操作はcomponentsで処理する
+		let (embed, components) = onboarding_summary(&msg, &guild_config, &roles);
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.components(components)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+}
@@ -0,0 +1,149 @@
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::{is_guild_owner, template_modal};
+use crate::i18n::{self, Messages};
+use crate::utils::color;
+use log::error;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_KEY: &str = "key";
+
+// Discordのオートコンプリートで返せる候補の最大数
+const AUTOCOMPLETE_LIMIT: usize = 25;
+
+command_args! {
+	struct TemplateArgs {
+		key: String = (PARAM_KEY, "編集するメッセージ", autocomplete),
+	}
+}
+
+pub struct TemplateCommand;
+
+impl BaseCommand for TemplateCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"template".into()
+	}
+
+	fn get_description(&self) -> String {
+		"ログなどに送信するメッセージを編集します".into()
+	}
+}
+
+impl TemplateCommand {
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(
+							CreateEmbed::new()
+								.title(msg.get("common.error.title"))
+								.description(message)
+								.color(color::failed_color()),
+						)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+}
+
+#[async_trait]
+impl Command for TemplateCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		TemplateArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.owner_only_command"))
+					.await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let args = match TemplateArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+		if !i18n::keys().contains(&args.key.as_str()) {
+			return self
+				.send_error(&ctx, &command, &msg, msg.get("template.select_key"))
+				.await;
+		}
+
+		// 編集するのはサーバー全体に出すメッセージなので、サーバーの言語のテンプレートを使う
+		let guild_msg = Messages::for_guild(&ctx, guild_id, None).await;
+		let current = guild_msg.template(&args.key).unwrap_or_default();
+
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Modal(template_modal(&msg, &args.key, current)),
+			)
+			.await
+	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_KEY, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+
+		let mut response = CreateAutocompleteResponse::new();
+		for key in i18n::keys()
+			.into_iter()
+			.filter(|v| v.contains(&input))
+			.take(AUTOCOMPLETE_LIMIT)
+		{
+			response = response.add_string_choice(key, key);
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
@@ -0,0 +1,277 @@
+use crate::audit_log;
+use crate::command_args::{command_args, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::is_guild_owner;
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use crate::voice::addressing;
+use entity::enums::AuditAction;
+use entity::GuildConfig;
+use log::error;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde_json::{json, Value};
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+	CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_INTERJECTION_RATE: &str = "interjection_rate";
+const PARAM_WAKE_WORDS: &str = "wake_words";
+const PARAM_IDLE_TOPIC_SECONDS: &str = "idle_topic_seconds";
+
+// 話題を出すまでの秒数の上限 (1時間)
+const MAX_IDLE_TOPIC_SECONDS: i64 = 3600;
+
+// ウェイクワードを削除する
+const WAKE_WORDS_RESET: &str = "-";
+// Embedのfieldのvalueの最大文字数 (ウェイクワードは全体でこれに収まるようにする)
+const EMBED_FIELD_LIMIT: usize = 1024;
+
+command_args! {
+	struct VoiceArgs {
+		interjection_rate: Option<i64> = (PARAM_INTERJECTION_RATE, "複数人の時に、呼ばれていなくても話に入る確率 (0〜100%)"),
+		wake_words: Option<String> = (PARAM_WAKE_WORDS, "追加のウェイクワード (カンマ区切り、「-」で削除)"),
+		idle_topic_seconds: Option<i64> = (PARAM_IDLE_TOPIC_SECONDS, "誰も話していない時に話題を出すまでの秒数 (0で無効)"),
+	}
+}
+
+pub struct VoiceCommand;
+
+impl BaseCommand for VoiceCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"voice".into()
+	}
+
+	fn get_description(&self) -> String {
+		"ボイスチャンネルでの会話を設定します".into()
+	}
+}
+
+impl VoiceCommand {
+	async fn send_embed(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		embed: CreateEmbed,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(message)
+				.color(color::failed_color()),
+		)
+		.await
+	}
+
+	fn settings_embed(msg: &Messages, guild_config: &GuildConfig) -> CreateEmbed {
+		let idle_topic = match guild_config.voice_idle_topic_seconds {
+			0 => msg.get("common.disabled"),
+			v => msg.format("voice.seconds", &[("seconds", v.to_string())]),
+		};
+
+		CreateEmbed::new()
+			.field(
+				msg.get("voice.wake_words_field"),
+				Self::wake_words_text(guild_config.voice_wake_words.as_deref()),
+				false,
+			)
+			.field(
+				msg.get("voice.interjection_rate_field"),
+				msg.format(
+					"voice.percent",
+					&[("rate", guild_config.voice_interjection_rate.to_string())],
+				),
+				true,
+			)
+			.field(msg.get("voice.idle_topic_field"), idle_topic, true)
+	}
+
+	fn wake_words_text(wake_words: Option<&str>) -> String {
+		addressing::wake_words(wake_words).join(", ")
+	}
+
+	fn settings_json(guild_config: &GuildConfig) -> Value {
+		json!({
+			"wake_words": guild_config.voice_wake_words,
+			"interjection_rate": guild_config.voice_interjection_rate,
+			"idle_topic_seconds": guild_config.voice_idle_topic_seconds,
+		})
+	}
+}
+
+#[async_trait]
+impl Command for VoiceCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		VoiceArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.owner_only_command"))
+					.await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let args = match VoiceArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+		if args.interjection_rate.is_some_and(|v| !(0..=100).contains(&v)) {
+			return self
+				.send_error(&ctx, &command, &msg, msg.get("voice.interjection_rate_range"))
+				.await;
+		}
+		if args
+			.idle_topic_seconds
+			.is_some_and(|v| !(0..=MAX_IDLE_TOPIC_SECONDS).contains(&v))
+		{
+			return self
+				.send_error(
+					&ctx,
+					&command,
+					&msg,
+					msg.format("voice.idle_topic_range", &[("max", MAX_IDLE_TOPIC_SECONDS.to_string())]),
+				)
+				.await;
+		}
+
+		let state = AppState::get(&ctx).await;
+		let guild_configs = state.guild_configs();
+		let guild_config = match guild_configs.find(guild_id.get()).await {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.guild_not_registered"))
+					.await;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+
+		// 何も指定されていない場合は、現在の設定を表示する
+		if args.interjection_rate.is_none() && args.wake_words.is_none() && args.idle_topic_seconds.is_none() {
+			return self
+				.send_embed(
+					&ctx,
+					&command,
+					Self::settings_embed(&msg, &guild_config)
+						.title(msg.get("voice.title"))
+						.color(color::normal_color()),
+				)
+				.await;
+		}
+
+		let before = Self::settings_json(&guild_config);
+		let wake_words = match args.wake_words.as_deref().map(str::trim) {
+			Some(WAKE_WORDS_RESET) => None,
+			Some(v) => Some(addressing::parse_wake_words(v).join(",")).filter(|v| !v.is_empty()),
+			None => guild_config.voice_wake_words.clone(),
+		};
+		if Self::wake_words_text(wake_words.as_deref()).chars().count() > EMBED_FIELD_LIMIT {
+			return self
+				.send_error(
+					&ctx,
+					&command,
+					&msg,
+					msg.format("voice.wake_words_too_long", &[("max", EMBED_FIELD_LIMIT.to_string())]),
+				)
+				.await;
+		}
+		let interjection_rate = args
+			.interjection_rate
+			.map_or(guild_config.voice_interjection_rate, |v| v as i32);
+		let idle_topic_seconds = args
+			.idle_topic_seconds
+			.map_or(guild_config.voice_idle_topic_seconds, |v| v as i32);
+
+		let mut update_entity = guild_config.into_active_model();
+		update_entity.voice_wake_words = ActiveValue::Set(wake_words);
+		update_entity.voice_interjection_rate = ActiveValue::Set(interjection_rate);
+		update_entity.voice_idle_topic_seconds = ActiveValue::Set(idle_topic_seconds);
+		let guild_config = match guild_configs.update(update_entity).await {
+			Ok(v) => v,
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(command.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "voice",
+				"before": before,
+				"after": Self::settings_json(&guild_config),
+			}),
+		)
+		.await;
+
+		self.send_embed(
+			&ctx,
+			&command,
+			Self::settings_embed(&msg, &guild_config)
+				.title(msg.get("common.done.title"))
+				.description(msg.get("voice.updated"))
+				.color(color::success_color()),
+		)
+		.await
+	}
+}
@@ -1,4 +1,5 @@
 use crate::command_define::{BaseCommand, Command};
+use crate::i18n::Messages;
 use crate::utils::color;
 use crate::voice::disconnect_voice_channel;
 use serenity::all::{
@@ -29,8 +30,9 @@ impl DisconnectCommand {
 		&self,
 		ctx: &Context,
 		command: &CommandInteraction,
-		msg: String,
+		message: String,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.create_response(
 				&ctx,
@@ -38,8 +40,8 @@ impl DisconnectCommand {
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("エラー")
-									.description(msg)
+									.title(msg.get("common.error.title"))
+									.description(message)
 									.color(color::failed_color()),
 							)
 					),
@@ -52,6 +54,7 @@ impl DisconnectCommand {
 		ctx: &Context,
 		command: &CommandInteraction,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.create_response(
 				&ctx,
@@ -59,8 +62,8 @@ impl DisconnectCommand {
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("完了")
-									.description("VCから切断しました！")
+									.title(msg.get("common.done.title"))
+									.description(msg.get("disconnect.done.description"))
 									.color(color::success_color()),
 							)
 					),
@@ -1,4 +1,5 @@
 use crate::command_define::{BaseCommand, Command};
+use crate::i18n::Messages;
 use crate::utils::color;
 use chrono::{TimeDelta, Utc};
 use log::error;
@@ -48,13 +49,14 @@ impl PingCommand {
 	}
 
 	async fn send_result(&self, ctx: &Context, command: &CommandInteraction, ping: i64) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.edit_response(
 				&ctx.http,
 				EditInteractionResponse::new().add_embed(
 					CreateEmbed::new()
-						.title("Ping結果")
-						.description(format!("{}ms", ping))
+						.title(msg.get("ping.title"))
+						.description(msg.format("ping.description", &[("ping", ping.to_string())]))
 						.color(color::normal_color()),
 				),
 			)
@@ -1,4 +1,5 @@
 use crate::command_define::{BaseCommand, Command};
+use crate::i18n::Messages;
 use crate::utils::color;
 use crate::voice::connect_voice_channel;
 use serenity::all::{
@@ -69,6 +70,7 @@ impl TalkCommand {
 		ctx: &Context,
 		command: &CommandInteraction,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.create_response(
 				&ctx,
@@ -76,8 +78,8 @@ impl TalkCommand {
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("接続に失敗")
-									.description("あなたはどのVCにも接続していません。\nコマンドを使用するには、VCに接続してから実行してください。")
+									.title(msg.get("talk.failed.title"))
+									.description(msg.get("talk.not_connected.description"))
 									.color(color::failed_color()),
 							)
 					),
@@ -90,6 +92,7 @@ impl TalkCommand {
 		ctx: &Context,
 		command: &CommandInteraction,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.create_response(
 				&ctx,
@@ -97,8 +100,8 @@ impl TalkCommand {
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("接続に失敗")
-									.description("VCに接続しようとした際にエラーが発生し接続できませんでした。")
+									.title(msg.get("talk.failed.title"))
+									.description(msg.get("talk.error.description"))
 									.color(color::failed_color()),
 							)
 					),
@@ -111,6 +114,7 @@ impl TalkCommand {
 		ctx: &Context,
 		command: &CommandInteraction,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(ctx, command.guild_id, &command.locale).await;
 		command
 			.create_response(
 				&ctx,
@@ -118,8 +122,8 @@ impl TalkCommand {
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("接続完了")
-									.description("VCに接続しました！")
+									.title(msg.get("talk.connected.title"))
+									.description(msg.get("talk.connected.description"))
 									.color(color::success_color()),
 							)
 					),
@@ -1,12 +1,14 @@
 use find::FindCommand;
 use reserve::ReserveCommand;
 use sub_application::SubApplicationCommand;
+use verify::VerifyCommand;
 
 use crate::command_define::{BaseCommand, CommonCommandType, SubCommand};
 
 mod find;
 mod reserve;
 mod sub_application;
+mod verify;
 
 pub struct UserCommands {
 	sub_commands: Vec<CommonCommandType>,
@@ -19,6 +21,7 @@ impl BaseCommand for UserCommands {
 				convert_command!(ReserveCommand),
 				convert_command!(SubApplicationCommand),
 				convert_command!(FindCommand),
+				convert_command!(VerifyCommand),
 			],
 		}
 	}
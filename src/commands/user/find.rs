@@ -1,26 +1,34 @@
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
 use crate::command_define::{BaseCommand, Command};
+use crate::i18n::{account_type_key, Messages};
+use crate::state::AppState;
 use crate::utils::convert::{flatten_result_option, utc_to_local_format};
-use crate::utils::{color, convert};
-use crate::STATIC_COMPONENTS;
+use crate::utils::{color, convert, glacialeur};
 use entity::enums::AccountType;
-use entity::UserDataBehavior;
-use log::error;
-use sea_orm::EntityTrait;
+use log::{error, warn};
 use serenity::all::{
-	CommandDataOption, CommandInteraction, CommandOptionType, CreateEmbed, CreateInteractionResponse,
-	CreateInteractionResponseMessage, InteractionResponseFlags,
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, InteractionResponseFlags, UserId,
 };
 use serenity::async_trait;
 use serenity::client::Context;
-use std::num::ParseIntError;
 
-const PARAM_USERID: &str = "user_id";
+const PARAM_USER: &str = "user";
+const PARAM_ACCOUNT: &str = "account";
+const PARAM_GLACIALEUR: &str = "glacialeur";
 
-/*
-Paramsは値名→説明→型定義→必須で構成されています
-*/
-const PARAMS: [(&str, &str, CommandOptionType, bool); 1] =
-	[(PARAM_USERID, "ユーザーID", CommandOptionType::String, false)];
+// Discordのオートコンプリートで返せる候補の最大数
+const AUTOCOMPLETE_LIMIT: u64 = 25;
+// 候補の名前の最大文字数
+const AUTOCOMPLETE_NAME_LIMIT: usize = 100;
+
+command_args! {
+	struct FindArgs {
+		user: Option<UserId> = (PARAM_USER, "表示するユーザー"),
+		account: Option<String> = (PARAM_ACCOUNT, "登録名から検索 (Glacialeurも可)", autocomplete),
+		glacialeur: Option<String> = (PARAM_GLACIALEUR, "Glacialeurから検索"),
+	}
+}
 
 pub struct FindCommand;
 
@@ -40,8 +48,8 @@ impl BaseCommand for FindCommand {
 
 #[async_trait]
 impl Command for FindCommand {
-	fn args_param(&self) -> &'static [(&'static str, &'static str, CommandOptionType, bool)] {
-		&PARAMS
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		FindArgs::options()
 	}
 
 	async fn execute(
@@ -50,53 +58,74 @@ impl Command for FindCommand {
 		command: CommandInteraction,
 		args: Vec<CommandDataOption>,
 	) -> serenity::Result<()> {
-		let mut user_id_o: Option<String> = None;
-
-		for option in &args {
-			//info!("option data: {} [{:?}]", b.name, b.value);
-			match (&option.name).as_str() {
-				PARAM_USERID => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						user_id_o = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				_ => {},
-			}
-		}
-
+		let msg = Messages::for_interaction(&ctx, command.guild_id, &command.locale).await;
 		let mut error_message: Option<String> = None;
 
 		let mut user_id = command.user.id.get();
-		if user_id_o.is_none() {
-			error!("UserID is undefined.");
-		} else {
-			let user_id_r: Result<u64, ParseIntError> = user_id_o.unwrap().parse::<u64>();
-			if let Err(error) = user_id_r {
-				error!("user_id coundnt convert u64: {:?}", error);
-				error_message = Some("UserIDの記述が正しくありません".to_string());
-			} else {
-				user_id = user_id_r.unwrap();
-			}
+		match FindArgs::parse(&args) {
+			Ok(FindArgs { user: Some(user), .. }) => user_id = user.get(),
+			Ok(FindArgs {
+				user: None,
+				account: Some(account),
+				..
+			}) => match account.parse::<u64>() {
+				Ok(v) => user_id = v,
+				// 候補から選ばれていない場合はGlacialeurとして探す
+				Err(error) => match find_by_glacialeur(&ctx, &msg, &account).await {
+					Ok(Some(v)) => user_id = v,
+					Ok(None) => {
+						error!("account coundnt convert u64: {:?}", error);
+						error_message = Some(msg.get("find.select_account"));
+					},
+					Err(message) => error_message = Some(message),
+				},
+			},
+			Ok(FindArgs {
+				user: None,
+				account: None,
+				glacialeur: Some(glacialeur_id),
+			}) => match find_by_glacialeur(&ctx, &msg, &glacialeur_id).await {
+				Ok(Some(v)) => user_id = v,
+				Ok(None) => {
+					error_message = Some(msg.format(
+						"find.glacialeur_not_registered",
+						&[("glacialeur", glacialeur_id.trim().to_string())],
+					))
+				},
+				Err(message) => error_message = Some(message),
+			},
+			Ok(_) => {},
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				error_message = Some(error.to_string());
+			},
 		}
 
 		if error_message.is_none() {
 			let user_mem = command.guild_id.unwrap().member(&ctx.http, user_id).await;
 			if let Err(error) = user_mem {
 				error!("Error: {}", error);
-				error_message = Some(format!("IDが見つからないかその他のエラーです: {}", error));
+				error_message = Some(msg.format("find.not_found", &[("error", error.to_string())]));
 			} else {
 				let user_mem = user_mem.unwrap();
 
-				let lsc = STATIC_COMPONENTS.lock().await;
-				let locked_db = lsc.get_sql_client();
-				let user_data = flatten_result_option(UserDataBehavior::find_by_id(user_id).one(locked_db).await);
-				std::mem::drop(lsc);
+				let user_data = flatten_result_option(AppState::get(&ctx).await.user_data().find(user_id).await);
 				if let Err(error) = user_data {
 					error!("DB Error: {:?}", error);
-					error_message = Some(format!("IDが見つからないかその他のエラーです: {:?}", error));
+					error_message = Some(msg.format("find.not_found", &[("error", format!("{:?}", error))]));
 				} else {
 					let user_data = user_data.unwrap();
+					let guild_id = command.guild_id.unwrap();
+					let glacialeur_check = match &user_data.glacialeur {
+						Some(glacialeur_id) => {
+							Some(check_glacialeur(&ctx, &msg, guild_id, user_id, glacialeur_id).await)
+						},
+						None => None,
+					};
+					let embed_color = match glacialeur_check {
+						Some(Err(_)) => color::warning_color(),
+						_ => user_mem.user.accent_colour.unwrap_or(color::normal_color()),
+					};
 
 					return command
 						.create_response(
@@ -105,16 +134,20 @@ impl Command for FindCommand {
 								CreateInteractionResponseMessage::new()
 									.add_embed(
 										CreateEmbed::new()
-											.title("ユーザー情報")
-											.field("ID", user_data.uid.to_string(), true)
-											.field("名前", convert::format_discord_username(&user_mem.user), true)
+											.title(msg.get("find.title"))
+											.field(msg.get("field.id"), user_data.uid.to_string(), true)
 											.field(
-												"アカウント作成日",
+												msg.get("field.name"),
+												convert::format_discord_username(&user_mem.user),
+												true,
+											)
+											.field(
+												msg.get("find.created_at_field"),
 												utc_to_local_format(&user_mem.user.created_at()),
 												true,
 											)
 											.field(
-												"サーバー入鯖日",
+												msg.get("find.joined_at_field"),
 												utc_to_local_format(
 													&user_mem
 														.joined_at
@@ -123,22 +156,28 @@ impl Command for FindCommand {
 												true,
 											)
 											.field(
-												"アカウントタイプ",
-												(if user_data.glacialeur.is_none() {
-													AccountType::Sub
+												msg.get("field.account_type"),
+												msg.get(account_type_key(if user_data.glacialeur.is_none() {
+													&AccountType::Sub
 												} else {
-													AccountType::Main
-												})
-												.to_string(),
+													&AccountType::Main
+												})),
 												true,
 											)
 											.field(
-												"Glacialeur",
-												user_data.glacialeur.unwrap_or("なし".to_string()),
+												msg.get("find.glacialeur_field"),
+												user_data.glacialeur.unwrap_or_else(|| msg.get("common.none")),
 												true,
 											)
+											.fields(glacialeur_check.map(|v| {
+												(
+													msg.get("find.glacialeur_check_field"),
+													v.unwrap_or_else(|error| error),
+													false,
+												)
+											}))
 											.thumbnail(user_mem.user.avatar_url().unwrap_or("".to_string()))
-											.color(user_mem.user.accent_colour.unwrap_or(color::normal_color())),
+											.color(embed_color),
 									)
 									.flags(InteractionResponseFlags::EPHEMERAL),
 							),
@@ -155,7 +194,7 @@ impl Command for FindCommand {
 					CreateInteractionResponseMessage::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("エラー")
+								.title(msg.get("common.error.title"))
 								.description(error_message.unwrap())
 								.color(color::failed_color()),
 						)
@@ -164,4 +203,115 @@ impl Command for FindCommand {
 			)
 			.await
 	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_ACCOUNT, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+		let guild_id = match command.guild_id {
+			Some(v) => v.get(),
+			None => return Ok(()),
+		};
+
+		let state = AppState::get(&ctx).await;
+		let accounts = state.accounts();
+		let main_accounts = accounts
+			.search_mains(guild_id, &input, AUTOCOMPLETE_LIMIT)
+			.await
+			.unwrap_or_else(|error| {
+				error!("DB Error: {:?}", error);
+				vec![]
+			});
+		let sub_accounts = accounts
+			.search_subs(guild_id, None, &input, AUTOCOMPLETE_LIMIT)
+			.await
+			.unwrap_or_else(|error| {
+				error!("DB Error: {:?}", error);
+				vec![]
+			});
+
+		let accounts = main_accounts
+			.into_iter()
+			.map(|v| (v.uid, v.name, AccountType::Main))
+			.chain(sub_accounts.into_iter().map(|v| (v.uid, v.name, AccountType::Sub)))
+			.take(AUTOCOMPLETE_LIMIT as usize);
+
+		let mut response = CreateAutocompleteResponse::new();
+		for (uid, name, account_type) in accounts {
+			let choice_name: String = format!("{} ({}: {})", name, account_type, uid)
+				.chars()
+				.take(AUTOCOMPLETE_NAME_LIMIT)
+				.collect();
+			response = response.add_string_choice(choice_name, uid.to_string());
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
+
+// Glacialeurからユーザーを探す
+async fn find_by_glacialeur(ctx: &Context, msg: &Messages, glacialeur_id: &str) -> Result<Option<u64>, String> {
+	let glacialeur_id = glacialeur_id.trim().to_uppercase();
+	let user_data = AppState::get(ctx)
+		.await
+		.user_data()
+		.find_by_glacialeur(&glacialeur_id)
+		.await
+		.map_err(|error| {
+			error!("DB Error: {:?}", error);
+			msg.format("find.not_found", &[("error", format!("{:?}", error))])
+		})?;
+
+	match user_data.as_slice() {
+		[] => Ok(None),
+		[user_data] => Ok(Some(user_data.uid.get())),
+		_ => {
+			let uids = user_data.iter().map(|v| v.uid.to_string()).collect::<Vec<_>>();
+			warn!("Glacialeur {} is duplicated: {}", glacialeur_id, uids.join(", "));
+			Err(msg.format(
+				"find.glacialeur_duplicated",
+				&[("glacialeur", glacialeur_id), ("user_ids", uids.join(", "))],
+			))
+		},
+	}
+}
+
+// 保存されているGlacialeurが、メインアカウントの入鯖日とバージョンから作られたものか確認する
+// Errの場合は改ざんかデータ破損の可能性がある
+async fn check_glacialeur(
+	ctx: &Context,
+	msg: &Messages,
+	guild_id: GuildId,
+	user_id: u64,
+	glacialeur_id: &str,
+) -> Result<String, String> {
+	let main_account = match AppState::get(ctx).await.accounts().main(guild_id.get(), user_id).await {
+		Ok(Some(v)) => v,
+		Ok(None) => return Ok(msg.get("find.check.no_main")),
+		Err(error) => {
+			error!("DB Error: {:?}", error);
+			return Ok(msg.format("find.check.db_error", &[("error", format!("{:?}", error))]));
+		},
+	};
+
+	match glacialeur::verify(
+		glacialeur_id,
+		user_id,
+		main_account.version.get(),
+		main_account.join_date.timestamp() - guild_id.created_at().timestamp(),
+	) {
+		Ok(_) => Ok(msg.get("find.check.ok")),
+		Err(error) => {
+			warn!("Glacialeur check failed: {} ({}): {}", glacialeur_id, user_id, error);
+			Err(msg.format("find.check.failed", &[("error", error.to_string())]))
+		},
+	}
 }
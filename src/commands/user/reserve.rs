@@ -1,40 +1,35 @@
+use crate::command_args::{command_args, CommandArgs};
 use crate::command_define::{BaseCommand, Command};
+use crate::components::{make_custom_id, RejectVotePayload};
 use crate::events::ready_event::ADD_PENDING_USERS;
+use crate::i18n::Messages;
+use crate::state::AppState;
 use crate::utils::convert::flatten_result_option;
 use crate::utils::{color, convert};
-use crate::STATIC_COMPONENTS;
 use chrono::{Duration, Utc};
 use entity::enums::AccountType;
-use entity::{
-	confirmed_account, main_account, pending_account, sub_account, ConfirmedAccountBehavior,
-	GuildConfigBehavior, MainAccountBehavior, PendingAccount, PendingAccountBehavior, SubAccountBehavior,
-};
+use entity::PendingAccount;
 use log::error;
-use sea_orm::ActiveModelTrait;
-use sea_orm::ColumnTrait;
-use sea_orm::{EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter};
 use serenity::all::{
-	ButtonStyle, CommandDataOption, CommandInteraction, CommandOptionType, CreateActionRow, CreateButton, CreateEmbed,
+	ButtonStyle, CommandDataOption, CommandInteraction, CreateActionRow, CreateButton, CreateCommandOption, CreateEmbed,
 	CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse,
-	InteractionResponseFlags,
+	InteractionResponseFlags, UserId,
 };
 use serenity::async_trait;
 use serenity::client::Context;
 use serenity::model::id::ChannelId;
-use std::num::ParseIntError;
 
-const PARAM_USERID: &str = "user_id";
+const PARAM_USER: &str = "user";
 const PARAM_NAME: &str = "name";
 const PARAM_REASON: &str = "reason";
 
-/*
-Paramsは値名→説明→型定義→必須で構成されています
-*/
-const PARAMS: [(&str, &str, CommandOptionType, bool); 3] = [
-	(PARAM_USERID, "ユーザーID", CommandOptionType::String, true),
-	(PARAM_NAME, "登録名", CommandOptionType::String, true),
-	(PARAM_REASON, "登録理由", CommandOptionType::String, false),
-];
+command_args! {
+	struct ReserveArgs {
+		user: UserId = (PARAM_USER, "登録するユーザー"),
+		name: String = (PARAM_NAME, "登録名"),
+		reason: Option<String> = (PARAM_REASON, "登録理由"),
+	}
+}
 
 pub struct ReserveCommand;
 
@@ -54,8 +49,8 @@ impl BaseCommand for ReserveCommand {
 
 #[async_trait]
 impl Command for ReserveCommand {
-	fn args_param(&self) -> &'static [(&'static str, &'static str, CommandOptionType, bool)] {
-		&PARAMS
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		ReserveArgs::options()
 	}
 
 	async fn execute(
@@ -64,72 +59,37 @@ impl Command for ReserveCommand {
 		command: CommandInteraction,
 		args: Vec<CommandDataOption>,
 	) -> serenity::Result<()> {
-		let mut user_id: Option<String> = None;
-		let mut name: Option<String> = None;
-		let mut reason: Option<String> = None;
-
-		for option in &args {
-			//info!("option data: {} [{:?}]", b.name, b.value);
-			match (&option.name).as_str() {
-				PARAM_USERID => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						user_id = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				PARAM_NAME => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						name = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				PARAM_REASON => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						reason = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				_ => {},
-			}
-		}
-
-		let mut error_message: Option<String> = None;
-
-		if user_id.is_none() {
-			error!("UserID is undefined.");
-			error_message = Some("UserIDが入力されていません".to_string());
-		} else if name.is_none() {
-			error!("Name is undefined.");
-			error_message = Some("Nameが入力されていません".to_string());
-		}
-
-		let mut user_id_r: Option<Result<u64, ParseIntError>> = None;
-		if error_message.is_none() {
-			user_id_r = Some(user_id.unwrap().parse::<u64>());
-			if let Err(error) = user_id_r.as_ref().unwrap() {
-				error!("user_id coundnt convert u64: {:?}", error);
-				error_message = Some(format!("UserIDの記述が正しくありません: {:?}", error).to_string());
-			}
-		}
+		// ログチャンネルにはサーバーの言語、本人への返信はインタラクションの言語で送る
+		let log_msg = match command.guild_id {
+			Some(guild_id) => Messages::for_guild(&ctx, guild_id, None).await,
+			None => Messages::default(),
+		};
+		let msg = log_msg.with_locale(&command.locale);
 
-		if let Some(err_msg_f) = error_message {
-			return command
-				.create_response(
-					&ctx.http,
-					CreateInteractionResponse::Message(
-						CreateInteractionResponseMessage::new().add_embed(
-							CreateEmbed::new()
-								.title("エラー")
-								.description(err_msg_f)
-								.color(color::failed_color()),
+		let args = match ReserveArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return command
+					.create_response(
+						&ctx.http,
+						CreateInteractionResponse::Message(
+							CreateInteractionResponseMessage::new().add_embed(
+								CreateEmbed::new()
+									.title(msg.get("common.error.title"))
+									.description(error.to_string())
+									.color(color::failed_color()),
+							),
 						),
-					),
-				)
-				.await;
-		}
+					)
+					.await;
+			},
+		};
 
-		let user_id: u64 = user_id_r.unwrap().unwrap();
-		let name: String = name.unwrap();
+		let user_id: u64 = args.user.get();
+		let name: String = args.name;
+		let reason: Option<String> = args.reason;
+		let mut error_message: Option<String> = None;
 
 		command
 			.create_response(
@@ -138,49 +98,30 @@ impl Command for ReserveCommand {
 					CreateInteractionResponseMessage::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("処理中...")
-								.description("このメッセージを削除しないでください")
+								.title(msg.get("common.processing.title"))
+								.description(msg.get("common.processing.description"))
 								.color(color::normal_color()),
 						)
 						.flags(InteractionResponseFlags::EPHEMERAL),
 				),
 			)
 			.await?;
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let locked_db = lsc.get_sql_client();
-		let check_user = MainAccountBehavior::find()
-			.filter(main_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-			.filter(main_account::Column::Uid.eq(user_id))
-			.count(locked_db)
+		let state = AppState::get(&ctx).await;
+		let check_user = state
+			.accounts()
+			.is_registered(command.guild_id.unwrap().get(), user_id)
 			.await
-			.unwrap_or(0) +
-			SubAccountBehavior::find()
-				.filter(sub_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(sub_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) +
-			ConfirmedAccountBehavior::find()
-				.filter(confirmed_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(confirmed_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) +
-			PendingAccountBehavior::find()
-				.filter(pending_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(pending_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) >
-			0;
-		std::mem::drop(lsc);
+			.unwrap_or_else(|error| {
+				error!("DB Error: {:?}", error);
+				false
+			});
 		if check_user {
 			command
 				.edit_response(
 					&ctx.http,
 					EditInteractionResponse::new().embeds(vec![CreateEmbed::new()
-						.title("エラー")
-						.description("すでに申請されているか登録されています")
+						.title(msg.get("common.error.title"))
+						.description(msg.get("reserve.already_registered"))
 						.color(color::failed_color())]),
 				)
 				.await?;
@@ -192,18 +133,18 @@ impl Command for ReserveCommand {
 				&ctx.http,
 				EditInteractionResponse::new()
 					.embeds(vec![CreateEmbed::new()
-						.title("確認")
-						.description("以下の内容で登録します")
-						.field("ユーザーID", user_id.to_string(), true)
-						.field("名前", &name, true)
+						.title(msg.get("common.confirm.title"))
+						.description(msg.get("reserve.confirm.description"))
+						.field(msg.get("field.user_id"), user_id.to_string(), true)
+						.field(msg.get("field.name"), &name, true)
 						.color(color::normal_color())])
 					.components(vec![CreateActionRow::Buttons(vec![
 						CreateButton::new(format!("ok_{}", &user_id))
 							.style(ButtonStyle::Success)
-							.label("OK"),
+							.label(msg.get("common.button.ok")),
 						CreateButton::new(format!("cancel_{}", &user_id))
 							.style(ButtonStyle::Danger)
-							.label("キャンセル"),
+							.label(msg.get("common.button.cancel")),
 					])]),
 			)
 			.await?;
@@ -234,61 +175,58 @@ impl Command for ReserveCommand {
 					EditInteractionResponse::new()
 						.components(vec![])
 						.embeds(vec![CreateEmbed::new()
-							.title("処理中...")
-							.description("このメッセージを削除しないでください")
+							.title(msg.get("common.processing.title"))
+							.description(msg.get("common.processing.description"))
 							.color(color::normal_color())]),
 				)
 				.await?;
 
-			let lsc = STATIC_COMPONENTS.lock().await;
-			let locked_db = lsc.get_sql_client();
 			if command.guild_id.is_none() {
 				error!("Should not occur error: GuildID is none");
 				return Ok(());
 			}
-			let guild_config = match flatten_result_option(
-				GuildConfigBehavior::find_by_id(command.guild_id.unwrap().get())
-					.one(locked_db)
-					.await,
-			) {
-				Ok(x) => Some(x),
-				Err(e) => {
-					error!("DB Error: {:?}", e);
-					error_message = Some(format!("{:?}", e));
-					None
-				},
-			};
+			let guild_config =
+				match flatten_result_option(state.guild_configs().find(command.guild_id.unwrap().get()).await) {
+					Ok(x) => Some(x),
+					Err(e) => {
+						error!("DB Error: {:?}", e);
+						error_message = Some(format!("{:?}", e));
+						None
+					},
+				};
 			if let Some(guild_config) = guild_config {
 				if let Some(guild_log_channel) = guild_config.log_channel_id {
 					let end_vote_time = Utc::now() + Duration::days(7);
-					let log_channel = ChannelId::new(guild_log_channel);
+					let log_channel = ChannelId::new(guild_log_channel.get());
+					let reject_id = make_custom_id(&RejectVotePayload(user_id));
+					let request_args = [("user_id", user_id.to_string()), ("name", name.clone())];
 					let vote_message = log_channel.send_message(&ctx.http,
 						CreateMessage::new()
 							.add_embed({
 								let e = CreateEmbed::new()
-									.title("追加申請")
-									.description("以下の内容で登録申請されました。内容を見てこのサーバーに入れたくないと判断した場合は「却下」ボタンを押してください")
-									.field("ユーザーID", user_id.to_string(), true)
-									.field("名前", &name, true)
-									.field("申請却下終了時刻", convert::utc_to_local_format(&end_vote_time), true)
+									.title(log_msg.format("reserve.request.title", &request_args))
+									.description(log_msg.format("reserve.request.description", &request_args))
+									.field(log_msg.get("field.user_id"), user_id.to_string(), true)
+									.field(log_msg.get("field.name"), &name, true)
+									.field(log_msg.get("reserve.request.end_field"), convert::utc_to_local_format(&end_vote_time), true)
 									.color(color::normal_color());
 								if let Some(reason) = reason {
-									e.field("申請理由", reason, true)
+									e.field(log_msg.get("reserve.request.reason_field"), reason, true)
 								} else {
 									e
 								}
 							})
 							.components(vec![
 								CreateActionRow::Buttons(vec![
-									CreateButton::new(format!("reject_{}", &user_id)).style(ButtonStyle::Danger).label("申請を却下する")
+									CreateButton::new(reject_id).style(ButtonStyle::Danger).label(log_msg.get("reserve.request.reject_button"))
 								])
 							])
 					).await?;
 
 					let pending_data = PendingAccount {
-						uid: user_id,
+						uid: user_id.into(),
 						name: Some(name.clone()),
-						message_id: vote_message.id.get(),
+						message_id: vote_message.id.get().into(),
 						end_voting: Some(Utc::now() + Duration::days(7) /*Duration::seconds(30)*/),
 						guild_id: guild_config.uid,
 						account_type: AccountType::Main,
@@ -296,7 +234,7 @@ impl Command for ReserveCommand {
 						first_cert: None,
 					};
 
-					let pending_data = pending_data.into_active_model().insert(locked_db).await;
+					let pending_data = state.accounts().insert_pending(pending_data).await;
 					if let Err(error) = pending_data {
 						error!("DB Error: {:?}", error);
 						error_message = Some(format!("{:?}", error));
@@ -307,27 +245,25 @@ impl Command for ReserveCommand {
 					}
 				} else {
 					error!("Error: Not found log channel");
-					error_message = Some(String::from("ログチャンネルが指定されていません"));
+					error_message = Some(msg.get("common.log_channel_not_set"));
 				}
 			}
 
-			std::mem::drop(lsc);
-
 			button_interaction
 				.edit_response(
 					&ctx.http,
 					EditInteractionResponse::new().components(vec![]).embeds(vec![
 						if let Some(err_msg) = error_message {
 							CreateEmbed::new()
-								.title("エラー")
+								.title(msg.get("common.error.title"))
 								.description(err_msg)
 								.color(color::failed_color())
 						} else {
 							CreateEmbed::new()
-								.title("完了")
-								.description("以下の内容で登録しました！7日間何も無ければ正式に登録されます")
-								.field("ユーザーID", user_id.to_string(), true)
-								.field("名前", &name, true)
+								.title(msg.get("common.done.title"))
+								.description(msg.format("reserve.done.description", &[("days", "7".to_string())]))
+								.field(msg.get("field.user_id"), user_id.to_string(), true)
+								.field(msg.get("field.name"), &name, true)
 								.color(color::success_color())
 						},
 					]),
@@ -346,8 +282,8 @@ impl Command for ReserveCommand {
 						CreateInteractionResponseMessage::new()
 							.components(vec![])
 							.embeds(vec![CreateEmbed::new()
-								.title("キャンセル")
-								.description("処理を取り消しました")
+								.title(msg.get("common.cancel.title"))
+								.description(msg.get("common.cancel.description"))
 								.color(color::normal_color())]),
 					),
 				)
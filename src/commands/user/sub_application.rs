@@ -1,37 +1,36 @@
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
 use crate::command_define::{BaseCommand, Command};
+use crate::components::{make_custom_id, ConfirmPayload};
+use crate::i18n::Messages;
+use crate::state::AppState;
 use crate::utils::color;
 use crate::utils::convert::flatten_result_option;
-use crate::STATIC_COMPONENTS;
 use entity::enums::AccountType;
-use entity::{
-	confirmed_account, main_account, pending_account, sub_account, ConfirmedAccountBehavior, GuildConfigBehavior,
-	MainAccountBehavior, PendingAccount, PendingAccountBehavior, SubAccountBehavior,
-};
+use entity::PendingAccount;
 use log::error;
-use sea_orm::ActiveModelTrait;
-use sea_orm::ColumnTrait;
-use sea_orm::PaginatorTrait;
-use sea_orm::{EntityTrait, IntoActiveModel, QueryFilter};
 use serenity::all::{
-	ButtonStyle, CommandDataOption, CommandInteraction, CommandOptionType, CreateActionRow, CreateButton, CreateEmbed,
-	CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse,
-	InteractionResponseFlags,
+	ButtonStyle, CommandDataOption, CommandDataOptionValue, CommandInteraction, CreateActionRow,
+	CreateAutocompleteResponse, CreateButton, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+	CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, InteractionResponseFlags, UserId,
 };
 use serenity::async_trait;
 use serenity::client::Context;
 use serenity::model::id::ChannelId;
-use std::num::ParseIntError;
 
-const PARAM_USERID: &str = "user_id";
+const PARAM_USER: &str = "user";
 const PARAM_NAME: &str = "name";
 
-/*
-Paramsは値名→説明→型定義→必須で構成されています
-*/
-const PARAMS: [(&str, &str, CommandOptionType, bool); 2] = [
-	(PARAM_USERID, "ユーザーID", CommandOptionType::String, true),
-	(PARAM_NAME, "登録名", CommandOptionType::String, true),
-];
+command_args! {
+	struct SubApplicationArgs {
+		user: UserId = (PARAM_USER, "申請するサブアカウント"),
+		name: String = (PARAM_NAME, "登録名", autocomplete),
+	}
+}
+
+// Discordのオートコンプリートで返せる候補の最大数
+const AUTOCOMPLETE_LIMIT: usize = 25;
+// 候補の名前と値の最大文字数
+const AUTOCOMPLETE_NAME_LIMIT: usize = 100;
 
 pub struct SubApplicationCommand;
 
@@ -51,8 +50,8 @@ impl BaseCommand for SubApplicationCommand {
 
 #[async_trait]
 impl Command for SubApplicationCommand {
-	fn args_param(&self) -> &'static [(&'static str, &'static str, CommandOptionType, bool)] {
-		&PARAMS
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		SubApplicationArgs::options()
 	}
 
 	async fn execute(
@@ -61,65 +60,36 @@ impl Command for SubApplicationCommand {
 		command: CommandInteraction,
 		args: Vec<CommandDataOption>,
 	) -> serenity::Result<()> {
-		let mut user_id: Option<String> = None;
-		let mut name: Option<String> = None;
-
-		for option in &args {
-			//info!("option data: {} [{:?}]", b.name, b.value);
-			match (&option.name).as_str() {
-				PARAM_USERID => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						user_id = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				PARAM_NAME => {
-					let option_value = &option.value;
-					if matches!(option_value.kind(), CommandOptionType::String) {
-						name = Some(option_value.as_str().unwrap_or_else(|| "").to_string());
-					}
-				},
-				_ => {},
-			}
-		}
+		// ログチャンネルにはサーバーの言語、本人への返信はインタラクションの言語で送る
+		let log_msg = match command.guild_id {
+			Some(guild_id) => Messages::for_guild(&ctx, guild_id, None).await,
+			None => Messages::default(),
+		};
+		let msg = log_msg.with_locale(&command.locale);
 
-		let mut error_message: Option<String> = None;
-
-		if user_id.is_none() {
-			error!("UserID is undefined.");
-			error_message = Some("UserIDが入力されていません".to_string());
-		} else if name.is_none() {
-			error!("Name is undefined.");
-			error_message = Some("Nameが入力されていません".to_string());
-		}
-
-		let mut user_id_r: Option<Result<u64, ParseIntError>> = None;
-		if error_message.is_none() {
-			user_id_r = Some(user_id.unwrap().parse::<u64>());
-			if let Err(error) = user_id_r.as_ref().unwrap() {
-				error!("user_id coundnt convert u64: {:?}", error);
-				error_message = Some(format!("UserIDの記述が正しくありません: {:?}", error).to_string());
-			}
-		}
-
-		if let Some(err_msg_f) = error_message {
-			return command
-				.create_response(
-					&ctx.http,
-					CreateInteractionResponse::Message(
-						CreateInteractionResponseMessage::new().add_embed(
-							CreateEmbed::new()
-								.title("エラー")
-								.description(err_msg_f)
-								.color(color::failed_color()),
+		let args = match SubApplicationArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return command
+					.create_response(
+						&ctx.http,
+						CreateInteractionResponse::Message(
+							CreateInteractionResponseMessage::new().add_embed(
+								CreateEmbed::new()
+									.title(msg.get("common.error.title"))
+									.description(error.to_string())
+									.color(color::failed_color()),
+							),
 						),
-					),
-				)
-				.await;
-		}
+					)
+					.await;
+			},
+		};
 
-		let user_id: u64 = user_id_r.unwrap().unwrap();
-		let name: String = name.unwrap();
+		let user_id: u64 = args.user.get();
+		let name: String = args.name;
+		let mut error_message: Option<String> = None;
 
 		command
 			.create_response(
@@ -128,8 +98,8 @@ impl Command for SubApplicationCommand {
 					CreateInteractionResponseMessage::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("処理中...")
-								.description("このメッセージを削除しないでください")
+								.title(msg.get("common.processing.title"))
+								.description(msg.get("common.processing.description"))
 								.color(color::normal_color()),
 						)
 						.flags(InteractionResponseFlags::EPHEMERAL),
@@ -137,41 +107,22 @@ impl Command for SubApplicationCommand {
 			)
 			.await?;
 
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let locked_db = lsc.get_sql_client();
-		let check_user = MainAccountBehavior::find()
-			.filter(main_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-			.filter(main_account::Column::Uid.eq(user_id))
-			.count(locked_db)
+		let state = AppState::get(&ctx).await;
+		let check_user = state
+			.accounts()
+			.is_registered(command.guild_id.unwrap().get(), user_id)
 			.await
-			.unwrap_or(0) +
-			SubAccountBehavior::find()
-				.filter(sub_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(sub_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) +
-			ConfirmedAccountBehavior::find()
-				.filter(confirmed_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(confirmed_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) +
-			PendingAccountBehavior::find()
-				.filter(pending_account::Column::GuildId.eq(command.guild_id.unwrap().get()))
-				.filter(pending_account::Column::Uid.eq(user_id))
-				.count(locked_db)
-				.await
-				.unwrap_or(0) >
-			0;
-		std::mem::drop(lsc);
+			.unwrap_or_else(|error| {
+				error!("DB Error: {:?}", error);
+				false
+			});
 		if check_user {
 			command
 				.edit_response(
 					&ctx.http,
 					EditInteractionResponse::new().embeds(vec![CreateEmbed::new()
-						.title("エラー")
-						.description("すでに申請されているか登録されています")
+						.title(msg.get("common.error.title"))
+						.description(msg.get("reserve.already_registered"))
 						.color(color::failed_color())]),
 				)
 				.await?;
@@ -183,18 +134,18 @@ impl Command for SubApplicationCommand {
 				&ctx.http,
 				EditInteractionResponse::new()
 					.embeds(vec![CreateEmbed::new()
-						.title("確認")
-						.description("以下の内容で申請します")
-						.field("ユーザーID", user_id.to_string(), true)
-						.field("名前", &name, true)
+						.title(msg.get("common.confirm.title"))
+						.description(msg.get("sub_application.confirm.description"))
+						.field(msg.get("field.user_id"), user_id.to_string(), true)
+						.field(msg.get("field.name"), &name, true)
 						.color(color::normal_color())])
 					.components(vec![CreateActionRow::Buttons(vec![
 						CreateButton::new(format!("ok_{}", &user_id))
 							.style(ButtonStyle::Success)
-							.label("OK"),
+							.label(msg.get("common.button.ok")),
 						CreateButton::new(format!("cancel_{}", &user_id))
 							.style(ButtonStyle::Danger)
-							.label("キャンセル"),
+							.label(msg.get("common.button.cancel")),
 					])]),
 			)
 			.await?;
@@ -222,88 +173,83 @@ impl Command for SubApplicationCommand {
 					EditInteractionResponse::new()
 						.components(vec![])
 						.embeds(vec![CreateEmbed::new()
-							.title("処理中...")
-							.description("このメッセージを削除しないでください")
+							.title(msg.get("common.processing.title"))
+							.description(msg.get("common.processing.description"))
 							.color(color::normal_color())]),
 				)
 				.await?;
 
-			let lsc = STATIC_COMPONENTS.lock().await;
-			let locked_db = lsc.get_sql_client();
 			if command.guild_id.is_none() {
 				error!("Should not occur error: GuildID is none");
 				return Ok(());
 			}
-			let guild_config = match flatten_result_option(
-				GuildConfigBehavior::find_by_id(command.guild_id.unwrap().get())
-					.one(locked_db)
-					.await,
-			) {
-				Ok(x) => Some(x),
-				Err(e) => {
-					error!("DB Error: {:?}", e);
-					error_message = Some(format!("{:?}", e));
-					None
-				},
-			};
+			let guild_config =
+				match flatten_result_option(state.guild_configs().find(command.guild_id.unwrap().get()).await) {
+					Ok(x) => Some(x),
+					Err(e) => {
+						error!("DB Error: {:?}", e);
+						error_message = Some(format!("{:?}", e));
+						None
+					},
+				};
 			if let Some(guild_config) = guild_config {
 				if let Some(guild_log_channel) = guild_config.log_channel_id {
-					let log_channel = ChannelId::new(guild_log_channel);
+					let log_channel = ChannelId::new(guild_log_channel.get());
+					let conf_id = make_custom_id(&ConfirmPayload(command.user.id.get(), user_id));
+					let request_args = [("user_id", user_id.to_string()), ("name", name.clone())];
 					let conf_message = log_channel.send_message(&ctx.http,
 						CreateMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("追加申請")
-									.description("以下の内容で登録申請されました。入れていても問題ない場合は承認ボタンを押してください！")
-									.field("ユーザーID", user_id.to_string(), true)
-									.field("名前", &name, true)
+									.title(log_msg.format("sub_application.request.title", &request_args))
+									.description(log_msg.format("sub_application.request.description", &request_args))
+									.field(log_msg.get("field.user_id"), user_id.to_string(), true)
+									.field(log_msg.get("field.name"), &name, true)
 									.color(color::normal_color())
 							)
 							.components(vec![
 								CreateActionRow::Buttons(vec![
-									CreateButton::new(format!("conf_{}_{}", command.user.id.get(), &user_id)).style(ButtonStyle::Success).label("承認する")
+									CreateButton::new(conf_id).style(ButtonStyle::Success).label(log_msg.get("sub_application.request.confirm_button"))
 								])
 							])
 					).await?;
 
 					let pending_data = PendingAccount {
-						uid: user_id,
+						uid: user_id.into(),
 						name: Some(name.clone()),
-						message_id: conf_message.id.get(),
+						message_id: conf_message.id.get().into(),
 						end_voting: None,
 						guild_id: guild_config.uid,
 						account_type: AccountType::Sub,
-						main_uid: Some(command.user.id.get()),
+						main_uid: Some(command.user.id.get().into()),
 						first_cert: None,
 					};
 
-					if let Err(error) = pending_data.into_active_model().insert(locked_db).await {
+					if let Err(error) = state.accounts().insert_pending(pending_data).await {
 						error!("DB Error: {:?}", error);
 						error_message = Some(format!("{:?}", error));
 					}
 				} else {
 					error!("Error: Not found log channel");
-					error_message = Some(String::from("ログチャンネルが指定されていません"));
+					error_message = Some(msg.get("common.log_channel_not_set"));
 				}
 			}
 
-			std::mem::drop(lsc);
-
 			button_interaction
 				.edit_response(
 					&ctx.http,
 					EditInteractionResponse::new().components(vec![]).embeds(vec![
 						if let Some(err_msg) = error_message {
 							CreateEmbed::new()
-								.title("エラー")
+								.title(msg.get("common.error.title"))
 								.description(err_msg)
 								.color(color::failed_color())
 						} else {
 							CreateEmbed::new()
-								.title("完了")
-								.description("以下の内容で登録しました！最大2人の承認が必要になります")
-								.field("ユーザーID", user_id.to_string(), true)
-								.field("名前", &name, true)
+								.title(msg.get("common.done.title"))
+								.description(msg.get("sub_application.done.description"))
+								.field(msg.get("field.user_id"), user_id.to_string(), true)
+								.field(msg.get("field.name"), &name, true)
 								.color(color::success_color())
 						},
 					]),
@@ -322,8 +268,8 @@ impl Command for SubApplicationCommand {
 						CreateInteractionResponseMessage::new()
 							.components(vec![])
 							.embeds(vec![CreateEmbed::new()
-								.title("キャンセル")
-								.description("処理を取り消しました")
+								.title(msg.get("common.cancel.title"))
+								.description(msg.get("common.cancel.description"))
 								.color(color::normal_color())]),
 					),
 				)
@@ -333,4 +279,56 @@ impl Command for SubApplicationCommand {
 
 		Ok(())
 	}
+
+	// 申請するユーザーのサーバーでの名前・表示名・ユーザー名を登録名の候補に出す
+	// (すでに登録されているサブアカウントの名前は新しい申請には使わないので出さない)
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_NAME, value)) => value.to_lowercase(),
+			_ => return Ok(()),
+		};
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => return Ok(()),
+		};
+		// userがまだ入力されていない場合は候補なし
+		let user_id = args.iter().find_map(|v| match (v.name.as_str(), &v.value) {
+			(PARAM_USER, CommandDataOptionValue::User(user_id)) => Some(*user_id),
+			_ => None,
+		});
+
+		let mut names: Vec<String> = Vec::new();
+		if let Some(user_id) = user_id {
+			match guild_id.member(&ctx, user_id).await {
+				Ok(member) => {
+					let candidates = [
+						member.nick.clone(),
+						member.user.global_name.clone(),
+						Some(member.user.name.clone()),
+					];
+					for name in candidates.into_iter().flatten() {
+						if name.to_lowercase().contains(&input) && !names.contains(&name) {
+							names.push(name);
+						}
+					}
+				},
+				Err(error) => error!("Member fetch error: {:?}", error),
+			}
+		}
+
+		let mut response = CreateAutocompleteResponse::new();
+		for name in names.into_iter().take(AUTOCOMPLETE_LIMIT) {
+			let name: String = name.chars().take(AUTOCOMPLETE_NAME_LIMIT).collect();
+			response = response.add_string_choice(name.clone(), name);
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
 }
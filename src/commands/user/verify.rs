@@ -0,0 +1,139 @@
+use crate::command_args::{command_args, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use crate::utils::convert::utc_to_local_format;
+use crate::utils::glacialeur;
+use chrono::DateTime;
+use log::error;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+	CreateInteractionResponseMessage, InteractionResponseFlags, UserId,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_GLACIALEUR: &str = "glacialeur";
+const PARAM_USER: &str = "user";
+
+command_args! {
+	struct VerifyArgs {
+		glacialeur: String = (PARAM_GLACIALEUR, "確認するGlacialeur"),
+		user: Option<UserId> = (PARAM_USER, "Glacialeurの持ち主 (省略時は登録済みのユーザーから探す)"),
+	}
+}
+
+pub struct VerifyCommand;
+
+impl BaseCommand for VerifyCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"verify".into()
+	}
+
+	fn get_description(&self) -> String {
+		"Glacialeurを検証します".into()
+	}
+}
+
+#[async_trait]
+impl Command for VerifyCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		VerifyArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(&ctx, command.guild_id, &command.locale).await;
+		let embed = match verify(&ctx, &msg, &command, &args).await {
+			Ok(embed) => embed,
+			Err(error_message) => CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(error_message)
+				.color(color::failed_color()),
+		};
+
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+}
+
+async fn verify(
+	ctx: &Context,
+	msg: &Messages,
+	command: &CommandInteraction,
+	args: &[CommandDataOption],
+) -> Result<CreateEmbed, String> {
+	let args = VerifyArgs::parse(args).map_err(|error| {
+		error!("Args parse error: {:?}", error);
+		error.to_string()
+	})?;
+	let guild_id = command.guild_id.ok_or_else(|| msg.get("verify.guild_only"))?;
+	let glacialeur_id = args.glacialeur.trim().to_uppercase();
+
+	// Glacialeurはdiscord_idとXORしてあるので、持ち主が分からないと復元できない
+	let not_found_args = [("glacialeur", glacialeur_id.clone()), ("user", PARAM_USER.to_string())];
+	let user_id = match args.user {
+		Some(user) => user.get(),
+		None => {
+			let user_data = AppState::get(ctx)
+				.await
+				.user_data()
+				.find_by_glacialeur(&glacialeur_id)
+				.await
+				.map_err(|error| {
+					error!("DB Error: {:?}", error);
+					msg.format("verify.db_error", &[("error", format!("{:?}", error))])
+				})?;
+			match user_data.as_slice() {
+				[user_data] => user_data.uid.get(),
+				[] => return Err(msg.format("verify.not_registered", &not_found_args)),
+				_ => return Err(msg.format("verify.duplicated", &not_found_args)),
+			}
+		},
+	};
+
+	let embed = CreateEmbed::new()
+		.title(msg.get("verify.title"))
+		.field(msg.get("verify.glacialeur_field"), &glacialeur_id, true)
+		.field(msg.get("verify.user_field"), format!("<@{}>", user_id), true);
+
+	let info = match glacialeur::decode(&glacialeur_id, user_id) {
+		Ok(v) => v,
+		Err(error) => {
+			return Ok(embed
+				.description(msg.format("verify.invalid", &[("error", error.to_string())]))
+				.color(color::failed_color()))
+		},
+	};
+
+	let join_date = DateTime::from_timestamp(guild_id.created_at().timestamp() + info.join_unixtime, 0)
+		.map(|v| utc_to_local_format(&v))
+		.unwrap_or_else(|| info.join_unixtime.to_string());
+
+	Ok(embed
+		.description(msg.get("verify.valid"))
+		.field(msg.get("verify.joined_at_field"), join_date, true)
+		.field(
+			msg.get("verify.version_field"),
+			format!("{} ({})", info.version_name(), info.version),
+			true,
+		)
+		.color(color::success_color()))
+}
@@ -1,4 +1,5 @@
 use crate::command_define::{BaseCommand, Command};
+use crate::i18n::Messages;
 use crate::utils::color;
 use serenity::all::{
 	CommandDataOption, CommandInteraction, CreateEmbed, CreateInteractionResponse,
@@ -42,14 +43,16 @@ impl Command for VersionCommand {
 		command: CommandInteraction,
 		_: Vec<CommandDataOption>,
 	) -> serenity::Result<()> {
+		let msg = Messages::for_interaction(&ctx, command.guild_id, &command.locale).await;
+		let args = [("name", self.get_project_name()), ("version", VERSION.to_string())];
 		command
 			.create_response(
 				&ctx.http,
 				CreateInteractionResponse::Message(
 					CreateInteractionResponseMessage::new().add_embed(
 						CreateEmbed::new()
-							.title("バージョン情報")
-							.description(format!("{} {}", self.get_project_name(), VERSION))
+							.title(msg.get("version.title"))
+							.description(msg.format("version.description", &args))
 							.color(color::normal_color()),
 					),
 				),
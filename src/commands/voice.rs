@@ -0,0 +1,33 @@
+use dict::DictCommand;
+use style::StyleCommand;
+
+use crate::command_define::{BaseCommand, CommonCommandType, SubCommand};
+
+mod dict;
+mod style;
+
+pub struct VoiceCommands {
+	sub_commands: Vec<CommonCommandType>,
+}
+
+impl BaseCommand for VoiceCommands {
+	fn new() -> Self {
+		Self {
+			sub_commands: vec![convert_command!(StyleCommand), convert_command!(DictCommand)],
+		}
+	}
+
+	fn get_name(&self) -> String {
+		"voice".into()
+	}
+
+	fn get_description(&self) -> String {
+		"Estella Voice Commands".into()
+	}
+}
+
+impl SubCommand for VoiceCommands {
+	fn get_sub_commands(&self) -> &Vec<CommonCommandType> {
+		&self.sub_commands
+	}
+}
@@ -0,0 +1,424 @@
+use crate::audit_log;
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::is_guild_owner;
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use crate::voice::text2speak::vvclient::{VoiceVoxTtsClient, VoiceVoxWord};
+use crate::voice::text2speak::{reload_dictionary, to_katakana};
+use entity::enums::AuditAction;
+use log::error;
+use serde_json::json;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_ACTION: &str = "action";
+const PARAM_SURFACE: &str = "surface";
+const PARAM_PRONUNCIATION: &str = "pronunciation";
+const PARAM_ACCENT_TYPE: &str = "accent_type";
+
+const ACTION_ADD: &str = "add";
+const ACTION_REMOVE: &str = "remove";
+const ACTION_LIST: &str = "list";
+// (値, 表示名のキー)
+const ACTIONS: [(&str, &str); 3] = [
+	(ACTION_ADD, "dict.action.add"),
+	(ACTION_REMOVE, "dict.action.remove"),
+	(ACTION_LIST, "dict.action.list"),
+];
+
+// tts_dictionaryの列の長さ
+const MAX_SURFACE_LENGTH: usize = 64;
+const MAX_PRONUNCIATION_LENGTH: usize = 128;
+// Embedのdescriptionの最大文字数
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+
+command_args! {
+	struct DictArgs {
+		action: String = (PARAM_ACTION, "操作 (add / remove / list)", autocomplete),
+		surface: Option<String> = (PARAM_SURFACE, "単語"),
+		pronunciation: Option<String> = (PARAM_PRONUNCIATION, "読み (カタカナ)"),
+		accent_type: Option<i64> = (PARAM_ACCENT_TYPE, "音が下がるモーラの位置 (0で下がらない、省略時は0)"),
+	}
+}
+
+pub struct DictCommand;
+
+impl BaseCommand for DictCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"dict".into()
+	}
+
+	fn get_description(&self) -> String {
+		"読み上げの辞書を編集します".into()
+	}
+}
+
+impl DictCommand {
+	async fn send_embed(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		embed: CreateEmbed,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(message)
+				.color(color::failed_color()),
+		)
+		.await
+	}
+
+	async fn send_success(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("common.done.title"))
+				.description(message)
+				.color(color::success_color()),
+		)
+		.await
+	}
+
+	async fn list(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		state: &AppState,
+	) -> serenity::Result<()> {
+		let guild_id = command.guild_id.unwrap();
+		let entries = match state.guild_configs().tts_dictionary(guild_id.get()).await {
+			Ok(v) => v,
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(ctx, command, msg, format!("{:?}", error)).await;
+			},
+		};
+
+		let mut description = String::new();
+		for entry in &entries {
+			let line = format!("{} → {} ({})\n", entry.surface, entry.pronunciation, entry.accent_type);
+			if description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_LIMIT {
+				break;
+			}
+			description += &line;
+		}
+		if description.is_empty() {
+			description = msg.get("dict.empty");
+		}
+
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("dict.title"))
+				.description(description)
+				.color(color::normal_color()),
+		)
+		.await
+	}
+
+	async fn add(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		state: &AppState,
+		args: &DictArgs,
+	) -> serenity::Result<()> {
+		let guild_id = command.guild_id.unwrap();
+		let (Some(surface), Some(pronunciation)) = (
+			args.surface.as_deref().map(str::trim),
+			args.pronunciation.as_deref().map(str::trim),
+		) else {
+			return self.send_error(ctx, command, msg, msg.get("dict.word_required")).await;
+		};
+		if surface.is_empty() || surface.chars().count() > MAX_SURFACE_LENGTH {
+			return self
+				.send_error(
+					ctx,
+					command,
+					msg,
+					msg.format("dict.surface_length", &[("max", MAX_SURFACE_LENGTH.to_string())]),
+				)
+				.await;
+		}
+		let pronunciation = to_katakana(pronunciation);
+		if pronunciation.is_empty() || pronunciation.chars().count() > MAX_PRONUNCIATION_LENGTH {
+			return self
+				.send_error(
+					ctx,
+					command,
+					msg,
+					msg.format(
+						"dict.pronunciation_length",
+						&[("max", MAX_PRONUNCIATION_LENGTH.to_string())],
+					),
+				)
+				.await;
+		}
+		let accent_type = args.accent_type.unwrap_or_default();
+		if accent_type < 0 {
+			return self.send_error(ctx, command, msg, msg.get("dict.invalid_accent")).await;
+		}
+
+		// 読みがカタカナでない、アクセントの位置が読みより長いなどは登録できない
+		let word = VoiceVoxWord::new(surface, pronunciation.as_str(), accent_type as usize);
+		if let Err(message) = VoiceVoxTtsClient::check_word(&word) {
+			return self
+				.send_error(
+					ctx,
+					command,
+					msg,
+					msg.format("dict.invalid_word", &[("error", message.to_string())]),
+				)
+				.await;
+		}
+
+		let guild_configs = state.guild_configs();
+		let before = match guild_configs.tts_dictionary(guild_id.get()).await {
+			Ok(v) => v.into_iter().find(|v| v.surface == surface),
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(ctx, command, msg, format!("{:?}", error)).await;
+			},
+		};
+		if let Err(error) = guild_configs
+			.save_tts_word(guild_id.get(), surface, &pronunciation, accent_type as i32)
+			.await
+		{
+			error!("DB Error: {:?}", error);
+			return self.send_error(ctx, command, msg, format!("{:?}", error)).await;
+		}
+		reload_dictionary(state, guild_id.get()).await;
+
+		audit_log::record(
+			state,
+			guild_id.get(),
+			Some(command.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "tts_dictionary",
+				"before": before.map(|v| json!({
+					"surface": v.surface,
+					"pronunciation": v.pronunciation,
+					"accent_type": v.accent_type,
+				})),
+				"after": {
+					"surface": surface,
+					"pronunciation": pronunciation,
+					"accent_type": accent_type,
+				},
+			}),
+		)
+		.await;
+
+		self.send_success(
+			ctx,
+			command,
+			msg,
+			msg.format(
+				"dict.added",
+				&[
+					("surface", surface.to_string()),
+					("pronunciation", pronunciation.clone()),
+				],
+			),
+		)
+		.await
+	}
+
+	async fn remove(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		state: &AppState,
+		args: &DictArgs,
+	) -> serenity::Result<()> {
+		let guild_id = command.guild_id.unwrap();
+		let Some(surface) = args.surface.as_deref().map(str::trim) else {
+			return self
+				.send_error(ctx, command, msg, msg.get("dict.surface_required"))
+				.await;
+		};
+
+		match state.guild_configs().delete_tts_word(guild_id.get(), surface).await {
+			Ok(true) => {},
+			Ok(false) => {
+				return self
+					.send_error(
+						ctx,
+						command,
+						msg,
+						msg.format("dict.not_registered", &[("surface", surface.to_string())]),
+					)
+					.await;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(ctx, command, msg, format!("{:?}", error)).await;
+			},
+		}
+		reload_dictionary(state, guild_id.get()).await;
+
+		audit_log::record(
+			state,
+			guild_id.get(),
+			Some(command.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "tts_dictionary",
+				"before": { "surface": surface },
+				"after": null,
+			}),
+		)
+		.await;
+
+		self.send_success(
+			ctx,
+			command,
+			msg,
+			msg.format("dict.removed", &[("surface", surface.to_string())]),
+		)
+		.await
+	}
+}
+
+#[async_trait]
+impl Command for DictCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		DictArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+
+		let args = match DictArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+		if !ACTIONS.iter().any(|(v, _)| *v == args.action) {
+			return self
+				.send_error(&ctx, &command, &msg, msg.get("dict.select_action"))
+				.await;
+		}
+
+		let state = AppState::get(&ctx).await;
+		match state.guild_configs().find(guild_id.get()).await {
+			Ok(Some(_)) => {},
+			Ok(None) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.guild_not_registered"))
+					.await;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		}
+
+		if args.action == ACTION_LIST {
+			return self.list(&ctx, &command, &msg, &state).await;
+		}
+
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self.send_error(&ctx, &command, &msg, msg.get("dict.owner_only")).await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		if args.action == ACTION_ADD {
+			self.add(&ctx, &command, &msg, &state, &args).await
+		} else {
+			self.remove(&ctx, &command, &msg, &state, &args).await
+		}
+	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_ACTION, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+
+		let msg = Messages::for_interaction(&ctx, command.guild_id, &command.locale).await;
+		let mut response = CreateAutocompleteResponse::new();
+		for (value, key) in ACTIONS {
+			let name = msg.get(key);
+			if value.contains(&input) || name.contains(&input) {
+				response = response.add_string_choice(format!("{} ({})", name, value), value);
+			}
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
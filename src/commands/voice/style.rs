@@ -0,0 +1,349 @@
+use crate::audit_log;
+use crate::command_args::{command_args, find_focused_arg, CommandArgs};
+use crate::command_define::{BaseCommand, Command};
+use crate::components::is_guild_owner;
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use crate::voice::text2speak::{select_style_id, voice_styles, VoiceStyle};
+use entity::enums::AuditAction;
+use entity::{GuildConfig, VoicePersona};
+use log::error;
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde_json::json;
+use serenity::all::{
+	CommandDataOption, CommandInteraction, CreateAutocompleteResponse, CreateCommandOption, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, InteractionResponseFlags,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const PARAM_STYLE: &str = "style";
+const PARAM_PERSONA: &str = "persona";
+
+// Discordのオートコンプリートで返せる候補の最大数
+const AUTOCOMPLETE_LIMIT: usize = 25;
+// Embedのdescriptionの最大文字数
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+// Embedのfieldのvalueの最大文字数
+const EMBED_FIELD_LIMIT: usize = 1024;
+// voice_persona.nameの長さ
+const MAX_PERSONA_NAME_LENGTH: usize = 64;
+
+command_args! {
+	struct StyleArgs {
+		style: Option<i64> = (PARAM_STYLE, "読み上げに使う声", autocomplete),
+		persona: Option<String> = (PARAM_PERSONA, "ペルソナの声を設定 (声を指定しない場合は削除)"),
+	}
+}
+
+pub struct StyleCommand;
+
+impl BaseCommand for StyleCommand {
+	fn new() -> Self {
+		Self {}
+	}
+
+	fn get_name(&self) -> String {
+		"style".into()
+	}
+
+	fn get_description(&self) -> String {
+		"読み上げの声を一覧・設定します".into()
+	}
+}
+
+impl StyleCommand {
+	async fn send_embed(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		embed: CreateEmbed,
+	) -> serenity::Result<()> {
+		command
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::Message(
+					CreateInteractionResponseMessage::new()
+						.add_embed(embed)
+						.flags(InteractionResponseFlags::EPHEMERAL),
+				),
+			)
+			.await
+	}
+
+	async fn send_error(
+		&self,
+		ctx: &Context,
+		command: &CommandInteraction,
+		msg: &Messages,
+		message: impl Into<String>,
+	) -> serenity::Result<()> {
+		self.send_embed(
+			ctx,
+			command,
+			CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(message)
+				.color(color::failed_color()),
+		)
+		.await
+	}
+
+	fn style_label(style_id: u32) -> String {
+		match voice_styles().iter().find(|v| v.id == style_id) {
+			Some(v) => format!("`{}` {}", v.id, v.label()),
+			None => format!("`{}`", style_id),
+		}
+	}
+
+	fn styles_embed(msg: &Messages, guild_config: &GuildConfig, personas: &[VoicePersona]) -> CreateEmbed {
+		let styles = voice_styles();
+		let current = select_style_id(styles, &[guild_config.voice_style_id]);
+
+		let mut description = String::new();
+		for style in styles {
+			let line = format!("`{}` {}\n", style.id, style.label());
+			if description.chars().count() + line.chars().count() > EMBED_DESCRIPTION_LIMIT {
+				break;
+			}
+			description += &line;
+		}
+		if description.is_empty() {
+			description = msg.get("style.empty");
+		}
+
+		let mut persona_text = String::new();
+		for persona in personas {
+			let line = format!(
+				"{}: {}\n",
+				persona.name,
+				Self::style_label(select_style_id(styles, &[Some(persona.style_id)]))
+			);
+			if persona_text.chars().count() + line.chars().count() > EMBED_FIELD_LIMIT {
+				break;
+			}
+			persona_text += &line;
+		}
+		if persona_text.is_empty() {
+			persona_text = msg.get("common.none");
+		}
+
+		CreateEmbed::new()
+			.description(description)
+			.field(msg.get("style.guild_style_field"), Self::style_label(current), false)
+			.field(msg.get("style.personas_field"), persona_text, false)
+	}
+
+	fn find_style(style_id: i64) -> Option<&'static VoiceStyle> {
+		voice_styles().iter().find(|v| i64::from(v.id) == style_id)
+	}
+}
+
+#[async_trait]
+impl Command for StyleCommand {
+	fn args_options(&self) -> Vec<CreateCommandOption> {
+		StyleArgs::options()
+	}
+
+	async fn execute(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let guild_id = match command.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		};
+
+		let msg = Messages::for_guild(&ctx, guild_id, Some(&command.locale)).await;
+
+		let args = match StyleArgs::parse(&args) {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Args parse error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, error.to_string()).await;
+			},
+		};
+
+		let state = AppState::get(&ctx).await;
+		let guild_configs = state.guild_configs();
+		let guild_config = match guild_configs.find(guild_id.get()).await {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				return self
+					.send_error(&ctx, &command, &msg, msg.get("common.guild_not_registered"))
+					.await;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+			},
+		};
+
+		// 何も指定されていない場合は、使える声と現在の設定を表示する
+		if args.style.is_none() && args.persona.is_none() {
+			let personas = match guild_configs.voice_personas(guild_id.get()).await {
+				Ok(v) => v,
+				Err(error) => {
+					error!("DB Error: {:?}", error);
+					return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+				},
+			};
+
+			return self
+				.send_embed(
+					&ctx,
+					&command,
+					Self::styles_embed(&msg, &guild_config, &personas)
+						.title(msg.get("style.title"))
+						.color(color::normal_color()),
+				)
+				.await;
+		}
+
+		match is_guild_owner(&ctx, guild_id, command.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				return self.send_error(&ctx, &command, &msg, msg.get("style.owner_only")).await;
+			},
+			None => {
+				error!("Not found Guild");
+				return Ok(());
+			},
+		}
+
+		let style = match args.style {
+			Some(v) => match Self::find_style(v) {
+				Some(style) => Some(style),
+				None => {
+					return self
+						.send_error(&ctx, &command, &msg, msg.get("style.select_style"))
+						.await;
+				},
+			},
+			None => None,
+		};
+
+		let (before, after, message) = match args.persona.as_deref().map(str::trim) {
+			Some(persona) => {
+				if persona.is_empty() || persona.chars().count() > MAX_PERSONA_NAME_LENGTH {
+					return self
+						.send_error(
+							&ctx,
+							&command,
+							&msg,
+							msg.format("style.persona_length", &[("max", MAX_PERSONA_NAME_LENGTH.to_string())]),
+						)
+						.await;
+				}
+
+				let before = match guild_configs.find_voice_persona(guild_id.get(), persona).await {
+					Ok(v) => v.map(|v| v.style_id),
+					Err(error) => {
+						error!("DB Error: {:?}", error);
+						return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+					},
+				};
+				let result = match style {
+					Some(style) => {
+						guild_configs
+							.save_voice_persona(guild_id.get(), persona, style.id as i32)
+							.await
+					},
+					None => guild_configs.delete_voice_persona(guild_id.get(), persona).await,
+				};
+				if let Err(error) = result {
+					error!("DB Error: {:?}", error);
+					return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+				}
+
+				let message = match style {
+					Some(style) => msg.format(
+						"style.persona_updated",
+						&[("persona", persona.to_string()), ("style", style.label())],
+					),
+					None => msg.format("style.persona_removed", &[("persona", persona.to_string())]),
+				};
+				(
+					json!({ "persona": persona, "style_id": before }),
+					json!({ "persona": persona, "style_id": style.map(|v| v.id) }),
+					message,
+				)
+			},
+			None => {
+				// personaが無い場合、styleは必ずある
+				let Some(style) = style else {
+					return Ok(());
+				};
+
+				let before = guild_config.voice_style_id;
+				let mut update_entity = guild_config.into_active_model();
+				update_entity.voice_style_id = ActiveValue::Set(Some(style.id as i32));
+				if let Err(error) = guild_configs.update(update_entity).await {
+					error!("DB Error: {:?}", error);
+					return self.send_error(&ctx, &command, &msg, format!("{:?}", error)).await;
+				}
+
+				(
+					json!({ "style_id": before }),
+					json!({ "style_id": style.id }),
+					msg.format("style.guild_updated", &[("style", style.label())]),
+				)
+			},
+		};
+
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(command.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "voice_style",
+				"before": before,
+				"after": after,
+			}),
+		)
+		.await;
+
+		self.send_embed(
+			&ctx,
+			&command,
+			CreateEmbed::new()
+				.title(msg.get("common.done.title"))
+				.description(message)
+				.color(color::success_color()),
+		)
+		.await
+	}
+
+	async fn autocomplete(
+		&self,
+		ctx: Context,
+		command: CommandInteraction,
+		args: Vec<CommandDataOption>,
+	) -> serenity::Result<()> {
+		let input = match find_focused_arg(&args) {
+			Some((PARAM_STYLE, value)) => value.to_string(),
+			_ => return Ok(()),
+		};
+
+		let mut response = CreateAutocompleteResponse::new();
+		for style in voice_styles()
+			.iter()
+			.filter(|v| v.label().contains(&input) || v.id.to_string() == input)
+			.take(AUTOCOMPLETE_LIMIT)
+		{
+			response = response.add_int_choice(style.label(), i64::from(style.id));
+		}
+
+		command
+			.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+			.await
+	}
+}
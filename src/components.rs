@@ -0,0 +1,427 @@
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use chrono::{DateTime, TimeZone, Utc};
+use config::ConfigHandler;
+use entity::PendingAccount;
+use hmac::{Hmac, Mac};
+use log::{debug, error, info, warn};
+use onboarding::{OnboardingHandler, RulesAcceptHandler};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serenity::all::{
+	ActionRowComponent, ComponentInteraction, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+	InteractionResponseFlags, ModalInteraction,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+use sha3::Sha3_256;
+use std::sync::{LazyLock, OnceLock};
+use template::TemplateHandler;
+use vote::{ConfirmHandler, RejectVoteHandler};
+
+mod config;
+mod onboarding;
+mod template;
+mod vote;
+
+pub use config::{config_summary, is_guild_owner};
+pub use onboarding::{onboarding_summary, rules_components};
+pub use template::template_modal;
+pub use vote::{ConfirmPayload, RejectVotePayload};
+
+/*
+ボタンなどのcustom_idは "prefix:payload:signature" で構成されています
+payloadはJSONにしたものを、signatureはprefixとpayloadに対するHMACの先頭16byteを16進数にしたものです
+Discordのcustom_idは100文字までなので、payloadはタプル構造体などで短くしてください
+モーダルのcustom_idも同じ形式で、送信時にModalHandlerへ振り分けます
+*/
+const SIGNATURE_BYTES: usize = 16;
+
+type HmacSha3 = Hmac<Sha3_256>;
+
+pub trait ComponentPayload: Serialize + DeserializeOwned + Send {
+	const PREFIX: &'static str;
+}
+
+#[async_trait]
+pub trait ComponentHandler: Send + Sync {
+	type Payload: ComponentPayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload);
+}
+
+#[async_trait]
+pub trait ModalHandler: Send + Sync {
+	type Payload: ComponentPayload;
+
+	async fn handle(&self, ctx: &Context, mi: &ModalInteraction, payload: Self::Payload);
+}
+
+#[async_trait]
+trait ComponentRoute: Send + Sync {
+	fn prefix(&self) -> &'static str;
+	async fn dispatch(&self, ctx: &Context, mc: &ComponentInteraction, payload: &str);
+}
+
+struct Route<H: ComponentHandler>(H);
+
+#[async_trait]
+impl<H: ComponentHandler> ComponentRoute for Route<H> {
+	fn prefix(&self) -> &'static str {
+		H::Payload::PREFIX
+	}
+
+	async fn dispatch(&self, ctx: &Context, mc: &ComponentInteraction, payload: &str) {
+		match serde_json::from_str::<H::Payload>(payload) {
+			Ok(payload) => self.0.handle(ctx, mc, payload).await,
+			Err(error) => {
+				warn!("Component payload parse error ({}): {:?}", mc.data.custom_id, error);
+				send_invalid_component(ctx, mc).await;
+			},
+		}
+	}
+}
+
+#[async_trait]
+trait ModalRoute: Send + Sync {
+	fn prefix(&self) -> &'static str;
+	async fn dispatch(&self, ctx: &Context, mi: &ModalInteraction, payload: &str);
+}
+
+struct Modal<H: ModalHandler>(H);
+
+#[async_trait]
+impl<H: ModalHandler> ModalRoute for Modal<H> {
+	fn prefix(&self) -> &'static str {
+		H::Payload::PREFIX
+	}
+
+	async fn dispatch(&self, ctx: &Context, mi: &ModalInteraction, payload: &str) {
+		match serde_json::from_str::<H::Payload>(payload) {
+			Ok(payload) => self.0.handle(ctx, mi, payload).await,
+			Err(error) => {
+				warn!("Modal payload parse error ({}): {:?}", mi.data.custom_id, error);
+				send_invalid_modal(ctx, mi).await;
+			},
+		}
+	}
+}
+
+macro_rules! route {
+	($handler: expr) => {
+		Box::new(Route($handler)) as Box<dyn ComponentRoute>
+	};
+}
+
+static COMPONENT_ROUTES: LazyLock<Vec<Box<dyn ComponentRoute>>> = LazyLock::new(|| {
+	vec![
+		route!(RejectVoteHandler),
+		route!(ConfirmHandler),
+		route!(ConfigHandler),
+		route!(OnboardingHandler),
+		route!(RulesAcceptHandler),
+	]
+});
+
+static MODAL_ROUTES: LazyLock<Vec<Box<dyn ModalRoute>>> =
+	LazyLock::new(|| vec![Box::new(Modal(TemplateHandler)) as Box<dyn ModalRoute>]);
+
+// 起動時にconfigのcomponent_secretで初期化する
+static COMPONENT_SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+
+pub fn init_secret(secret: &str) {
+	if COMPONENT_SECRET.set(secret.as_bytes().to_vec()).is_err() {
+		warn!("Component secret is already initialized");
+	}
+}
+
+fn create_mac() -> HmacSha3 {
+	let secret = COMPONENT_SECRET.get().expect("Component secret is not initialized");
+
+	HmacSha3::new_from_slice(secret).expect("HMAC can take key of any size")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|v| format!("{:02x}", v)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+	if !text.len().is_multiple_of(2) {
+		return None;
+	}
+
+	(0..text.len())
+		.step_by(2)
+		.map(|i| text.get(i..i + 2).and_then(|v| u8::from_str_radix(v, 16).ok()))
+		.collect()
+}
+
+pub fn make_custom_id<P: ComponentPayload>(payload: &P) -> String {
+	let payload = serde_json::to_string(payload).expect("Component payload must be serializable");
+	let body = format!("{}:{}", P::PREFIX, payload);
+
+	let mut mac = create_mac();
+	mac.update(body.as_bytes());
+	let signature = mac.finalize().into_bytes();
+
+	format!("{}:{}", body, to_hex(&signature[..SIGNATURE_BYTES]))
+}
+
+// 署名が正しければ (prefix, payload) を返す
+fn verify_custom_id(custom_id: &str) -> Option<(&str, &str)> {
+	let (body, signature) = custom_id.rsplit_once(':')?;
+	let (prefix, payload) = body.split_once(':')?;
+	let signature = from_hex(signature)?;
+	if signature.len() != SIGNATURE_BYTES {
+		return None;
+	}
+
+	let mut mac = create_mac();
+	mac.update(body.as_bytes());
+	mac.verify_truncated_left(&signature).ok()?;
+
+	Some((prefix, payload))
+}
+
+// 署名付きcustom_idにする前に送った投票ボタン
+// 署名が無いので、ボタンが付いているメッセージの申請が残っている場合だけ受け付ける
+#[derive(Debug, PartialEq)]
+enum LegacyCustomId {
+	// "reject_<uid>"
+	Reject(u64),
+	// "conf_<申請者>_<uid>"
+	Confirm(u64, u64),
+}
+
+impl LegacyCustomId {
+	fn parse(custom_id: &str) -> Option<Self> {
+		if let Some(user_id) = custom_id.strip_prefix("reject_") {
+			return Some(Self::Reject(user_id.parse().ok()?));
+		}
+		if let Some(ids) = custom_id.strip_prefix("conf_") {
+			let (requester_id, user_id) = ids.split_once('_')?;
+			return Some(Self::Confirm(requester_id.parse().ok()?, user_id.parse().ok()?));
+		}
+
+		None
+	}
+
+	fn user_id(&self) -> u64 {
+		match self {
+			Self::Reject(user_id) | Self::Confirm(_, user_id) => *user_id,
+		}
+	}
+
+	// 申請がこのボタンのメッセージのものか (conf_の場合は申請者も一致すること)
+	fn matches(&self, pending: &PendingAccount, message_id: u64) -> bool {
+		if pending.message_id.get() != message_id {
+			return false;
+		}
+		match self {
+			Self::Reject(_) => true,
+			Self::Confirm(requester_id, _) => pending.main_uid.map(|v| v.get()) == Some(*requester_id),
+		}
+	}
+
+	// (prefix, payload) にする
+	fn route(&self) -> (&'static str, String) {
+		let payload = match self {
+			Self::Reject(user_id) => serde_json::to_string(&RejectVotePayload(*user_id)),
+			Self::Confirm(requester_id, user_id) => serde_json::to_string(&ConfirmPayload(*requester_id, *user_id)),
+		};
+		let prefix = match self {
+			Self::Reject(_) => RejectVotePayload::PREFIX,
+			Self::Confirm(..) => ConfirmPayload::PREFIX,
+		};
+		(prefix, payload.expect("Component payload must be serializable"))
+	}
+}
+
+// 署名無しのボタンを受け付ける期限
+// 移行前のメインアカウントの投票は7日で終わるので、余裕を持たせて2週間後まで (過ぎたらLegacyCustomIdごと削除する)
+// サブアカウントの承認待ちが期限を過ぎた場合は、申請し直してもらう
+fn legacy_cutoff() -> DateTime<Utc> {
+	Utc.with_ymd_and_hms(2026, 11, 2, 0, 0, 0).unwrap()
+}
+
+async fn legacy_component_route(ctx: &Context, mc: &ComponentInteraction, legacy: LegacyCustomId) {
+	if Utc::now() >= legacy_cutoff() {
+		warn!(
+			"Expired legacy component: {} (user: {})",
+			mc.data.custom_id,
+			mc.user.id.get()
+		);
+		send_invalid_component(ctx, mc).await;
+		return;
+	}
+
+	let pending = match mc.guild_id {
+		Some(guild_id) => AppState::get(ctx)
+			.await
+			.accounts()
+			.pending(guild_id.get(), legacy.user_id())
+			.await
+			.unwrap_or_else(|error| {
+				error!("DB Error: {:?}", error);
+				None
+			}),
+		None => None,
+	};
+	if !pending.is_some_and(|v| legacy.matches(&v, mc.message.id.get())) {
+		warn!(
+			"Invalid legacy component: {} (user: {})",
+			mc.data.custom_id,
+			mc.user.id.get()
+		);
+		send_invalid_component(ctx, mc).await;
+		return;
+	}
+
+	let (prefix, payload) = legacy.route();
+	if let Some(route) = COMPONENT_ROUTES.iter().find(|v| v.prefix() == prefix) {
+		info!("Legacy component: {} (user: {})", mc.data.custom_id, mc.user.id.get());
+		route.dispatch(ctx, mc, &payload).await;
+	}
+}
+
+fn invalid_response(msg: &Messages, description: String) -> CreateInteractionResponse {
+	CreateInteractionResponse::Message(
+		CreateInteractionResponseMessage::new()
+			.add_embed(
+				CreateEmbed::new()
+					.title(msg.get("common.error.title"))
+					.description(description)
+					.color(color::failed_color()),
+			)
+			.flags(InteractionResponseFlags::EPHEMERAL),
+	)
+}
+
+async fn send_invalid_component(ctx: &Context, mc: &ComponentInteraction) {
+	let msg = Messages::for_interaction(ctx, mc.guild_id, &mc.locale).await;
+	if let Err(error) = mc
+		.create_response(&ctx.http, invalid_response(&msg, msg.get("common.invalid_button")))
+		.await
+	{
+		error!("Error: {:?}", error);
+	}
+}
+
+async fn send_invalid_modal(ctx: &Context, mi: &ModalInteraction) {
+	let msg = Messages::for_interaction(ctx, mi.guild_id, &mi.locale).await;
+	if let Err(error) = mi
+		.create_response(&ctx.http, invalid_response(&msg, msg.get("common.invalid_form")))
+		.await
+	{
+		error!("Error: {:?}", error);
+	}
+}
+
+// モーダルの入力欄からcustom_idが一致するものの値を取り出す
+pub fn modal_value<'a>(mi: &'a ModalInteraction, custom_id: &str) -> Option<&'a str> {
+	mi.data
+		.components
+		.iter()
+		.flat_map(|v| v.components.iter())
+		.find_map(|v| match v {
+			ActionRowComponent::InputText(input) if input.custom_id == custom_id => input.value.as_deref(),
+			_ => None,
+		})
+}
+
+pub async fn component_route(ctx: Context, mc: ComponentInteraction) {
+	let custom_id = mc.data.custom_id.clone();
+	if let Some(legacy) = LegacyCustomId::parse(&custom_id) {
+		legacy_component_route(&ctx, &mc, legacy).await;
+		return;
+	}
+	let prefix = custom_id.split(':').next().unwrap_or_default();
+
+	// 登録されていないものはコマンド内のcollectorで待ち受けているものなので何もしない
+	let route = match COMPONENT_ROUTES.iter().find(|v| v.prefix() == prefix) {
+		Some(v) => v,
+		None => {
+			debug!("Not routed component: {}", custom_id);
+			return;
+		},
+	};
+
+	match verify_custom_id(&custom_id) {
+		Some((_, payload)) => route.dispatch(&ctx, &mc, payload).await,
+		None => {
+			warn!(
+				"Invalid component signature: {} (user: {})",
+				custom_id,
+				mc.user.id.get()
+			);
+			send_invalid_component(&ctx, &mc).await;
+		},
+	}
+}
+
+pub async fn modal_route(ctx: Context, mi: ModalInteraction) {
+	let custom_id = mi.data.custom_id.clone();
+	let prefix = custom_id.split(':').next().unwrap_or_default();
+
+	let route = match MODAL_ROUTES.iter().find(|v| v.prefix() == prefix) {
+		Some(v) => v,
+		None => {
+			debug!("Not routed modal: {}", custom_id);
+			return;
+		},
+	};
+
+	match verify_custom_id(&custom_id) {
+		Some((_, payload)) => route.dispatch(&ctx, &mi, payload).await,
+		None => {
+			warn!("Invalid modal signature: {} (user: {})", custom_id, mi.user.id.get());
+			send_invalid_modal(&ctx, &mi).await;
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use entity::enums::AccountType;
+
+	#[test]
+	fn legacy_ids() {
+		assert_eq!(LegacyCustomId::parse("reject_123"), Some(LegacyCustomId::Reject(123)));
+		assert_eq!(LegacyCustomId::parse("conf_1_2"), Some(LegacyCustomId::Confirm(1, 2)));
+
+		let (prefix, payload) = LegacyCustomId::Confirm(1, 2).route();
+		assert_eq!(prefix, ConfirmPayload::PREFIX);
+		let ConfirmPayload(requester_id, user_id) = serde_json::from_str(&payload).unwrap();
+		assert_eq!((requester_id, user_id), (1, 2));
+	}
+
+	#[test]
+	fn legacy_ignores_signed_and_broken_ids() {
+		assert!(LegacyCustomId::parse("reject:[123]:00").is_none());
+		assert!(LegacyCustomId::parse("reject_abc").is_none());
+		assert!(LegacyCustomId::parse("conf_1").is_none());
+	}
+
+	#[test]
+	fn legacy_ids_need_pending_on_the_same_message() {
+		let pending = PendingAccount {
+			uid: 2.into(),
+			name: Some("sub".to_string()),
+			guild_id: 10.into(),
+			account_type: AccountType::Sub,
+			message_id: 100.into(),
+			end_voting: None,
+			main_uid: Some(1.into()),
+			first_cert: None,
+		};
+
+		assert!(LegacyCustomId::Confirm(1, 2).matches(&pending, 100));
+		assert!(LegacyCustomId::Reject(2).matches(&pending, 100));
+		// 別のメッセージ
+		assert!(!LegacyCustomId::Reject(2).matches(&pending, 101));
+		// 申請者を偽ったもの
+		assert!(!LegacyCustomId::Confirm(3, 2).matches(&pending, 100));
+	}
+}
@@ -0,0 +1,352 @@
+use super::{make_custom_id, ComponentHandler, ComponentPayload};
+use crate::audit_log;
+use crate::i18n::Messages;
+use crate::state::AppState;
+use crate::utils::color;
+use entity::enums::AuditAction;
+use entity::guild_config::ActiveModel as GuildConfigActiveModel;
+use entity::unsigned::DbU64;
+use entity::GuildConfig;
+use log::{error, info, warn};
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serenity::all::{
+	ButtonStyle, ChannelId, ChannelType, ComponentInteraction, ComponentInteractionDataKind, CreateActionRow,
+	CreateButton, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu,
+	CreateSelectMenuKind, GuildId, InteractionResponseFlags, RoleId, UserId,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigItem {
+	LogChannel,
+	AuthRole,
+	BotRole,
+	AiChatChannel,
+	WhiteList,
+	LeaveBan,
+}
+
+impl ConfigItem {
+	// 選択された値をguild_configに反映したActiveModelを返す (操作の種類が合わない場合はNone)
+	fn apply(&self, guild_config: &GuildConfig, kind: &ComponentInteractionDataKind) -> Option<GuildConfigActiveModel> {
+		let mut update_entity = guild_config.clone().into_active_model();
+		match (self, kind) {
+			(ConfigItem::LogChannel, ComponentInteractionDataKind::ChannelSelect { values }) => {
+				update_entity.log_channel_id = ActiveValue::Set(values.first().map(|v| DbU64(v.get())));
+			},
+			(ConfigItem::AiChatChannel, ComponentInteractionDataKind::ChannelSelect { values }) => {
+				update_entity.send_ai_chat_channel_id = ActiveValue::Set(values.first().map(|v| DbU64(v.get())));
+			},
+			(ConfigItem::AuthRole, ComponentInteractionDataKind::RoleSelect { values }) => {
+				update_entity.auth_role_id = ActiveValue::Set(values.first().map(|v| DbU64(v.get())));
+			},
+			(ConfigItem::BotRole, ComponentInteractionDataKind::RoleSelect { values }) => {
+				update_entity.bot_role_id = ActiveValue::Set(values.first().map(|v| DbU64(v.get())));
+			},
+			(ConfigItem::WhiteList, ComponentInteractionDataKind::Button) => {
+				update_entity.white_list = ActiveValue::Set(!guild_config.white_list);
+			},
+			(ConfigItem::LeaveBan, ComponentInteractionDataKind::Button) => {
+				update_entity.leave_ban = ActiveValue::Set(!guild_config.leave_ban);
+			},
+			_ => return None,
+		}
+
+		Some(update_entity)
+	}
+
+	// 監査ログ用の値 (IDは精度が落ちないように文字列にする)
+	fn value(&self, guild_config: &GuildConfig) -> Value {
+		let id = |v: Option<DbU64>| v.map(|v| v.to_string());
+		match self {
+			ConfigItem::LogChannel => json!(id(guild_config.log_channel_id)),
+			ConfigItem::AiChatChannel => json!(id(guild_config.send_ai_chat_channel_id)),
+			ConfigItem::AuthRole => json!(id(guild_config.auth_role_id)),
+			ConfigItem::BotRole => json!(id(guild_config.bot_role_id)),
+			ConfigItem::WhiteList => json!(guild_config.white_list),
+			ConfigItem::LeaveBan => json!(guild_config.leave_ban),
+		}
+	}
+}
+
+// 設定項目 (ConfigItem)
+#[derive(Serialize, Deserialize)]
+pub struct ConfigPayload(pub ConfigItem);
+
+impl ComponentPayload for ConfigPayload {
+	const PREFIX: &'static str = "config";
+}
+
+// ボタンのラベルは80文字まで
+const BUTTON_LABEL_MAX_CHARS: usize = 80;
+
+pub(super) fn channel_text(msg: &Messages, id: Option<DbU64>) -> String {
+	id.map(|v| format!("<#{}>", v))
+		.unwrap_or_else(|| msg.get("common.not_set"))
+}
+
+fn role_text(msg: &Messages, id: Option<DbU64>) -> String {
+	id.map(|v| format!("<@&{}>", v))
+		.unwrap_or_else(|| msg.get("common.not_set"))
+}
+
+pub(super) fn enabled_text(msg: &Messages, value: bool) -> String {
+	if value {
+		msg.get("common.enabled")
+	} else {
+		msg.get("common.disabled")
+	}
+}
+
+pub(super) fn channel_select(custom_id: String, placeholder: &str, current: Option<DbU64>) -> CreateActionRow {
+	CreateActionRow::SelectMenu(
+		CreateSelectMenu::new(
+			custom_id,
+			CreateSelectMenuKind::Channel {
+				channel_types: Some(vec![ChannelType::Text]),
+				default_channels: current.map(|v| vec![ChannelId::new(v.get())]),
+			},
+		)
+		.placeholder(placeholder)
+		.min_values(0)
+		.max_values(1),
+	)
+}
+
+fn role_select(custom_id: String, placeholder: &str, current: Option<DbU64>) -> CreateActionRow {
+	CreateActionRow::SelectMenu(
+		CreateSelectMenu::new(
+			custom_id,
+			CreateSelectMenuKind::Role {
+				default_roles: current.map(|v| vec![RoleId::new(v.get())]),
+			},
+		)
+		.placeholder(placeholder)
+		.min_values(0)
+		.max_values(1),
+	)
+}
+
+pub(super) fn toggle_button(msg: &Messages, custom_id: String, label: &str, value: bool) -> CreateButton {
+	CreateButton::new(custom_id)
+		.style(if value {
+			ButtonStyle::Success
+		} else {
+			ButtonStyle::Secondary
+		})
+		.label(
+			format!("{}: {}", label, enabled_text(msg, value))
+				.chars()
+				.take(BUTTON_LABEL_MAX_CHARS)
+				.collect::<String>(),
+		)
+}
+
+// guild_configの内容と編集用のメニュー・ボタンを作成する
+pub fn config_summary(msg: &Messages, guild_config: &GuildConfig) -> (CreateEmbed, Vec<CreateActionRow>) {
+	let embed = CreateEmbed::new()
+		.title(msg.get("config.title"))
+		.description(msg.get("config.description"))
+		.field(
+			msg.get("config.log_channel_field"),
+			channel_text(msg, guild_config.log_channel_id),
+			true,
+		)
+		.field(
+			msg.get("config.ai_chat_channel_field"),
+			channel_text(msg, guild_config.send_ai_chat_channel_id),
+			true,
+		)
+		.field("\u{200b}", "\u{200b}", true)
+		.field(
+			msg.get("config.auth_role_field"),
+			role_text(msg, guild_config.auth_role_id),
+			true,
+		)
+		.field(
+			msg.get("config.bot_role_field"),
+			role_text(msg, guild_config.bot_role_id),
+			true,
+		)
+		.field("\u{200b}", "\u{200b}", true)
+		.field(
+			msg.get("config.white_list_field"),
+			enabled_text(msg, guild_config.white_list),
+			true,
+		)
+		.field(
+			msg.get("config.leave_ban_field"),
+			enabled_text(msg, guild_config.leave_ban),
+			true,
+		)
+		.field(
+			msg.get("config.locale_field"),
+			guild_config
+				.locale
+				.clone()
+				.unwrap_or_else(|| msg.get("config.locale_auto")),
+			true,
+		)
+		.color(color::normal_color());
+
+	let components = vec![
+		channel_select(
+			make_custom_id(&ConfigPayload(ConfigItem::LogChannel)),
+			&msg.get("config.log_channel_placeholder"),
+			guild_config.log_channel_id,
+		),
+		channel_select(
+			make_custom_id(&ConfigPayload(ConfigItem::AiChatChannel)),
+			&msg.get("config.ai_chat_channel_placeholder"),
+			guild_config.send_ai_chat_channel_id,
+		),
+		role_select(
+			make_custom_id(&ConfigPayload(ConfigItem::AuthRole)),
+			&msg.get("config.auth_role_placeholder"),
+			guild_config.auth_role_id,
+		),
+		role_select(
+			make_custom_id(&ConfigPayload(ConfigItem::BotRole)),
+			&msg.get("config.bot_role_placeholder"),
+			guild_config.bot_role_id,
+		),
+		CreateActionRow::Buttons(vec![
+			toggle_button(
+				msg,
+				make_custom_id(&ConfigPayload(ConfigItem::WhiteList)),
+				&msg.get("config.white_list_field"),
+				guild_config.white_list,
+			),
+			toggle_button(
+				msg,
+				make_custom_id(&ConfigPayload(ConfigItem::LeaveBan)),
+				&msg.get("config.leave_ban_field"),
+				guild_config.leave_ban,
+			),
+		]),
+	];
+
+	(embed, components)
+}
+
+pub fn is_guild_owner(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Option<bool> {
+	guild_id.to_guild_cached(&ctx.cache).map(|v| v.owner_id == user_id)
+}
+
+pub(super) async fn send_error(ctx: &Context, mc: &ComponentInteraction, msg: &Messages, message: impl Into<String>) {
+	if let Err(error) = mc
+		.create_response(
+			&ctx.http,
+			CreateInteractionResponse::Message(
+				CreateInteractionResponseMessage::new()
+					.add_embed(
+						CreateEmbed::new()
+							.title(msg.get("common.error.title"))
+							.description(message)
+							.color(color::failed_color()),
+					)
+					.flags(InteractionResponseFlags::EPHEMERAL),
+			),
+		)
+		.await
+	{
+		error!("Error: {:?}", error);
+	}
+}
+
+pub struct ConfigHandler;
+
+#[async_trait]
+impl ComponentHandler for ConfigHandler {
+	type Payload = ConfigPayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload) {
+		let ConfigPayload(item) = payload;
+
+		let guild_id = match mc.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Error: Not found guild id");
+				return;
+			},
+		};
+		let msg = Messages::for_guild(ctx, guild_id, Some(&mc.locale)).await;
+		match is_guild_owner(ctx, guild_id, mc.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				send_error(ctx, mc, &msg, msg.get("common.owner_only_setting")).await;
+				return;
+			},
+			None => {
+				error!("Not found Guild");
+				return;
+			},
+		}
+
+		let state = AppState::get(ctx).await;
+		let guild_config = state.guild_configs().find(guild_id.get()).await;
+		let guild_config = match guild_config {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				send_error(ctx, mc, &msg, msg.get("common.guild_not_registered")).await;
+				return;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mc, &msg, format!("{:?}", error)).await;
+				return;
+			},
+		};
+
+		let update_entity = match item.apply(&guild_config, &mc.data.kind) {
+			Some(v) => v,
+			None => {
+				warn!("Config component kind mismatch: {:?} ({:?})", item, mc.data.kind);
+				send_error(ctx, mc, &msg, msg.get("common.invalid_menu")).await;
+				return;
+			},
+		};
+
+		let updated_config = match state.guild_configs().update(update_entity).await {
+			Ok(v) => v,
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mc, &msg, format!("{:?}", error)).await;
+				return;
+			},
+		};
+		info!("guild config updated: {:?} ({})", item, guild_id.get());
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(mc.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": item,
+				"before": item.value(&guild_config),
+				"after": item.value(&updated_config),
+			}),
+		)
+		.await;
+
+		let (embed, components) = config_summary(&msg, &updated_config);
+		if let Err(error) = mc
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::UpdateMessage(
+					CreateInteractionResponseMessage::new()
+						.embeds(vec![embed])
+						.components(components),
+				),
+			)
+			.await
+		{
+			error!("Error: {:?}", error);
+		}
+	}
+}
@@ -0,0 +1,440 @@
+use super::config::{channel_select, channel_text, enabled_text, send_error, toggle_button};
+use super::{is_guild_owner, make_custom_id, ComponentHandler, ComponentPayload};
+use crate::audit_log;
+use crate::i18n::Messages;
+use crate::onboarding::{filter_roles, guild_roles};
+use crate::state::AppState;
+use crate::utils::color;
+use entity::enums::{AccountType, AuditAction};
+use entity::unsigned::DbU64;
+use entity::{GuildConfig, OnboardingRole};
+use log::{error, info, warn};
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serenity::all::{
+	ButtonStyle, ComponentInteraction, ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateEmbed,
+	CreateInteractionResponse, CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind, GuildId,
+	InteractionResponseFlags, RoleId,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+// アカウントタイプごとに選択できるロールの最大数
+const ROLE_SELECT_MAX: u8 = 10;
+
+// ルール同意ボタン (サーバーID)
+// DMに送ることもあるので、guild_idはpayloadに含める
+#[derive(Serialize, Deserialize)]
+pub struct RulesAcceptPayload(pub u64);
+
+impl ComponentPayload for RulesAcceptPayload {
+	const PREFIX: &'static str = "rules";
+}
+
+pub fn rules_components(msg: &Messages, guild_id: GuildId) -> Vec<CreateActionRow> {
+	vec![CreateActionRow::Buttons(vec![CreateButton::new(make_custom_id(
+		&RulesAcceptPayload(guild_id.get()),
+	))
+	.style(ButtonStyle::Success)
+	.label(msg.get("welcome.rules.button"))])]
+}
+
+async fn send_rules_response(ctx: &Context, mc: &ComponentInteraction, embed: CreateEmbed) {
+	if let Err(error) = mc
+		.create_response(
+			&ctx.http,
+			CreateInteractionResponse::Message(
+				CreateInteractionResponseMessage::new()
+					.add_embed(embed)
+					.flags(InteractionResponseFlags::EPHEMERAL),
+			),
+		)
+		.await
+	{
+		error!("Error: {:?}", error);
+	}
+}
+
+pub struct RulesAcceptHandler;
+
+#[async_trait]
+impl ComponentHandler for RulesAcceptHandler {
+	type Payload = RulesAcceptPayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload) {
+		let RulesAcceptPayload(guild_id) = payload;
+		let guild_id = GuildId::new(guild_id);
+		let user_id = mc.user.id;
+		let msg = Messages::for_guild(ctx, guild_id, Some(&mc.locale)).await;
+		let error_embed = |description: String| {
+			CreateEmbed::new()
+				.title(msg.get("common.error.title"))
+				.description(description)
+				.color(color::failed_color())
+		};
+
+		let state = AppState::get(ctx).await;
+		let accounts = state.accounts();
+		let guild_config = state.guild_configs().find(guild_id.get()).await;
+		let main = accounts.main(guild_id.get(), user_id.get()).await;
+		let sub = accounts.sub(guild_id.get(), user_id.get()).await;
+
+		let guild_config = match guild_config {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				error!("not found guild config.");
+				return;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_rules_response(ctx, mc, error_embed(format!("{:?}", error))).await;
+				return;
+			},
+		};
+		// 退出済みのメインアカウントは対象外
+		let registered = match (main, sub) {
+			(Ok(main), Ok(sub)) => main.is_some_and(|v| !v.is_leaved) || sub.is_some(),
+			(Err(error), _) | (_, Err(error)) => {
+				error!("DB Error: {:?}", error);
+				send_rules_response(ctx, mc, error_embed(format!("{:?}", error))).await;
+				return;
+			},
+		};
+		if !registered {
+			send_rules_response(ctx, mc, error_embed(msg.get("welcome.rules.not_member"))).await;
+			return;
+		}
+		let role_id = match guild_config.auth_role_id {
+			Some(v) => RoleId::new(v.get()),
+			None => {
+				warn!("auth_role_id is none");
+				send_rules_response(ctx, mc, error_embed(msg.get("welcome.rules.role_not_set"))).await;
+				return;
+			},
+		};
+
+		let member = match guild_id.member(ctx, user_id).await {
+			Ok(v) => v,
+			Err(error) => {
+				error!("Error: {:?}", error);
+				send_rules_response(ctx, mc, error_embed(msg.get("welcome.rules.not_member"))).await;
+				return;
+			},
+		};
+		if member.roles.contains(&role_id) {
+			send_rules_response(
+				ctx,
+				mc,
+				CreateEmbed::new()
+					.title(msg.get("common.done.title"))
+					.description(msg.get("welcome.rules.already"))
+					.color(color::normal_color()),
+			)
+			.await;
+			return;
+		}
+		if let Err(error) = member.add_role(&ctx.http, role_id).await {
+			error!("Error: {:?}", error);
+			send_rules_response(ctx, mc, error_embed(format!("{:?}", error))).await;
+			return;
+		}
+		info!("rules accepted: {} ({})", user_id.get(), guild_id.get());
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(user_id.get()),
+			Some(user_id.get()),
+			AuditAction::RulesAccept,
+			json!({ "role_id": role_id.to_string() }),
+		)
+		.await;
+
+		send_rules_response(
+			ctx,
+			mc,
+			CreateEmbed::new()
+				.title(msg.get("common.done.title"))
+				.description(msg.get("welcome.rules.accepted"))
+				.color(color::success_color()),
+		)
+		.await;
+	}
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingItem {
+	WelcomeChannel,
+	MainRoles,
+	SubRoles,
+	WelcomeDm,
+	RulesAccept,
+}
+
+impl OnboardingItem {
+	fn account_type(&self) -> Option<AccountType> {
+		match self {
+			OnboardingItem::MainRoles => Some(AccountType::Main),
+			OnboardingItem::SubRoles => Some(AccountType::Sub),
+			_ => None,
+		}
+	}
+
+	// 監査ログ用の値 (IDは精度が落ちないように文字列にする)
+	fn value(&self, guild_config: &GuildConfig, roles: &[OnboardingRole]) -> Value {
+		match (self, self.account_type()) {
+			(_, Some(account_type)) => json!(filter_roles(roles, &account_type)
+				.iter()
+				.map(|v| v.to_string())
+				.collect::<Vec<_>>()),
+			(OnboardingItem::WelcomeChannel, _) => json!(guild_config.welcome_channel_id.map(|v| v.to_string())),
+			(OnboardingItem::WelcomeDm, _) => json!(guild_config.welcome_dm),
+			(OnboardingItem::RulesAccept, _) => json!(guild_config.rules_accept),
+			_ => Value::Null,
+		}
+	}
+}
+
+// オンボーディングの設定項目 (OnboardingItem)
+#[derive(Serialize, Deserialize)]
+pub struct OnboardingPayload(pub OnboardingItem);
+
+impl ComponentPayload for OnboardingPayload {
+	const PREFIX: &'static str = "onboard";
+}
+
+fn roles_text(msg: &Messages, roles: &[RoleId]) -> String {
+	if roles.is_empty() {
+		return msg.get("common.not_set");
+	}
+
+	roles.iter().map(|v| format!("<@&{}>", v)).collect::<Vec<_>>().join(" ")
+}
+
+fn roles_select(custom_id: String, placeholder: &str, current: Vec<RoleId>) -> CreateActionRow {
+	CreateActionRow::SelectMenu(
+		CreateSelectMenu::new(
+			custom_id,
+			CreateSelectMenuKind::Role {
+				default_roles: (!current.is_empty()).then_some(current),
+			},
+		)
+		.placeholder(placeholder)
+		.min_values(0)
+		.max_values(ROLE_SELECT_MAX),
+	)
+}
+
+// オンボーディングの設定内容と編集用のメニュー・ボタンを作成する
+pub fn onboarding_summary(
+	msg: &Messages,
+	guild_config: &GuildConfig,
+	roles: &[OnboardingRole],
+) -> (CreateEmbed, Vec<CreateActionRow>) {
+	let main_roles = filter_roles(roles, &AccountType::Main);
+	let sub_roles = filter_roles(roles, &AccountType::Sub);
+
+	let embed = CreateEmbed::new()
+		.title(msg.get("onboarding.settings.title"))
+		.description(msg.get("onboarding.settings.description"))
+		.field(
+			msg.get("onboarding.settings.welcome_channel_field"),
+			channel_text(msg, guild_config.welcome_channel_id),
+			true,
+		)
+		.field(
+			msg.get("onboarding.settings.welcome_dm_field"),
+			enabled_text(msg, guild_config.welcome_dm),
+			true,
+		)
+		.field(
+			msg.get("onboarding.settings.rules_accept_field"),
+			enabled_text(msg, guild_config.rules_accept),
+			true,
+		)
+		.field(
+			msg.get("onboarding.settings.main_roles_field"),
+			roles_text(msg, &main_roles),
+			false,
+		)
+		.field(
+			msg.get("onboarding.settings.sub_roles_field"),
+			roles_text(msg, &sub_roles),
+			false,
+		)
+		.color(color::normal_color());
+
+	let components = vec![
+		channel_select(
+			make_custom_id(&OnboardingPayload(OnboardingItem::WelcomeChannel)),
+			&msg.get("onboarding.settings.welcome_channel_placeholder"),
+			guild_config.welcome_channel_id,
+		),
+		roles_select(
+			make_custom_id(&OnboardingPayload(OnboardingItem::MainRoles)),
+			&msg.get("onboarding.settings.main_roles_placeholder"),
+			main_roles,
+		),
+		roles_select(
+			make_custom_id(&OnboardingPayload(OnboardingItem::SubRoles)),
+			&msg.get("onboarding.settings.sub_roles_placeholder"),
+			sub_roles,
+		),
+		CreateActionRow::Buttons(vec![
+			toggle_button(
+				msg,
+				make_custom_id(&OnboardingPayload(OnboardingItem::WelcomeDm)),
+				&msg.get("onboarding.settings.welcome_dm_field"),
+				guild_config.welcome_dm,
+			),
+			toggle_button(
+				msg,
+				make_custom_id(&OnboardingPayload(OnboardingItem::RulesAccept)),
+				&msg.get("onboarding.settings.rules_accept_field"),
+				guild_config.rules_accept,
+			),
+		]),
+	];
+
+	(embed, components)
+}
+
+pub struct OnboardingHandler;
+
+impl OnboardingHandler {
+	// 選択された内容を保存する (操作の種類が合わない場合はOk(false))
+	async fn apply(
+		&self,
+		state: &AppState,
+		item: OnboardingItem,
+		guild_config: &GuildConfig,
+		kind: &ComponentInteractionDataKind,
+	) -> Result<bool, sea_orm::DbErr> {
+		let mut update_entity = guild_config.clone().into_active_model();
+		match (item, kind) {
+			(OnboardingItem::WelcomeChannel, ComponentInteractionDataKind::ChannelSelect { values }) => {
+				update_entity.welcome_channel_id = ActiveValue::Set(values.first().map(|v| DbU64(v.get())));
+			},
+			(OnboardingItem::WelcomeDm, ComponentInteractionDataKind::Button) => {
+				update_entity.welcome_dm = ActiveValue::Set(!guild_config.welcome_dm);
+			},
+			(OnboardingItem::RulesAccept, ComponentInteractionDataKind::Button) => {
+				update_entity.rules_accept = ActiveValue::Set(!guild_config.rules_accept);
+			},
+			(
+				OnboardingItem::MainRoles | OnboardingItem::SubRoles,
+				ComponentInteractionDataKind::RoleSelect { values },
+			) => {
+				let account_type = item.account_type().unwrap();
+				let role_ids: Vec<u64> = values.iter().map(|v| v.get()).collect();
+				return state
+					.guild_configs()
+					.replace_onboarding_roles(guild_config.uid.get(), account_type, &role_ids)
+					.await
+					.map(|_| true);
+			},
+			_ => return Ok(false),
+		}
+
+		state.guild_configs().update(update_entity).await.map(|_| true)
+	}
+}
+
+#[async_trait]
+impl ComponentHandler for OnboardingHandler {
+	type Payload = OnboardingPayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload) {
+		let OnboardingPayload(item) = payload;
+
+		let guild_id = match mc.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Error: Not found guild id");
+				return;
+			},
+		};
+		let msg = Messages::for_guild(ctx, guild_id, Some(&mc.locale)).await;
+		match is_guild_owner(ctx, guild_id, mc.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				send_error(ctx, mc, &msg, msg.get("common.owner_only_setting")).await;
+				return;
+			},
+			None => {
+				error!("Not found Guild");
+				return;
+			},
+		}
+
+		let state = AppState::get(ctx).await;
+		let guild_config = match state.guild_configs().find(guild_id.get()).await {
+			Ok(Some(v)) => v,
+			Ok(None) => {
+				send_error(ctx, mc, &msg, msg.get("common.guild_not_registered")).await;
+				return;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mc, &msg, format!("{:?}", error)).await;
+				return;
+			},
+		};
+		let roles = guild_roles(&state, guild_id.get()).await;
+
+		match self.apply(&state, item, &guild_config, &mc.data.kind).await {
+			Ok(true) => {},
+			Ok(false) => {
+				warn!("Onboarding component kind mismatch: {:?} ({:?})", item, mc.data.kind);
+				send_error(ctx, mc, &msg, msg.get("common.invalid_menu")).await;
+				return;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mc, &msg, format!("{:?}", error)).await;
+				return;
+			},
+		}
+
+		let updated_config = match state.guild_configs().find(guild_id.get()).await {
+			Ok(Some(v)) => v,
+			Ok(None) => return,
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mc, &msg, format!("{:?}", error)).await;
+				return;
+			},
+		};
+		let updated_roles = guild_roles(&state, guild_id.get()).await;
+		info!("onboarding config updated: {:?} ({})", item, guild_id.get());
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(mc.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": item,
+				"before": item.value(&guild_config, &roles),
+				"after": item.value(&updated_config, &updated_roles),
+			}),
+		)
+		.await;
+
+		let (embed, components) = onboarding_summary(&msg, &updated_config, &updated_roles);
+		if let Err(error) = mc
+			.create_response(
+				&ctx.http,
+				CreateInteractionResponse::UpdateMessage(
+					CreateInteractionResponseMessage::new()
+						.embeds(vec![embed])
+						.components(components),
+				),
+			)
+			.await
+		{
+			error!("Error: {:?}", error);
+		}
+	}
+}
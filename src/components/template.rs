@@ -0,0 +1,187 @@
+use super::{is_guild_owner, make_custom_id, modal_value, ComponentPayload, ModalHandler};
+use crate::audit_log;
+use crate::i18n::{self, Messages};
+use crate::state::AppState;
+use crate::utils::color;
+use entity::enums::AuditAction;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serenity::all::{
+	CreateActionRow, CreateEmbed, CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage,
+	CreateModal, InputTextStyle, InteractionResponseFlags, ModalInteraction,
+};
+use serenity::async_trait;
+use serenity::client::Context;
+
+const INPUT_TEMPLATE: &str = "template";
+// モーダルのタイトルは45文字まで
+const MODAL_TITLE_MAX_CHARS: usize = 45;
+// 説明文のテンプレートは長いので、結果に表示する時はフィールドの値の制限に合わせる
+const FIELD_VALUE_MAX_CHARS: usize = 1024;
+
+// 編集するメッセージのキー
+#[derive(Serialize, Deserialize)]
+pub struct TemplatePayload(pub String);
+
+impl ComponentPayload for TemplatePayload {
+	const PREFIX: &'static str = "template";
+}
+
+// 現在のテンプレートを入力済みにした編集用のモーダルを作成する
+// 入力できる長さは、そのメッセージが表示される場所の制限に合わせる
+pub fn template_modal(msg: &Messages, key: &str, current: &str) -> CreateModal {
+	let max_chars = i18n::max_chars(key);
+	CreateModal::new(
+		make_custom_id(&TemplatePayload(key.to_string())),
+		key.chars().take(MODAL_TITLE_MAX_CHARS).collect::<String>(),
+	)
+	.components(vec![CreateActionRow::InputText(
+		CreateInputText::new(
+			InputTextStyle::Paragraph,
+			msg.get("template.modal.input_label"),
+			INPUT_TEMPLATE,
+		)
+		.value(i18n::truncate(current.to_string(), max_chars))
+		.required(false)
+		.max_length(max_chars as u16),
+	)])
+}
+
+async fn send_response(ctx: &Context, mi: &ModalInteraction, embed: CreateEmbed) {
+	if let Err(error) = mi
+		.create_response(
+			&ctx.http,
+			CreateInteractionResponse::Message(
+				CreateInteractionResponseMessage::new()
+					.add_embed(embed)
+					.flags(InteractionResponseFlags::EPHEMERAL),
+			),
+		)
+		.await
+	{
+		error!("Error: {:?}", error);
+	}
+}
+
+async fn send_error(ctx: &Context, mi: &ModalInteraction, msg: &Messages, message: impl Into<String>) {
+	send_response(
+		ctx,
+		mi,
+		CreateEmbed::new()
+			.title(msg.get("common.error.title"))
+			.description(message)
+			.color(color::failed_color()),
+	)
+	.await;
+}
+
+pub struct TemplateHandler;
+
+#[async_trait]
+impl ModalHandler for TemplateHandler {
+	type Payload = TemplatePayload;
+
+	async fn handle(&self, ctx: &Context, mi: &ModalInteraction, payload: Self::Payload) {
+		let TemplatePayload(key) = payload;
+
+		let guild_id = match mi.guild_id {
+			Some(v) => v,
+			None => {
+				error!("Error: Not found guild id");
+				return;
+			},
+		};
+		// 応答はインタラクションの言語、テンプレートはサーバーの言語のものを使う
+		let reply_msg = Messages::for_guild(ctx, guild_id, Some(&mi.locale)).await;
+		match is_guild_owner(ctx, guild_id, mi.user.id) {
+			Some(true) => {},
+			Some(false) => {
+				send_error(ctx, mi, &reply_msg, reply_msg.get("template.owner_only")).await;
+				return;
+			},
+			None => {
+				error!("Not found Guild");
+				return;
+			},
+		}
+		if !i18n::keys().contains(&key.as_str()) {
+			send_error(ctx, mi, &reply_msg, reply_msg.get("template.not_found")).await;
+			return;
+		}
+
+		let msg = Messages::for_guild(ctx, guild_id, None).await;
+		let input = modal_value(mi, INPUT_TEMPLATE).unwrap_or_default().trim();
+		let max_chars = i18n::max_chars(&key);
+		if input.chars().count() > max_chars {
+			send_error(
+				ctx,
+				mi,
+				&reply_msg,
+				reply_msg.format("template.too_long", &[("max", max_chars.to_string())]),
+			)
+			.await;
+			return;
+		}
+		let before = msg.overridden(&key).map(|v| v.to_string());
+		// 空か初期値と同じなら上書きを削除する
+		let after = if input.is_empty() || i18n::default_template(msg.locale(), &key) == Some(input) {
+			None
+		} else {
+			Some(input.to_string())
+		};
+
+		let state = AppState::get(ctx).await;
+		let guild_configs = state.guild_configs();
+		match guild_configs.find(guild_id.get()).await {
+			Ok(Some(_)) => {},
+			Ok(None) => {
+				send_error(ctx, mi, &reply_msg, reply_msg.get("common.guild_not_registered")).await;
+				return;
+			},
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				send_error(ctx, mi, &reply_msg, format!("{:?}", error)).await;
+				return;
+			},
+		}
+		let result = match &after {
+			Some(template) => guild_configs.save_template(guild_id.get(), &key, template).await,
+			None => guild_configs.delete_template(guild_id.get(), &key).await,
+		};
+		if let Err(error) = result {
+			error!("DB Error: {:?}", error);
+			send_error(ctx, mi, &reply_msg, format!("{:?}", error)).await;
+			return;
+		}
+		info!("message template updated: {} ({})", key, guild_id.get());
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			Some(mi.user.id.get()),
+			None,
+			AuditAction::ConfigUpdate,
+			json!({
+				"item": "template",
+				"key": key,
+				"before": before,
+				"after": after,
+			}),
+		)
+		.await;
+
+		let embed = CreateEmbed::new()
+			.title(reply_msg.get("common.done.title"))
+			.field(reply_msg.get("template.key_field"), format!("`{}`", key), false)
+			.color(color::success_color());
+		let embed = match after {
+			Some(template) => embed.description(reply_msg.get("template.updated")).field(
+				reply_msg.get("template.template_field"),
+				i18n::truncate(template, FIELD_VALUE_MAX_CHARS),
+				false,
+			),
+			None => embed.description(reply_msg.get("template.reset")),
+		};
+		send_response(ctx, mi, embed).await;
+	}
+}
@@ -0,0 +1,76 @@
+use super::{ComponentHandler, ComponentPayload};
+use crate::events::ready_event::{conf_process, conf_result_send_message, reject_vote_process};
+use crate::utils::enums::ConfResponseType;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serenity::all::ComponentInteraction;
+use serenity::async_trait;
+use serenity::client::Context;
+
+// 申請却下ボタン (申請されたユーザーID)
+#[derive(Serialize, Deserialize)]
+pub struct RejectVotePayload(pub u64);
+
+impl ComponentPayload for RejectVotePayload {
+	const PREFIX: &'static str = "reject";
+}
+
+pub struct RejectVoteHandler;
+
+#[async_trait]
+impl ComponentHandler for RejectVoteHandler {
+	type Payload = RejectVotePayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload) {
+		let RejectVotePayload(user_id) = payload;
+		info!("{}", user_id);
+
+		let guild_id = match mc.guild_id {
+			Some(v) => v.get(),
+			None => {
+				error!("Error: Not found guild id");
+				return;
+			},
+		};
+
+		reject_vote_process(ctx, guild_id, user_id, mc.user.id.get()).await;
+	}
+}
+
+// サブアカウント承認ボタン (申請者のユーザーID, 申請されたユーザーID)
+#[derive(Serialize, Deserialize)]
+pub struct ConfirmPayload(pub u64, pub u64);
+
+impl ComponentPayload for ConfirmPayload {
+	const PREFIX: &'static str = "conf";
+}
+
+pub struct ConfirmHandler;
+
+#[async_trait]
+impl ComponentHandler for ConfirmHandler {
+	type Payload = ConfirmPayload;
+
+	async fn handle(&self, ctx: &Context, mc: &ComponentInteraction, payload: Self::Payload) {
+		let ConfirmPayload(requester_id, user_id) = payload;
+		info!("{}", requester_id);
+		info!("{}", mc.user.id.get());
+
+		if mc.user.id.get() == requester_id {
+			info!("user_id is equal pressed button user id");
+			conf_result_send_message(ctx, mc, ConfResponseType::EqualErr, "").await;
+			return;
+		}
+
+		let guild_id = match mc.guild_id {
+			Some(v) => v.get(),
+			None => {
+				error!("Error: Not found guild id");
+				return;
+			},
+		};
+
+		info!("{}", user_id);
+		conf_process(ctx, mc, guild_id, user_id, mc.user.id.get()).await;
+	}
+}
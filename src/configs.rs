@@ -1,16 +1,78 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandScope {
+	Global,
+	#[default]
+	Guild,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeechToTextBackend {
+	#[default]
+	Deepgram,
+	// whisper.cppでローカルに文字起こしする (`--features whisper` が必要)
+	Whisper,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SpeechToTextConfig {
+	#[serde(default)]
+	backend: SpeechToTextBackend,
+	// whisper.cppのggmlモデルファイル (ggml-small.bin など)
+	#[serde(default)]
+	whisper_model_path: String,
+	// 0の場合はCPUのコア数を使う
+	#[serde(default)]
+	whisper_threads: u16,
+}
+
+impl SpeechToTextConfig {
+	pub fn get_backend(&self) -> SpeechToTextBackend {
+		self.backend
+	}
+
+	pub fn get_whisper_model_path(&self) -> &String {
+		&self.whisper_model_path
+	}
+
+	pub fn get_whisper_threads(&self) -> u16 {
+		self.whisper_threads
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ConfigData {
 	token: String,
 	bot_id: u64,
 	owner_id: u64,
 
+	#[serde(default)]
+	command_scope: CommandScope,
+
+	// ボタンのcustom_idの署名に使う鍵 (未設定の場合はtokenを使う)
+	#[serde(default)]
+	component_secret: String,
+
+	// "mysql://", "postgres://", "sqlite:" から始まる場合はURLとしてそのまま使う
+	// ホスト名の場合は以前と同じくdb_username等と組み合わせてMySQLに接続する
 	db_url: String,
+	#[serde(default)]
 	db_username: String,
+	#[serde(default)]
 	db_password: String,
+	#[serde(default)]
 	db_database: String,
+	// 起動時に未適用のマイグレーションがあれば自動で適用する (falseの場合は起動しない)
+	#[serde(default)]
+	auto_migrate: bool,
 
+	#[serde(default)]
+	speech_to_text: SpeechToTextConfig,
+	// speech_to_text.backendがdeepgramの場合のみ使う
+	#[serde(default)]
 	deepgram_token: String,
 	chatgpt_token: String,
 }
@@ -28,13 +90,41 @@ impl ConfigData {
 		&self.owner_id
 	}
 
+	pub fn get_command_scope(&self) -> CommandScope {
+		self.command_scope
+	}
+
+	pub fn get_component_secret(&self) -> &String {
+		if self.component_secret.is_empty() {
+			&self.token
+		} else {
+			&self.component_secret
+		}
+	}
+
 	pub fn get_db_url(&self) -> String {
+		// "mysql:3306" のようなホスト名と区別するため、mysql/postgresは "://" まで見る
+		if ["mysql://", "postgres://", "postgresql://", "sqlite:"]
+			.iter()
+			.any(|v| self.db_url.starts_with(v))
+		{
+			return self.db_url.clone();
+		}
+
 		format!(
 			"mysql://{}:{}@{}/{}",
 			self.db_username, self.db_password, self.db_url, self.db_database
 		)
 	}
 
+	pub fn get_auto_migrate(&self) -> bool {
+		self.auto_migrate
+	}
+
+	pub fn get_speech_to_text(&self) -> &SpeechToTextConfig {
+		&self.speech_to_text
+	}
+
 	pub fn get_deepgram_token(&self) -> &String {
 		&self.deepgram_token
 	}
@@ -5,3 +5,4 @@ pub mod message_event;
 pub mod message_remove_event;
 pub mod ready_event;
 pub mod route;
+pub mod voice_state_event;
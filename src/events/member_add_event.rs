@@ -1,10 +1,13 @@
+use crate::audit_log;
+use crate::i18n::{account_type_key, user_args, Messages};
+use crate::onboarding;
+use crate::state::AppState;
 use crate::utils::convert::{flatten_result_option, format_discord_username};
-use crate::utils::{color, convert, glacialeur};
-use crate::STATIC_COMPONENTS;
-use entity::enums::AccountType;
-use entity::{ConfirmedAccountBehavior, GuildConfigBehavior, MainAccount, SubAccount, UserData};
+use crate::utils::{color, convert};
+use chrono::Utc;
+use entity::enums::{AccountType, AuditAction};
 use log::{error, info, warn};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, ModelTrait, QueryFilter};
+use serde_json::json;
 use serenity::all::{CreateEmbed, CreateMessage};
 use serenity::client::Context;
 use serenity::model::guild::Member;
@@ -15,10 +18,8 @@ pub async fn execute(ctx: Context, guild_id: GuildId, new_member: Member) {
 	info!("new member!");
 	info!("  username: {}", format_discord_username(&new_member.user));
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = GuildConfigBehavior::find_by_id(guild_id.get()).one(mysql_client).await;
-	std::mem::drop(lsc);
+	let state = AppState::get(&ctx).await;
+	let guild_config = state.guild_configs().find(guild_id.get()).await;
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
 		return;
@@ -32,15 +33,16 @@ pub async fn execute(ctx: Context, guild_id: GuildId, new_member: Member) {
 		info!("this guild not enabled white list");
 		return;
 	}
+	let msg = Messages::for_guild(&ctx, guild_id, None).await;
 	if new_member.user.bot {
 		info!("is bot");
 		if let Some(log_channel_id) = guild_config.log_channel_id {
-			send_bot_message(&ctx, log_channel_id, &new_member.user).await;
+			send_bot_message(&ctx, &msg, log_channel_id.get(), &new_member.user).await;
 		} else {
 			warn!("log channel is not found");
 		}
 		if let Some(role_id) = guild_config.bot_role_id {
-			if let Err(error) = new_member.add_role(&ctx.http, role_id).await {
+			if let Err(error) = new_member.add_role(&ctx.http, role_id.get()).await {
 				error!("Error: {:?}", error);
 			}
 		} else {
@@ -49,123 +51,100 @@ pub async fn execute(ctx: Context, guild_id: GuildId, new_member: Member) {
 		return;
 	}
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
 	let member_account = flatten_result_option(
-		ConfirmedAccountBehavior::find_by_id(new_member.user.id.get())
-			.filter(entity::confirmed_account::Column::GuildId.eq(guild_config.uid))
-			.one(mysql_client)
+		state
+			.accounts()
+			.confirmed(guild_config.uid.get(), new_member.user.id.get())
 			.await,
 	);
-	std::mem::drop(lsc);
 	if let Err(error) = member_account {
 		// member kick when if not exist account from db.
 		error!("DB Error: {:?}", error);
 
 		if let Some(log_channel_id) = guild_config.log_channel_id {
-			send_kicked_message(&ctx, log_channel_id, &new_member.user).await;
+			send_kicked_message(&ctx, &msg, log_channel_id.get(), &new_member.user).await;
 		} else {
 			warn!("log channel is not found");
 		}
 
 		if let Err(kick_error) = new_member.kick(&ctx.http).await {
 			error!("Error: {:?}", kick_error);
+		} else {
+			audit_log::record(
+				&state,
+				guild_id.get(),
+				None,
+				Some(new_member.user.id.get()),
+				AuditAction::MemberKick,
+				json!({ "reason": "not_confirmed" }),
+			)
+			.await;
 		}
 		return;
 	}
 	let member_account = member_account.unwrap();
 
-	if let Some(log_channel_id) = guild_config.log_channel_id {
-		send_success_message(&ctx, log_channel_id, &member_account.account_type, &new_member.user).await;
-	} else {
-		warn!("log channel is not found");
-	}
-
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = member_account.clone().delete(mysql_client).await {
-		error!("DB Error: {:?}", error);
-	}
-	let mut g_str: Option<String> = None;
-	if matches!(member_account.account_type, AccountType::Main) {
-		let main_account = MainAccount {
-			uid: member_account.uid,
-			name: member_account.name,
-			guild_id: member_account.guild_id,
-			version: 1 << 3,
-			join_date: new_member
-				.joined_at
-				.map(|v| v.to_utc())
-				.unwrap_or_else(|| chrono::Utc::now()),
-			is_server_creator: false,
-			is_leaved: false,
-		};
-		let insert_res = main_account.into_active_model().insert(mysql_client).await;
-		if let Err(error) = insert_res {
+	let join_date = new_member.joined_at.map(|v| v.to_utc()).unwrap_or_else(|| Utc::now());
+	let admission = match state
+		.account_service()
+		.admit(&member_account, join_date, guild_id.created_at().timestamp())
+		.await
+	{
+		Ok(v) => v,
+		Err(error) => {
+			// トランザクションは戻っているので、承認済みのまま再入鯖で再度処理できる
 			error!("DB Error: {:?}", error);
 			return;
-			// TODO: もしエラーだった場合のリカバリを考える
-		}
-		let main_account = insert_res.unwrap();
-		g_str = Some(glacialeur::generate(
-			main_account.uid,
-			main_account.version,
-			main_account.join_date.timestamp() - guild_id.created_at().timestamp(),
-		));
-	} else {
-		let sub_account = SubAccount {
-			uid: member_account.uid,
-			name: member_account.name,
-			guild_id: member_account.guild_id,
-			join_date: new_member
-				.joined_at
-				.map(|v| v.to_utc())
-				.unwrap_or_else(|| chrono::Utc::now()),
-			main_uid: member_account.main_uid.unwrap(),
-			first_cert: member_account.first_cert.unwrap(),
-			second_cert: member_account.second_cert,
-		};
-		if let Err(error) = sub_account.into_active_model().insert(mysql_client).await {
+		},
+	};
+
+	// ロールの付与はコミット後に行い、失敗した場合は登録を承認済みに戻して、付与済みのロールを外す
+	if let Err(error) = onboarding::grant_roles(&ctx, &guild_config, &new_member, &member_account.account_type).await {
+		error!("Error: {:?}", error);
+		if let Err(error) = state.account_service().revert_admission(&admission).await {
 			error!("DB Error: {:?}", error);
 		}
-	}
-	std::mem::drop(lsc);
-
-	if let Some(role_id) = guild_config.auth_role_id {
-		if let Err(error) = new_member.add_role(&ctx.http, role_id).await {
+		if let Err(error) = onboarding::revoke_roles(&ctx, &new_member, &member_account.account_type).await {
 			error!("Error: {:?}", error);
 		}
-	} else {
-		warn!("auth_role_id is none");
+		return;
 	}
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let user_data = UserData {
-		uid: member_account.uid,
-		glacialeur: g_str,
-		call_name: None,
-		gender: None,
-		chat_message_count: None,
-	};
-	if let Err(error) = user_data.into_active_model().insert(mysql_client).await {
-		error!("DB Error: {:?}", error);
+	audit_log::record(
+		&state,
+		guild_id.get(),
+		None,
+		Some(member_account.uid.get()),
+		AuditAction::MemberAdmit,
+		json!({
+			"account_type": format!("{:?}", member_account.account_type),
+			"main_uid": member_account.main_uid.map(|v| v.to_string()),
+			"glacialeur": admission.glacialeur,
+		}),
+	)
+	.await;
+
+	if let Some(log_channel_id) = guild_config.log_channel_id {
+		send_success_message(&ctx, &msg, log_channel_id.get(), &member_account.account_type, &new_member.user).await;
+	} else {
+		warn!("log channel is not found");
 	}
 
-	std::mem::drop(lsc);
+	onboarding::send_welcome(&ctx, &guild_config, &new_member).await;
 }
 
-async fn send_bot_message(ctx: &Context, channel_id: u64, usr: &User) {
+async fn send_bot_message(ctx: &Context, msg: &Messages, channel_id: u64, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("Botが追加されました")
-					.description("以下のBotが追加されました。")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
+					.title(msg.format("member_add.bot.title", &args))
+					.description(msg.format("member_add.bot.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::normal_color()),
 			),
@@ -176,17 +155,18 @@ async fn send_bot_message(ctx: &Context, channel_id: u64, usr: &User) {
 	}
 }
 
-async fn send_kicked_message(ctx: &Context, channel_id: u64, usr: &User) {
+async fn send_kicked_message(ctx: &Context, msg: &Messages, channel_id: u64, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("ブロックされました")
-					.description("以下のユーザーは未承認なので入ることができません。")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
+					.title(msg.format("member_add.kicked.title", &args))
+					.description(msg.format("member_add.kicked.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::failed_color()),
 			),
@@ -197,18 +177,19 @@ async fn send_kicked_message(ctx: &Context, channel_id: u64, usr: &User) {
 	}
 }
 
-async fn send_success_message(ctx: &Context, channel_id: u64, a_type: &AccountType, usr: &User) {
+async fn send_success_message(ctx: &Context, msg: &Messages, channel_id: u64, a_type: &AccountType, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("許可されました")
-					.description("以下のユーザーは承認済みのため入鯖を許可しました。")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
-					.field("アカウントタイプ", a_type.to_string(), true)
+					.title(msg.format("member_add.admitted.title", &args))
+					.description(msg.format("member_add.admitted.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
+					.field(msg.get("field.account_type"), msg.get(account_type_key(a_type)), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::success_color()),
 			),
@@ -1,10 +1,12 @@
+use crate::audit_log;
+use crate::i18n::{user_args, Messages};
+use crate::services::Leave;
+use crate::state::AppState;
 use crate::utils::convert::format_discord_username;
 use crate::utils::{color, convert};
-use crate::STATIC_COMPONENTS;
-use entity::{GuildConfigBehavior, MainAccountBehavior, SubAccountBehavior};
+use entity::enums::AuditAction;
 use log::{error, info, warn};
-use sea_orm::ActiveValue::Set;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, ModelTrait, QueryFilter};
+use serde_json::json;
 use serenity::all::{CreateEmbed, CreateMessage};
 use serenity::client::Context;
 use serenity::model::guild::Member;
@@ -15,10 +17,8 @@ pub async fn execute(ctx: Context, guild_id: GuildId, user: User, member_data_if
 	info!("member removed");
 	info!("  username: {}", format_discord_username(&user));
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = GuildConfigBehavior::find_by_id(guild_id.get()).one(mysql_client).await;
-	std::mem::drop(lsc);
+	let state = AppState::get(&ctx).await;
+	let guild_config = state.guild_configs().find(guild_id.get()).await;
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
 		return;
@@ -32,65 +32,67 @@ pub async fn execute(ctx: Context, guild_id: GuildId, user: User, member_data_if
 		info!("this guild not enabled leave ban");
 		return;
 	}
+	let msg = Messages::for_guild(&ctx, guild_id, None).await;
 
 	if user.bot {
 		info!("user is bot");
 		if let Some(log_channel_id) = guild_config.log_channel_id {
-			send_bot_message(&ctx, log_channel_id, &user).await;
+			send_bot_message(&ctx, &msg, log_channel_id.get(), &user).await;
 		} else {
 			warn!("log channel is not found");
 		}
 		return;
 	}
 
-	let mut is_sub = false;
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let mem_account = SubAccountBehavior::find_by_id(user.id.get())
-		.filter(entity::sub_account::Column::GuildId.eq(guild_id.get()))
-		.one(mysql_client)
-		.await;
-	if let Ok(Some(mem_account)) = mem_account {
-		is_sub = true;
-		if let Err(error) = mem_account.delete(mysql_client).await {
+	// 先に登録を更新し、コミット後に紐づくサブアカウントをキックする
+	let (is_sub, sub_uids) = match state.account_service().leave(guild_id.get(), user.id.get()).await {
+		Ok(Leave::Sub) => (true, vec![]),
+		Ok(Leave::Main { sub_uids }) => (false, sub_uids),
+		Ok(Leave::NotFound) => {
+			warn!("Not found member account.");
+			(false, vec![])
+		},
+		Err(error) => {
 			error!("DB Error: {:?}", error);
-		}
-	} else if let Ok(None) = mem_account {
-		warn!("Not found member account from sub.");
-	} else if let Err(error) = mem_account {
-		error!("DB Error: {:?}", error);
-	}
+			(false, vec![])
+		},
+	};
 
-	let mem_accounts = MainAccountBehavior::find_by_id(user.id.get())
-		.find_with_related(entity::sub_account::Entity)
-		.filter(entity::sub_account::Column::GuildId.eq(guild_id.get()))
-		.all(mysql_client)
-		.await;
-	if let Ok(mem_accounts) = mem_accounts {
-		let (mem_account, main_sub_accounts) = mem_accounts[0].to_owned();
-		for main_sub_account in main_sub_accounts {
-			if let Err(error) = guild_id.kick(&ctx.http, main_sub_account.uid).await {
-				error!("{}", error);
-			}
-		}
-
-		let mut mem_account = mem_account.into_active_model();
-		mem_account.is_leaved = Set(true);
-		if let Err(error) = mem_account.update(mysql_client).await {
-			error!("DB Error: {:?}", error);
+	for sub_uid in sub_uids {
+		if let Err(error) = guild_id.kick(&ctx.http, sub_uid).await {
+			error!("{}", error);
+			continue;
 		}
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			None,
+			Some(sub_uid),
+			AuditAction::MemberKick,
+			json!({ "reason": "main_account_left", "main_uid": user.id.get().to_string() }),
+		)
+		.await;
 	}
-	std::mem::drop(lsc);
 
 	if is_sub {
+		audit_log::record(
+			&state,
+			guild_id.get(),
+			None,
+			Some(user.id.get()),
+			AuditAction::MemberRemove,
+			json!({ "reason": "sub_account_left" }),
+		)
+		.await;
+
 		if let Some(log_channel_id) = guild_config.log_channel_id {
-			send_remove_message(&ctx, log_channel_id, &user).await;
+			send_remove_message(&ctx, &msg, log_channel_id.get(), &user).await;
 		} else {
 			warn!("log channel is not found");
 		}
 	} else {
 		if let Some(log_channel_id) = guild_config.log_channel_id {
-			send_ban_message(&ctx, log_channel_id, &user).await;
+			send_ban_message(&ctx, &msg, log_channel_id.get(), &user).await;
 		} else {
 			warn!("log channel is not found");
 		}
@@ -99,22 +101,33 @@ pub async fn execute(ctx: Context, guild_id: GuildId, user: User, member_data_if
 			info!("member data found!");
 			if let Err(error) = member.ban(&ctx.http, 0).await {
 				error!("{}", error);
+			} else {
+				audit_log::record(
+					&state,
+					guild_id.get(),
+					None,
+					Some(user.id.get()),
+					AuditAction::MemberBan,
+					json!({ "reason": "leave_ban" }),
+				)
+				.await;
 			}
 		}
 	}
 }
 
-async fn send_bot_message(ctx: &Context, channel_id: u64, usr: &User) {
+async fn send_bot_message(ctx: &Context, msg: &Messages, channel_id: u64, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("Botをサーバーから削除しました")
-					.description("以下のBotを削除しました。")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
+					.title(msg.format("member_remove.bot.title", &args))
+					.description(msg.format("member_remove.bot.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::normal_color()),
 			),
@@ -125,17 +138,18 @@ async fn send_bot_message(ctx: &Context, channel_id: u64, usr: &User) {
 	}
 }
 
-async fn send_remove_message(ctx: &Context, channel_id: u64, usr: &User) {
+async fn send_remove_message(ctx: &Context, msg: &Messages, channel_id: u64, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("サーバーから削除しました")
-					.description("以下のユーザーを削除しました。またサーバーに入れる場合は再度申請をしてください")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
+					.title(msg.format("member_remove.removed.title", &args))
+					.description(msg.format("member_remove.removed.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::failed_color()),
 			),
@@ -146,17 +160,18 @@ async fn send_remove_message(ctx: &Context, channel_id: u64, usr: &User) {
 	}
 }
 
-async fn send_ban_message(ctx: &Context, channel_id: u64, usr: &User) {
+async fn send_ban_message(ctx: &Context, msg: &Messages, channel_id: u64, usr: &User) {
+	let args = user_args(usr);
 	let log_channel = ChannelId::from(channel_id);
 	if let Err(error) = log_channel
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("サーバーを抜けました")
-					.description("以下のユーザーはサーバーを抜けたためBANされました")
-					.field("ID", usr.id.to_string(), true)
-					.field("ユーザー名", convert::format_discord_username(usr), true)
+					.title(msg.format("member_remove.banned.title", &args))
+					.description(msg.format("member_remove.banned.description", &args))
+					.field(msg.get("field.id"), usr.id.to_string(), true)
+					.field(msg.get("field.username"), convert::format_discord_username(usr), true)
 					.thumbnail(usr.avatar_url().unwrap_or_else(|| "".to_string()))
 					.color(color::critical_color()),
 			),
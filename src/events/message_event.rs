@@ -1,9 +1,9 @@
 use crate::chat::{create_user_message, getchat_responce};
+use crate::state::AppState;
 use crate::utils::convert::format_discord_username;
 use crate::utils::glacialeur;
-use crate::{commands, exit, STATIC_COMPONENTS};
+use crate::{commands, exit};
 use log::{debug, error, info};
-use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, PaginatorTrait};
 use serenity::all::{CacheHttp, CreateMessage, EditMessage};
 use serenity::client::Context;
 use serenity::http::Http;
@@ -19,20 +19,18 @@ struct UserData {
 pub async fn execute(ctx: Context, message: Message) {
 	message_log(&message, &ctx).await;
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let config = lsc.get_config();
-	if message.author.id.get() != *config.get_owner_id()
+	let state = AppState::get(&ctx).await;
+	if message.author.id.get() != *state.config().get_owner_id()
 	/*owner userid*/
 	{
 		return;
 	}
-	std::mem::drop(lsc);
 
 	if message.content == "estella.logout" {
 		info!("logging out...");
-		exit(true).await;
+		exit(&state, true).await;
 	} else if message.content.starts_with("estella.test") {
-		test().await;
+		test(&state).await;
 	} else if message.content.starts_with("estella.rep") {
 		ping(&ctx, message).await;
 	} else if message.content.starts_with("estella.create") {
@@ -41,29 +39,28 @@ pub async fn execute(ctx: Context, message: Message) {
 		// estella.delete (command_id)
 		delete(message, &ctx.http).await;
 	} else if message.content.starts_with("estella.g_init") {
-		guild_init(message.guild_id.unwrap().get()).await;
+		guild_init(&state, message.guild_id.unwrap().get()).await;
 	} else if message.content.starts_with("estella.insert") {
 		// estella.insert (uid) (name) (version) (is_sc) (is_leave)
-		insert(&ctx, message).await;
+		insert(&ctx, &state, message).await;
 	} else if message.content.starts_with("estella.sub_insert") {
 		// estella.sub_insert (uid) (name) (main_uid)
-		insert_sub(&ctx, message).await;
+		insert_sub(&ctx, &state, message).await;
 	} else if message.content.starts_with("estella.chat_test") {
 		// estella.chat_test (msg)
-		test_chat(&ctx, message).await;
+		test_chat(&ctx, &state, message).await;
 	}
 }
 
-async fn test_chat(ctx: &Context, message: Message) {
+async fn test_chat(ctx: &Context, state: &AppState, message: Message) {
 	let message_rep = message.content.replace("estella.chat_test", "");
 	let message_split = message_rep.trim().split(' ');
 	let message_vec: Vec<&str> = message_split.collect::<Vec<&str>>();
 
-	let comp_lock = STATIC_COMPONENTS.lock().await;
-	let prev_id = comp_lock.get_prev_id().map(|v| v.clone());
-	std::mem::drop(comp_lock);
+	let prev_id = state.prev_id().await;
 
 	let (data, id) = getchat_responce(
+		state,
 		message.author.id.get(),
 		create_user_message(
 			message_vec[3..].join(" "),
@@ -76,9 +73,7 @@ async fn test_chat(ctx: &Context, message: Message) {
 		prev_id
 	).await.unwrap();
 
-	let mut comp_lock = STATIC_COMPONENTS.lock().await;
-	comp_lock.set_prev_id(id);
-	std::mem::drop(comp_lock);
+	state.set_prev_id(id).await;
 
 	message.channel_id
 		.send_message(ctx, CreateMessage::new().content(data.message)).await.unwrap();
@@ -103,7 +98,7 @@ async fn message_log(message: &Message, cache: impl CacheHttp) {
 	);
 }
 
-async fn insert_sub(ctx: &Context, message: Message) {
+async fn insert_sub(ctx: &Context, state: &AppState, message: Message) {
 	let message_rep = message.content.replace("estella.sub_insert", "");
 	let message_split = message_rep.trim().split(' ');
 	let message_vec: Vec<&str> = message_split.collect::<Vec<&str>>();
@@ -123,21 +118,19 @@ async fn insert_sub(ctx: &Context, message: Message) {
 	let user_data = message.guild_id.unwrap().member(&ctx.http, user_id).await.unwrap();
 
 	let insert_data = entity::SubAccount {
-		uid: user_id,
+		uid: user_id.into(),
 		name: message_vec[1].to_string(),
-		guild_id: message.guild_id.unwrap().get(),
+		guild_id: message.guild_id.unwrap().get().into(),
 		join_date: user_data
 			.joined_at
 			.unwrap_or(message.guild_id.unwrap().created_at())
 			.to_utc(),
 		main_uid: message_vec[2].parse().expect("Coundnt parse main_uid"),
-		first_cert: message.author.id.get(),
+		first_cert: message.author.id.get().into(),
 		second_cert: None,
 	};
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let insert_res = insert_data.into_active_model().insert(mysql_client).await;
+	let insert_res = state.accounts().insert_sub(insert_data).await;
 	if let Err(error) = insert_res {
 		error!("DB Error: {:?}", error);
 		return;
@@ -151,13 +144,12 @@ async fn insert_sub(ctx: &Context, message: Message) {
 		gender: None,
 		chat_message_count: None,
 	};
-	if let Err(error) = user_data.into_active_model().insert(mysql_client).await {
+	if let Err(error) = state.user_data().insert(user_data).await {
 		error!("DB Error: {:?}", error);
 	}
-	std::mem::drop(lsc);
 }
 
-async fn insert(ctx: &Context, message: Message) {
+async fn insert(ctx: &Context, state: &AppState, message: Message) {
 	let message_rep = message.content.replace("estella.insert", "");
 	let message_split = message_rep.trim().split(' ');
 	let message_vec: Vec<&str> = message_split.collect::<Vec<&str>>();
@@ -177,9 +169,9 @@ async fn insert(ctx: &Context, message: Message) {
 	let user_data = message.guild_id.unwrap().member(&ctx.http, user_id).await.unwrap();
 
 	let insert_data = entity::MainAccount {
-		uid: user_id,
+		uid: user_id.into(),
 		name: message_vec[1].to_string(),
-		guild_id: message.guild_id.unwrap().get(),
+		guild_id: message.guild_id.unwrap().get().into(),
 		version: message_vec[2].parse().expect("Coundnt parse version"),
 		join_date: user_data
 			.joined_at
@@ -189,14 +181,17 @@ async fn insert(ctx: &Context, message: Message) {
 		is_leaved: message_vec[4].parse().expect("Coundnt parse is_leaved"),
 	};
 	let g_str = glacialeur::generate(
-		insert_data.uid,
-		insert_data.version,
+		insert_data.uid.get(),
+		insert_data.version.get(),
 		insert_data.join_date.timestamp() - message.guild_id.unwrap().created_at().timestamp(),
 	);
+	if let Err(error) = g_str {
+		error!("Glacialeur Error: {:?}", error);
+		return;
+	}
+	let g_str = g_str.unwrap();
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let insert_res = insert_data.into_active_model().insert(mysql_client).await;
+	let insert_res = state.accounts().insert_main(insert_data).await;
 	if let Err(error) = insert_res {
 		error!("DB Error: {:?}", error);
 		return;
@@ -210,38 +205,36 @@ async fn insert(ctx: &Context, message: Message) {
 		gender: None,
 		chat_message_count: None,
 	};
-	if let Err(error) = user_data.into_active_model().insert(mysql_client).await {
+	if let Err(error) = state.user_data().insert(user_data).await {
 		error!("DB Error: {:?}", error);
 	}
-	std::mem::drop(lsc);
 }
 
-async fn guild_init(guild_id: u64) {
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-
+async fn guild_init(state: &AppState, guild_id: u64) {
 	let guild_config = entity::GuildConfig {
-		uid: guild_id,
+		uid: guild_id.into(),
 		white_list: false,
 		leave_ban: false,
 		log_channel_id: None,
 		auth_role_id: None,
 		bot_role_id: None,
 		send_ai_chat_channel_id: None,
+		locale: None,
+		welcome_channel_id: None,
+		welcome_dm: false,
+		rules_accept: false,
+		voice_wake_words: None,
+		voice_interjection_rate: 20,
+		voice_idle_topic_seconds: 300,
+		voice_style_id: None,
 	};
-	if let Err(error) = guild_config.into_active_model().insert(mysql_client).await {
+	if let Err(error) = state.guild_configs().insert(guild_config).await {
 		error!("DB Error: {:?}", error);
 	}
-	std::mem::drop(lsc);
 }
 
-async fn test() {
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-
-	let res = entity::UserDataBehavior::find().count(mysql_client).await;
-
-	std::mem::drop(lsc);
+async fn test(state: &AppState) {
+	let res = state.user_data().count().await;
 
 	if let Ok(cnt) = res {
 		info!("get value: {}", cnt);
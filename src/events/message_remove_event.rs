@@ -1,9 +1,9 @@
 use crate::events::ready_event::DEL_PENDING_USERS;
+use crate::i18n::{account_type_key, Messages};
+use crate::state::AppState;
 use crate::utils::color;
-use crate::STATIC_COMPONENTS;
 use entity::enums::AccountType;
 use log::{error, info};
-use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
 use serenity::all::{CreateEmbed, CreateMessage};
 use serenity::client::Context;
 use serenity::model::id::{ChannelId, GuildId, MessageId};
@@ -21,14 +21,11 @@ pub async fn execute(ctx: Context, channel_id: ChannelId, deleted_message_id: Me
 		guild_id.get()
 	);
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let pending_account = entity::PendingAccountBehavior::find()
-		.filter(entity::pending_account::Column::GuildId.eq(guild_id.get()))
-		.filter(entity::pending_account::Column::MessageId.eq(deleted_message_id.get()))
-		.one(mysql_client)
+	let state = AppState::get(&ctx).await;
+	let pending_account = state
+		.accounts()
+		.pending_by_message(guild_id.get(), deleted_message_id.get())
 		.await;
-	std::mem::drop(lsc);
 
 	if let Err(error) = pending_account {
 		error!("DB Error: {:?}", error);
@@ -40,27 +37,33 @@ pub async fn execute(ctx: Context, channel_id: ChannelId, deleted_message_id: Me
 
 	if matches!(pending_account.account_type, AccountType::Main) {
 		let mut lpu = DEL_PENDING_USERS.lock().await;
-		lpu.push(pending_account.uid);
+		lpu.push(pending_account.uid.get());
 		std::mem::drop(lpu);
 	}
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = pending_account.clone().delete(mysql_client).await {
+	if let Err(error) = state.accounts().delete_pending(pending_account.clone()).await {
 		error!("DB Error: {:?}", error);
 	}
-	std::mem::drop(lsc);
 
+	let msg = Messages::for_guild(&ctx, guild_id, None).await;
+	let args = [
+		("user_id", pending_account.uid.to_string()),
+		("name", pending_account.name.clone().unwrap_or_default()),
+	];
 	if let Err(error) = channel_id
 		.send_message(
 			&ctx.http,
 			CreateMessage::new().add_embed(
 				CreateEmbed::new()
-					.title("取り消されました")
-					.description("申請用メッセージが削除されたため以下の申請を自動的に取り消しました。")
-					.field("ユーザーID", pending_account.uid.to_string(), true)
-					.field("名前", pending_account.name.unwrap(), true)
-					.field("アカウントタイプ", pending_account.account_type.to_string(), true)
+					.title(msg.format("message_remove.title", &args))
+					.description(msg.format("message_remove.description", &args))
+					.field(msg.get("field.user_id"), pending_account.uid.to_string(), true)
+					.field(msg.get("field.name"), pending_account.name.unwrap_or_default(), true)
+					.field(
+						msg.get("field.account_type"),
+						msg.get(account_type_key(&pending_account.account_type)),
+						true,
+					)
 					.color(color::warning_color()),
 			),
 		)
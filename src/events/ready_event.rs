@@ -1,14 +1,18 @@
+use crate::audit_log;
+use crate::command_register::sync_commands;
+use crate::i18n::Messages;
+use crate::state::AppState;
 use crate::utils::convert::{flatten_result_option, format_discord_username};
 use crate::utils::enums::ConfResponseType;
 use crate::utils::{color, enums, glacialeur};
-use crate::STATIC_COMPONENTS;
 use chrono::Utc;
-use entity::enums::AccountType;
-use entity::{ConfirmedAccount, GuildConfigBehavior, MainAccountBehavior, PendingAccount, PendingAccountBehavior};
+use entity::enums::{AccountType, AuditAction};
+use entity::PendingAccount;
 use log::{debug, error, info};
-use sea_orm::ActiveValue::Set;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, ModelTrait, QueryFilter};
-use serenity::all::{ActivityData, ChannelId, ComponentInteraction, InteractionResponseFlags, MessageFlags, MessageId};
+use serde_json::json;
+use serenity::all::{
+	ActivityData, ChannelId, ComponentInteraction, GuildId, InteractionResponseFlags, MessageFlags, MessageId,
+};
 use serenity::builder::{
 	CreateEmbed, CreateInteractionResponse, CreateInteractionResponseFollowup,
 	CreateInteractionResponseMessage, EditMessage,
@@ -26,6 +30,10 @@ pub async fn execute(ctx: Context, data_about_bot: Ready) {
 	ctx.dnd();
 	ctx.set_activity(Some(ActivityData::playing("Starting...")));
 
+	let command_scope = AppState::get(&ctx).await.config().get_command_scope();
+	let guild_ids: Vec<_> = data_about_bot.guilds.iter().map(|v| v.id).collect();
+	sync_commands(&ctx.http, command_scope, &guild_ids).await;
+
 	for guild in data_about_bot.guilds {
 		let guild_id = guild.id;
 		debug!("id: {}", guild_id.get());
@@ -43,7 +51,10 @@ pub async fn execute(ctx: Context, data_about_bot: Ready) {
 						member.joined_at.unwrap_or_else(|| guild_id.created_at()).timestamp() -
 							guild_id.created_at().timestamp(),
 					);
-					println!("{}: {}", format_discord_username(&member.user), id);
+					match id {
+						Ok(id) => println!("{}: {}", format_discord_username(&member.user), id),
+						Err(error) => println!("{}: {}", format_discord_username(&member.user), error),
+					}
 				}
 			}
 
@@ -63,48 +74,48 @@ pub static DEL_PENDING_USERS: LazyLock<Mutex<Vec<u64>>> = LazyLock::new(|| Mutex
 
 fn check_vote_task(ctx: Context) -> JoinHandle<()> {
 	tokio::spawn(async move {
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let mysql_client = lsc.get_sql_client();
-		let select_res = PendingAccountBehavior::find()
-			.filter(entity::pending_account::Column::AccountType.eq(AccountType::Main))
-			.all(mysql_client)
-			.await;
+		let state = AppState::get(&ctx).await;
+		let select_res = state.accounts().pending_mains().await;
 		let mut pending_users = select_res.unwrap_or_else(|error| {
 			error!("Error: {:?}", error);
 			Vec::new()
 		});
-		std::mem::drop(lsc);
 		loop {
 			let mut lpu = ADD_PENDING_USERS.lock().await;
 			pending_users.append(&mut lpu);
 			std::mem::drop(lpu);
 			let mut lpu = DEL_PENDING_USERS.lock().await;
-			pending_users.retain(|v| !lpu.contains(&v.uid));
+			pending_users.retain(|v| !lpu.contains(&v.uid.get()));
 			lpu.clear();
 			std::mem::drop(lpu);
 
-			let mut del_user_id = Vec::<u64>::new();
-			for p_user in &pending_users {
-				info!(
-					"{}: {} ({:?})",
-					p_user.uid,
-					p_user.name.clone().unwrap_or_default(),
-					p_user.end_voting
-				);
-				if matches!(p_user.account_type, AccountType::Main) {
-					if p_user.end_voting.unwrap() <= Utc::now() {
-						end_vote_main_process(&ctx, p_user).await;
-						del_user_id.push(p_user.uid);
-					}
-				}
-			}
-			pending_users.retain(|v| !del_user_id.contains(&v.uid));
+			end_votes(&ctx, &mut pending_users).await;
 
 			tokio::time::sleep(Duration::from_secs(1)).await;
 		}
 	})
 }
 
+// 投票期間が終わったメインアカウントの申請を承認し、pending_usersから外す
+// 承認できなかったものは残して、次の確認で再度処理する
+pub async fn end_votes(ctx: &Context, pending_users: &mut Vec<PendingAccount>) {
+	let mut del_user_id = Vec::<u64>::new();
+	for p_user in pending_users.iter() {
+		info!(
+			"{}: {} ({:?})",
+			p_user.uid,
+			p_user.name.clone().unwrap_or_default(),
+			p_user.end_voting
+		);
+		if matches!(p_user.account_type, AccountType::Main) {
+			if p_user.end_voting.unwrap() <= Utc::now() && end_vote_main_process(ctx, p_user).await {
+				del_user_id.push(p_user.uid.get());
+			}
+		}
+	}
+	pending_users.retain(|v| !del_user_id.contains(&v.uid.get()));
+}
+
 pub async fn end_conf_sub_process(
 	ctx: &Context,
 	mc: &ComponentInteraction,
@@ -114,162 +125,171 @@ pub async fn end_conf_sub_process(
 ) {
 	info!("End confirmed!");
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = flatten_result_option(GuildConfigBehavior::find_by_id(p_user.guild_id).one(mysql_client).await);
-	std::mem::drop(lsc);
+	let state = AppState::get(ctx).await;
+	let guild_config = flatten_result_option(state.guild_configs().find(p_user.guild_id.get()).await);
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
+		typing_process.stop();
+		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
 		return;
 	}
 	let guild_config = guild_config.unwrap();
+	let msg = Messages::for_guild(ctx, GuildId::new(p_user.guild_id.get()), None).await;
 
 	let message = ctx
 		.http
 		.get_message(
-			ChannelId::from(guild_config.log_channel_id.unwrap()),
-			MessageId::from(p_user.message_id),
+			ChannelId::from(guild_config.log_channel_id.unwrap().get()),
+			MessageId::from(p_user.message_id.get()),
 		)
 		.await;
-	if let Err(ref error) = message {
+	if let Err(error) = message {
 		error!("Error: {:?}", error);
+		typing_process.stop();
+		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
 		return;
 	}
 	let mut message = message.unwrap();
 
+	// 先に登録を確定し、埋め込みの編集に失敗した場合は申請中に戻す
+	let confirmed = match state.account_service().approve_sub(p_user, cert_id).await {
+		Ok(v) => v,
+		Err(error) => {
+			error!("DB Error: {:?}", error);
+			typing_process.stop();
+			conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
+			return;
+		},
+	};
+
+	let args = [("user_id", p_user.uid.to_string()), ("name", p_user.name.clone().unwrap_or_default())];
 	if let Err(error) = message
 		.edit(
 			&ctx.http,
 			EditMessage::new().components(vec![]).embeds(vec![]).add_embed(
 				CreateEmbed::new()
-					.title("承認完了")
-					.description("以下のサブ垢が承認されました！正式に招待可能です")
-					.field("ユーザーID", p_user.uid.to_string(), true)
-					.field("名前", p_user.name.as_ref().unwrap(), true)
+					.title(msg.format("confirm.approved.title", &args))
+					.description(msg.format("confirm.approved.description", &args))
+					.field(msg.get("field.user_id"), p_user.uid.to_string(), true)
+					.field(msg.get("field.name"), p_user.name.as_ref().unwrap(), true)
 					.color(color::success_color()),
 			),
 		)
 		.await
 	{
 		error!("Error: {:?}", error);
-	}
-
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = p_user.clone().delete(mysql_client).await {
-		error!("DB Error: {:?}", error);
-	} else {
-		let confirmed_data = if p_user.first_cert.is_some() {
-			ConfirmedAccount {
-				uid: p_user.uid,
-				name: p_user.name.clone().unwrap_or_default(),
-				guild_id: p_user.guild_id,
-				account_type: AccountType::Sub,
-				main_uid: p_user.main_uid,
-				first_cert: p_user.first_cert,
-				second_cert: Some(cert_id),
-			}
-		} else {
-			ConfirmedAccount {
-				uid: p_user.uid,
-				name: p_user.name.clone().unwrap_or_default(),
-				guild_id: p_user.guild_id,
-				account_type: AccountType::Sub,
-				main_uid: p_user.main_uid,
-				first_cert: Some(cert_id),
-				second_cert: None,
-			}
-		};
-		if let Err(error) = confirmed_data.into_active_model().insert(mysql_client).await {
+		if let Err(error) = state.account_service().revert_approval(p_user).await {
 			error!("DB Error: {:?}", error);
 		}
+		typing_process.stop();
+		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
+		return;
 	}
-	std::mem::drop(lsc);
+
+	audit_log::record(
+		&state,
+		confirmed.guild_id.get(),
+		Some(cert_id),
+		Some(confirmed.uid.get()),
+		AuditAction::SubApprove,
+		json!({
+			"name": confirmed.name,
+			"main_uid": confirmed.main_uid.map(|v| v.to_string()),
+			"first_cert": confirmed.first_cert.map(|v| v.to_string()),
+			"second_cert": confirmed.second_cert.map(|v| v.to_string()),
+		}),
+	)
+	.await;
 
 	typing_process.stop();
 	conf_result_send_message(ctx, mc, ConfResponseType::Success, "").await;
 }
 
-pub async fn end_vote_main_process(ctx: &Context, p_user: &PendingAccount) {
+// 承認できた場合はtrueを返す (失敗した場合は申請中のまま)
+pub async fn end_vote_main_process(ctx: &Context, p_user: &PendingAccount) -> bool {
 	info!("End vote!");
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = flatten_result_option(GuildConfigBehavior::find_by_id(p_user.guild_id).one(mysql_client).await);
-	std::mem::drop(lsc);
+	let state = AppState::get(ctx).await;
+	// 却下などで申請が無くなっている場合は、終わったものとして扱う
+	match state.accounts().pending(p_user.guild_id.get(), p_user.uid.get()).await {
+		Ok(Some(_)) => {},
+		Ok(None) => {
+			info!("pending account is already removed: {}", p_user.uid);
+			return true;
+		},
+		Err(error) => {
+			error!("DB Error: {:?}", error);
+			return false;
+		},
+	}
+	let guild_config = flatten_result_option(state.guild_configs().find(p_user.guild_id.get()).await);
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
-		return;
+		return false;
 	}
 	let guild_config = guild_config.unwrap();
+	let msg = Messages::for_guild(ctx, GuildId::new(p_user.guild_id.get()), None).await;
 
 	let message = ctx
 		.http
 		.get_message(
-			ChannelId::from(guild_config.log_channel_id.unwrap()),
-			MessageId::from(p_user.message_id),
+			ChannelId::from(guild_config.log_channel_id.unwrap().get()),
+			MessageId::from(p_user.message_id.get()),
 		)
 		.await;
 	if let Err(ref error) = message {
 		error!("Error: {:?}", error);
-		return;
+		return false;
 	}
 	let mut message = message.unwrap();
 
+	// 先に登録を確定し、埋め込みの編集に失敗した場合は申請中に戻す
+	if let Err(error) = state.account_service().approve_main(p_user).await {
+		error!("DB Error: {:?}", error);
+		return false;
+	}
+
+	let args = [("user_id", p_user.uid.to_string()), ("name", p_user.name.clone().unwrap_or_default())];
 	if let Err(error) = message
 		.edit(
 			&ctx.http,
 			EditMessage::new().components(vec![]).embeds(vec![]).add_embed(
 				CreateEmbed::new()
-					.title("投票終了")
-					.description("以下の内容を正式に登録されました！正式に招待可能です")
-					.field("ユーザーID", p_user.uid.to_string(), true)
-					.field("名前", p_user.name.as_ref().unwrap(), true)
+					.title(msg.format("vote.approved.title", &args))
+					.description(msg.format("vote.approved.description", &args))
+					.field(msg.get("field.user_id"), p_user.uid.to_string(), true)
+					.field(msg.get("field.name"), p_user.name.as_ref().unwrap(), true)
 					.color(color::success_color()),
 			),
 		)
 		.await
 	{
 		error!("Error: {:?}", error);
-	}
-
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = p_user.clone().delete(mysql_client).await {
-		error!("DB Error: {:?}", error);
-	} else {
-		let confirmed_data = ConfirmedAccount {
-			uid: p_user.uid,
-			name: p_user.name.clone().unwrap_or_default(),
-			guild_id: p_user.guild_id,
-			account_type: AccountType::Main,
-			main_uid: None,
-			first_cert: None,
-			second_cert: None,
-		};
-		if let Err(error) = confirmed_data.into_active_model().insert(mysql_client).await {
+		if let Err(error) = state.account_service().revert_approval(p_user).await {
 			error!("DB Error: {:?}", error);
 		}
+		return false;
 	}
-	std::mem::drop(lsc);
+
+	audit_log::record(
+		&state,
+		p_user.guild_id.get(),
+		None,
+		Some(p_user.uid.get()),
+		AuditAction::VoteApprove,
+		json!({ "name": p_user.name, "end_voting": p_user.end_voting.map(|v| v.to_rfc3339()) }),
+	)
+	.await;
+
+	true
 }
 
-pub async fn reject_vote_process(ctx: &Context, guild_id: u64, user_id: u64) {
+pub async fn reject_vote_process(ctx: &Context, guild_id: u64, user_id: u64, reject_id: u64) {
 	info!("Reject vote...");
-	let mut lpu = DEL_PENDING_USERS.lock().await;
-	lpu.push(user_id);
-	std::mem::drop(lpu);
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = flatten_result_option(GuildConfigBehavior::find_by_id(guild_id).one(mysql_client).await);
-	let p_user = flatten_result_option(
-		PendingAccountBehavior::find_by_id(user_id)
-			.filter(entity::pending_account::Column::GuildId.eq(guild_id))
-			.one(mysql_client)
-			.await,
-	);
-	std::mem::drop(lsc);
+	let state = AppState::get(ctx).await;
+	let guild_config = flatten_result_option(state.guild_configs().find(guild_id).await);
+	let p_user = flatten_result_option(state.accounts().pending(guild_id, user_id).await);
 
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
@@ -281,12 +301,13 @@ pub async fn reject_vote_process(ctx: &Context, guild_id: u64, user_id: u64) {
 	}
 	let guild_config = guild_config.unwrap();
 	let p_user = p_user.unwrap();
+	let msg = Messages::for_guild(ctx, GuildId::new(guild_id), None).await;
 
 	let message = ctx
 		.http
 		.get_message(
-			ChannelId::from(guild_config.log_channel_id.unwrap()),
-			MessageId::from(p_user.message_id),
+			ChannelId::from(guild_config.log_channel_id.unwrap().get()),
+			MessageId::from(p_user.message_id.get()),
 		)
 		.await;
 	if let Err(ref error) = message {
@@ -295,29 +316,47 @@ pub async fn reject_vote_process(ctx: &Context, guild_id: u64, user_id: u64) {
 	}
 	let mut message = message.unwrap();
 
+	// 先に申請を削除し、埋め込みの編集に失敗した場合は申請中に戻す
+	if let Err(error) = state.account_service().reject(&p_user).await {
+		error!("DB Error: {:?}", error);
+		return;
+	}
+
+	let args = [("user_id", p_user.uid.to_string()), ("name", p_user.name.clone().unwrap_or_default())];
 	if let Err(error) = message
 		.edit(
 			&ctx.http,
 			EditMessage::new().components(vec![]).embeds(vec![]).add_embed(
 				CreateEmbed::new()
-					.title("申請却下")
-					.description("以下の申請を取り下げました")
-					.field("ユーザーID", p_user.uid.to_string(), true)
-					.field("名前", p_user.name.as_ref().unwrap(), true)
+					.title(msg.format("vote.rejected.title", &args))
+					.description(msg.format("vote.rejected.description", &args))
+					.field(msg.get("field.user_id"), p_user.uid.to_string(), true)
+					.field(msg.get("field.name"), p_user.name.as_ref().unwrap(), true)
 					.color(color::critical_color()),
 			),
 		)
 		.await
 	{
 		error!("Error: {:?}", error);
+		if let Err(error) = state.account_service().revert_rejection(&p_user).await {
+			error!("DB Error: {:?}", error);
+		}
+		return;
 	}
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = p_user.clone().delete(mysql_client).await {
-		error!("DB Error: {:?}", error);
-	}
-	std::mem::drop(lsc);
+	let mut lpu = DEL_PENDING_USERS.lock().await;
+	lpu.push(user_id);
+	std::mem::drop(lpu);
+
+	audit_log::record(
+		&state,
+		guild_id,
+		Some(reject_id),
+		Some(p_user.uid.get()),
+		AuditAction::VoteReject,
+		json!({ "name": p_user.name, "end_voting": p_user.end_voting.map(|v| v.to_rfc3339()) }),
+	)
+	.await;
 }
 
 pub async fn conf_process(ctx: &Context, mc: &ComponentInteraction, guild_id: u64, user_id: u64, conf_id: u64) {
@@ -328,23 +367,14 @@ pub async fn conf_process(ctx: &Context, mc: &ComponentInteraction, guild_id: u6
 	}
 
 	let typing_process = mc.channel_id.start_typing(&ctx.http);
+	let log_msg = Messages::for_guild(ctx, GuildId::new(guild_id), None).await;
+	let msg = log_msg.with_locale(&mc.locale);
 
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	let guild_config = flatten_result_option(GuildConfigBehavior::find_by_id(guild_id).one(mysql_client).await);
-	let p_user = flatten_result_option(
-		PendingAccountBehavior::find_by_id(user_id)
-			.filter(entity::pending_account::Column::GuildId.eq(guild_id))
-			.one(mysql_client)
-			.await,
-	);
-	let c_user = flatten_result_option(
-		MainAccountBehavior::find_by_id(conf_id)
-			.filter(entity::main_account::Column::GuildId.eq(guild_id))
-			.one(mysql_client)
-			.await,
-	);
-	std::mem::drop(lsc);
+	let state = AppState::get(ctx).await;
+	let accounts = state.accounts();
+	let guild_config = flatten_result_option(state.guild_configs().find(guild_id).await);
+	let p_user = flatten_result_option(accounts.pending(guild_id, user_id).await);
+	let c_user = flatten_result_option(accounts.main(guild_id, conf_id).await);
 
 	if let Err(error) = guild_config {
 		error!("DB Error: {:?}", error);
@@ -366,7 +396,7 @@ pub async fn conf_process(ctx: &Context, mc: &ComponentInteraction, guild_id: u6
 		return;
 	}
 	if p_user.first_cert.is_some() || c_user.is_server_creator {
-		if p_user.first_cert.unwrap_or_else(|| 0) != conf_id {
+		if p_user.first_cert.map(|v| v.get()).unwrap_or_else(|| 0) != conf_id {
 			end_conf_sub_process(&ctx, &mc, typing_process, &p_user, conf_id).await;
 		} else {
 			typing_process.stop();
@@ -377,23 +407,23 @@ pub async fn conf_process(ctx: &Context, mc: &ComponentInteraction, guild_id: u6
 
 	let message = ctx
 		.http
-		.get_message(guild_config.log_channel_id.unwrap().into(), p_user.message_id.into())
+		.get_message(guild_config.log_channel_id.unwrap().get().into(), p_user.message_id.get().into())
 		.await;
 	if let Err(ref error) = message {
 		error!("Error: {:?}", error);
-		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, "メッセージが見つかりません").await;
+		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, msg.get("common.message_not_found")).await;
 		return;
 	}
 	let mut message = message.unwrap();
 	if message.embeds.len() == 0 {
 		error!("Error: Not found embed");
-		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, "メッセージが見つかりません").await;
+		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, msg.get("common.message_not_found")).await;
 		return;
 	}
 	let mut message_embed: Embed = message.embeds[0].clone();
 	message_embed
 		.fields
-		.push(EmbedField::new("第一承認者ID", conf_id.to_string(), true));
+		.push(EmbedField::new(log_msg.get("confirm.first_cert_field"), conf_id.to_string(), true));
 
 	if let Err(error) = message
 		.edit(
@@ -405,16 +435,27 @@ pub async fn conf_process(ctx: &Context, mc: &ComponentInteraction, guild_id: u6
 		error!("Error: {:?}", error);
 	}
 
-	let mut p_user = p_user.into_active_model();
-	p_user.first_cert = Set(Some(conf_id));
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let mysql_client = lsc.get_sql_client();
-	if let Err(error) = p_user.update(mysql_client).await {
-		error!("DB Error: {:?}", error);
-		conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
-		return;
-	}
-	std::mem::drop(lsc);
+	let p_user = match accounts.set_first_cert(p_user, conf_id).await {
+		Ok(v) => v,
+		Err(error) => {
+			error!("DB Error: {:?}", error);
+			conf_result_send_message(ctx, mc, ConfResponseType::OtherErr, error).await;
+			return;
+		},
+	};
+
+	audit_log::record(
+		&state,
+		guild_id,
+		Some(conf_id),
+		Some(p_user.uid.get()),
+		AuditAction::SubConfirm,
+		json!({
+			"name": p_user.name,
+			"main_uid": p_user.main_uid.map(|v| v.to_string()),
+		}),
+	)
+	.await;
 
 	typing_process.stop();
 	conf_result_send_message(ctx, mc, ConfResponseType::Ok, "").await;
@@ -427,6 +468,11 @@ pub async fn conf_result_send_message<E>(
 	error: E,
 ) where
 	E: std::fmt::Debug, {
+	let msg = match mc.guild_id {
+		Some(guild_id) => Messages::for_guild(ctx, guild_id, Some(&mc.locale)).await,
+		None => Messages::default().with_locale(&mc.locale),
+	};
+
 	match cr_type {
 		ConfResponseType::Ok => {
 			if let Err(error) = mc
@@ -435,8 +481,8 @@ pub async fn conf_result_send_message<E>(
 					CreateInteractionResponseFollowup::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("完了")
-								.description("承認しました！残り1人の承認が必要になります")
+								.title(msg.get("common.done.title"))
+								.description(msg.get("confirm.ok"))
 								.color(color::success_color()),
 						)
 						.flags(MessageFlags::EPHEMERAL),
@@ -454,8 +500,8 @@ pub async fn conf_result_send_message<E>(
 						CreateInteractionResponseMessage::new()
 							.add_embed(
 								CreateEmbed::new()
-									.title("エラー")
-									.description("登録者は承認できません")
+									.title(msg.get("common.error.title"))
+									.description(msg.get("confirm.equal_error"))
 									.color(color::failed_color()),
 							)
 							.flags(InteractionResponseFlags::EPHEMERAL),
@@ -473,8 +519,8 @@ pub async fn conf_result_send_message<E>(
 					CreateInteractionResponseFollowup::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("エラー")
-								.description("すでに承認されています")
+								.title(msg.get("common.error.title"))
+								.description(msg.get("confirm.exist_error"))
 								.color(color::failed_color()),
 						)
 						.flags(MessageFlags::EPHEMERAL),
@@ -491,7 +537,7 @@ pub async fn conf_result_send_message<E>(
 					CreateInteractionResponseFollowup::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("エラー")
+								.title(msg.get("common.error.title"))
 								.description(format!("{:?}", error))
 								.color(color::failed_color()),
 						)
@@ -509,8 +555,8 @@ pub async fn conf_result_send_message<E>(
 					CreateInteractionResponseFollowup::new()
 						.add_embed(
 							CreateEmbed::new()
-								.title("完了")
-								.description("承認しました！")
+								.title(msg.get("common.done.title"))
+								.description(msg.get("confirm.success"))
 								.color(color::success_color()),
 						)
 						.flags(MessageFlags::EPHEMERAL),
@@ -1,8 +1,9 @@
 use crate::events::{
 	interaction_event, member_add_event, member_remove_event, message_event, message_remove_event, ready_event,
+	voice_state_event,
 };
 use log::info;
-use serenity::all::Interaction;
+use serenity::all::{Interaction, VoiceState};
 use serenity::async_trait;
 use serenity::client::{Context, EventHandler};
 use serenity::model::channel::Message;
@@ -63,4 +64,10 @@ impl EventHandler for Router {
 		interaction_event::execute(ctx, interaction).await;
 		info!("Message Interaction created event end");
 	}
+
+	async fn voice_state_update(&self, ctx: Context, old: Option<VoiceState>, new: VoiceState) {
+		info!("Voice State Update event start");
+		voice_state_event::execute(ctx, old, new).await;
+		info!("Voice State Update event end");
+	}
 }
@@ -0,0 +1,8 @@
+use serenity::all::VoiceState;
+use serenity::client::Context;
+
+use crate::voice;
+
+pub async fn execute(ctx: Context, _old: Option<VoiceState>, new: VoiceState) {
+	voice::voice_state_update(&ctx, &new).await;
+}
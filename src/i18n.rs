@@ -0,0 +1,270 @@
+use crate::state::AppState;
+use crate::utils::convert::format_discord_username;
+use entity::enums::AccountType;
+use log::{error, warn};
+use serenity::all::{GuildId, Mentionable, User};
+use serenity::client::Context;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/*
+埋め込みなどで使う文言のカタログ
+locales/<言語>.yaml に "キー: テンプレート" で定義し、テンプレート中の {name} は送信時に置き換える
+サーバーごとの上書き (message_template) があればそちらを優先する
+*/
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Locale {
+	#[default]
+	Ja,
+	En,
+}
+
+impl Locale {
+	pub const ALL: [Locale; 2] = [Locale::Ja, Locale::En];
+
+	pub fn code(&self) -> &'static str {
+		match self {
+			Locale::Ja => "ja",
+			Locale::En => "en",
+		}
+	}
+
+	// Discordのロケール ("ja", "en-US" など) から変換する
+	pub fn from_code(code: &str) -> Option<Self> {
+		let lang = code.split('-').next().unwrap_or_default();
+		Self::ALL.into_iter().find(|v| v.code() == lang)
+	}
+}
+
+static CATALOGS: LazyLock<HashMap<Locale, HashMap<String, String>>> = LazyLock::new(|| {
+	let parse = |text: &str| serde_yaml::from_str::<HashMap<String, String>>(text).expect("Locale file is broken");
+
+	HashMap::from([
+		(Locale::Ja, parse(include_str!("../locales/ja.yaml"))),
+		(Locale::En, parse(include_str!("../locales/en.yaml"))),
+	])
+});
+
+pub fn default_template(locale: Locale, key: &str) -> Option<&'static str> {
+	CATALOGS
+		.get(&locale)
+		.and_then(|v| v.get(key))
+		.or_else(|| CATALOGS.get(&Locale::default()).and_then(|v| v.get(key)))
+		.map(|v| v.as_str())
+}
+
+pub fn keys() -> Vec<&'static str> {
+	let mut keys: Vec<&str> = CATALOGS[&Locale::default()].keys().map(|v| v.as_str()).collect();
+	keys.sort();
+	keys
+}
+
+// Discordの文字数制限
+const TITLE_MAX_CHARS: usize = 256;
+const FIELD_NAME_MAX_CHARS: usize = 256;
+const BUTTON_LABEL_MAX_CHARS: usize = 80;
+const PLACEHOLDER_MAX_CHARS: usize = 150;
+// モーダルの入力欄のラベル
+const INPUT_LABEL_MAX_CHARS: usize = 45;
+// 埋め込みの説明は4096文字までだが、テンプレートを編集するモーダルの入力欄が4000文字まで
+const DESCRIPTION_MAX_CHARS: usize = 4000;
+// フィールドの値など
+const TEXT_MAX_CHARS: usize = 1024;
+
+/*
+テンプレートの最大文字数
+表示される場所はキーの末尾で決める (".title", "_field", "_button", "_placeholder", "_label", ".description")
+それ以外はフィールドの値などに使われるものとして扱う
+*/
+pub fn max_chars(key: &str) -> usize {
+	let name = key.rsplit('.').next().unwrap_or(key);
+	if name == "title" {
+		TITLE_MAX_CHARS
+	} else if key.starts_with("field.") || name.ends_with("_field") {
+		FIELD_NAME_MAX_CHARS
+	} else if key.starts_with("common.button.") || name == "button" || name.ends_with("_button") {
+		BUTTON_LABEL_MAX_CHARS
+	} else if name.ends_with("_placeholder") {
+		PLACEHOLDER_MAX_CHARS
+	} else if name.ends_with("_label") {
+		INPUT_LABEL_MAX_CHARS
+	} else if name == "description" {
+		DESCRIPTION_MAX_CHARS
+	} else {
+		TEXT_MAX_CHARS
+	}
+}
+
+pub fn truncate(text: String, max_chars: usize) -> String {
+	if text.chars().count() > max_chars {
+		text.chars().take(max_chars).collect()
+	} else {
+		text
+	}
+}
+
+pub fn render(template: &str, args: &[(&str, String)]) -> String {
+	let mut text = template.to_string();
+	for (name, value) in args {
+		text = text.replace(&format!("{{{}}}", name), value);
+	}
+
+	text
+}
+
+// ユーザーに関するメッセージで使えるプレースホルダー
+pub fn user_args(user: &User) -> Vec<(&'static str, String)> {
+	vec![
+		("user", user.mention().to_string()),
+		("user_id", user.id.to_string()),
+		("username", format_discord_username(user)),
+	]
+}
+
+pub fn account_type_key(account_type: &AccountType) -> &'static str {
+	match account_type {
+		AccountType::Main => "account_type.main",
+		AccountType::Sub => "account_type.sub",
+	}
+}
+
+#[derive(Clone, Default)]
+pub struct Messages {
+	locale: Locale,
+	overrides: HashMap<String, String>,
+}
+
+impl Messages {
+	/*
+	言語は 引数のロケール (インタラクションのlocale) > サーバー設定 > サーバーの優先ロケール の順で決める
+	ログチャンネルなどサーバー全体に出すものはNoneを渡してください
+	*/
+	// コマンドやボタンへの応答用 (サーバー外ではインタラクションのロケールだけで決める)
+	pub async fn for_interaction(ctx: &Context, guild_id: Option<GuildId>, locale: &str) -> Self {
+		match guild_id {
+			Some(guild_id) => Self::for_guild(ctx, guild_id, Some(locale)).await,
+			None => Self::default().with_locale(locale),
+		}
+	}
+
+	pub async fn for_guild(ctx: &Context, guild_id: GuildId, locale: Option<&str>) -> Self {
+		let state = AppState::get(ctx).await;
+		let guild_configs = state.guild_configs();
+		let guild_config = guild_configs.find(guild_id.get()).await;
+		let templates = guild_configs.templates(guild_id.get()).await;
+
+		let guild_locale = match guild_config {
+			Ok(v) => v.and_then(|v| v.locale).and_then(|v| Locale::from_code(&v)),
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				None
+			},
+		};
+		let overrides = match templates {
+			Ok(v) => v.into_iter().map(|v| (v.key, v.template)).collect(),
+			Err(error) => {
+				error!("DB Error: {:?}", error);
+				HashMap::new()
+			},
+		};
+
+		let locale = locale
+			.and_then(Locale::from_code)
+			.or(guild_locale)
+			.or_else(|| {
+				guild_id
+					.to_guild_cached(&ctx.cache)
+					.and_then(|v| Locale::from_code(&v.preferred_locale))
+			})
+			.unwrap_or_default();
+
+		Self { locale, overrides }
+	}
+
+	// サーバー設定を読み込んだものを、インタラクションのロケールに切り替える
+	pub fn with_locale(&self, locale: &str) -> Self {
+		Self {
+			locale: Locale::from_code(locale).unwrap_or(self.locale),
+			overrides: self.overrides.clone(),
+		}
+	}
+
+	pub fn locale(&self) -> Locale {
+		self.locale
+	}
+
+	// サーバーで上書きされているテンプレート
+	pub fn overridden(&self, key: &str) -> Option<&str> {
+		self.overrides.get(key).map(|v| v.as_str())
+	}
+
+	pub fn template(&self, key: &str) -> Option<&str> {
+		self.overridden(key).or_else(|| default_template(self.locale, key))
+	}
+
+	// プレースホルダーを置き換えて長くなりすぎた場合は、表示される場所の制限に合わせて切り詰める
+	pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+		match self.template(key) {
+			Some(template) => truncate(render(template, args), max_chars(key)),
+			None => {
+				warn!("Not found message key: {}", key);
+				key.to_string()
+			},
+		}
+	}
+
+	pub fn get(&self, key: &str) -> String {
+		self.format(key, &[])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn catalogs_have_same_keys() {
+		let mut en_keys: Vec<&str> = CATALOGS[&Locale::En].keys().map(|v| v.as_str()).collect();
+		en_keys.sort();
+		assert_eq!(keys(), en_keys);
+	}
+
+	#[test]
+	fn max_chars_by_location() {
+		assert_eq!(max_chars("member_add.kicked.title"), TITLE_MAX_CHARS);
+		assert_eq!(max_chars("member_add.kicked.description"), DESCRIPTION_MAX_CHARS);
+		assert_eq!(max_chars("field.user_id"), FIELD_NAME_MAX_CHARS);
+		assert_eq!(max_chars("reserve.request.end_field"), FIELD_NAME_MAX_CHARS);
+		assert_eq!(max_chars("common.button.ok"), BUTTON_LABEL_MAX_CHARS);
+		assert_eq!(max_chars("welcome.rules.button"), BUTTON_LABEL_MAX_CHARS);
+		assert_eq!(max_chars("reserve.request.reject_button"), BUTTON_LABEL_MAX_CHARS);
+		assert_eq!(max_chars("config.log_channel_placeholder"), PLACEHOLDER_MAX_CHARS);
+		assert_eq!(max_chars("template.modal.input_label"), INPUT_LABEL_MAX_CHARS);
+		assert_eq!(max_chars("confirm.ok"), TEXT_MAX_CHARS);
+	}
+
+	#[test]
+	fn catalog_templates_fit_limits() {
+		for locale in Locale::ALL {
+			for (key, template) in CATALOGS[&locale].iter() {
+				assert!(
+					template.chars().count() <= max_chars(key),
+					"{} ({})",
+					key,
+					locale.code()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn format_truncates_to_limit() {
+		let msg = Messages {
+			locale: Locale::Ja,
+			overrides: HashMap::from([("member_add.kicked.title".to_string(), "{user}".to_string())]),
+		};
+		let text = msg.format("member_add.kicked.title", &[("user", "あ".repeat(300))]);
+		assert_eq!(text.chars().count(), TITLE_MAX_CHARS);
+	}
+}
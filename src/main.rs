@@ -1,14 +1,27 @@
+mod audit_log;
+mod command_args;
 mod command_define;
+mod command_register;
 mod commands;
+mod components;
 mod configs;
 mod events;
+mod i18n;
+mod migrate;
+mod onboarding;
+mod repositories;
+mod services;
+mod state;
 mod utils;
 mod voice;
 mod chat;
 
+#[cfg(test)]
+mod tests;
+
 use crate::configs::ConfigData;
 use crate::events::route::Router;
-use crate::utils::client::Components;
+use crate::state::AppState;
 use log::{debug, error, info};
 use sea_orm::Database;
 use serenity::all::ApplicationId;
@@ -16,16 +29,15 @@ use serenity::prelude::GatewayIntents;
 use serenity::Client;
 use songbird::driver::DecodeMode;
 use songbird::SerenityInit;
-use voice::text2speak::init_voicevox;
+use voice::speak2text::init_speech_to_text;
+use voice::text2speak::{init_voicevox, load_dictionaries};
 use std::fs;
 use std::path::Path;
-use std::sync::LazyLock;
-use tokio::sync::Mutex;
+use std::sync::Arc;
 use tokio::task::JoinHandle;
 
 static LOG_DIR: &str = "logs";
 static LOG_FILE: &str = "bot_log";
-pub static STATIC_COMPONENTS: LazyLock<Mutex<Components>> = LazyLock::new(|| Mutex::new(Components::new()));
 
 #[tokio::main]
 async fn main() {
@@ -62,9 +74,7 @@ async fn main() {
 	}
 	let config = config.unwrap();
 
-	info!("Voicevox Initialize...");
-
-	init_voicevox().await;
+	components::init_secret(config.get_component_secret());
 
 	info!("Database Connecting...");
 
@@ -77,15 +87,55 @@ async fn main() {
 
 	let mysql_client = mysql_client.unwrap();
 
+	// estella migrate [up|down [steps]|status]
+	let args = command_args();
+	if args.first().map(|v| v.as_str()) == Some("migrate") {
+		let result = migrate::run(&mysql_client, &args[1..]).await;
+		let _ = mysql_client.close().await;
+		if let Err(error) = result {
+			error!("Migration error: {:?}", error);
+			// スクリプトなどから失敗を判別できるように、終了コードを返す
+			std::process::exit(1);
+		}
+		return;
+	}
+
+	match migrate::check(&mysql_client, config.get_auto_migrate()).await {
+		Ok(true) => {},
+		Ok(false) => {
+			let _ = mysql_client.close().await;
+			std::process::exit(1);
+		},
+		Err(error) => {
+			error!("Migration error: {:?}", error);
+			let _ = mysql_client.close().await;
+			std::process::exit(1);
+		},
+	}
+
+	info!("Voicevox Initialize...");
+
+	init_voicevox().await;
+
+	info!("Speech To Text Initialize...");
+
+	if let Err(error) = init_speech_to_text(&config) {
+		error!("Speech to text initialize error: {}", error);
+		let _ = mysql_client.close().await;
+		return;
+	}
+
 	info!("Bot Starting...");
 
 	let mut client = create_client(&config).await;
 
-	let mut lsc = STATIC_COMPONENTS.lock().await;
-	lsc.sets(config, mysql_client, client.shard_manager.clone());
-	std::mem::drop(lsc);
+	// ハンドラーからはctx.dataのAppStateを使う
+	let state = Arc::new(AppState::new(config, mysql_client, client.shard_manager.clone()));
+	client.data.write().await.insert::<AppState>(Arc::clone(&state));
+
+	load_dictionaries(&state).await;
 
-	start_signal();
+	start_signal(state);
 
 	if let Err(error) = client.start().await {
 		error!("Stop Error: {}", error);
@@ -105,6 +155,11 @@ fn debug_arg_check() -> bool {
 	return false;
 }
 
+// "--debug" などのオプションを除いた引数
+fn command_args() -> Vec<String> {
+	std::env::args().skip(1).filter(|v| !v.starts_with("--")).collect()
+}
+
 fn get_log_path(dir_only: bool) -> String {
 	if dir_only {
 		format!("./{}/", LOG_DIR)
@@ -184,7 +239,7 @@ async fn create_client(config: &ConfigData) -> Client {
 		.expect("Erred at client")
 }
 
-fn start_signal() -> JoinHandle<()> {
+fn start_signal(state: Arc<AppState>) -> JoinHandle<()> {
 	tokio::spawn(async move {
 		if let Err(_) = tokio::signal::ctrl_c().await {
 			error!("Could not Ctrl+C signal wait");
@@ -192,21 +247,20 @@ fn start_signal() -> JoinHandle<()> {
 		}
 
 		debug!("Ctrl+C Received!");
-		exit(false).await;
+		exit(&state, false).await;
 	})
 }
 
-pub async fn exit(at_exit: bool) {
-	let lsc = STATIC_COMPONENTS.lock().await;
-	let locked_shard_manager = lsc.get_shard_manager();
-	locked_shard_manager.shutdown_all().await;
+pub async fn exit(state: &AppState, at_exit: bool) {
+	let shard_manager = state.shard_manager();
+	shard_manager.shutdown_all().await;
 
 	info!("Exiting...");
 
-	while locked_shard_manager.shards_instantiated().await.len() != 0 {}
+	while shard_manager.shards_instantiated().await.len() != 0 {}
 	info!("Bot logged out.");
 
-	let res = lsc.get_sql_client().to_owned().close().await;
+	let res = state.db().to_owned().close().await;
 	if let Err(e) = res {
 		error!("Database disconnect error: {:?}", e);
 	}
@@ -0,0 +1,96 @@
+use log::{error, info};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{DatabaseConnection, DbErr};
+
+/*
+DBのマイグレーション
+起動時に未適用のマイグレーションが無いか確認し、`estella migrate` からも手動で実行できるようにする
+*/
+
+// 起動時の確認 (falseの場合は起動しない)
+pub async fn check(db: &DatabaseConnection, auto_migrate: bool) -> Result<bool, DbErr> {
+	let pending = Migrator::get_pending_migrations(db).await?;
+	if pending.is_empty() {
+		return Ok(true);
+	}
+
+	if auto_migrate {
+		info!("Applying {} pending migration(s)...", pending.len());
+		for migration in &pending {
+			info!("  {}", migration.name());
+		}
+		Migrator::up(db, None).await?;
+		info!("Migration completed.");
+		return Ok(true);
+	}
+
+	error!(
+		"Database schema is out of date. {} migration(s) are not applied:",
+		pending.len()
+	);
+	for migration in &pending {
+		error!("  {}", migration.name());
+	}
+	error!("Run `estella migrate` or set `auto_migrate: true` in the config file.");
+	Ok(false)
+}
+
+// estella migrate [up|down [steps]|status]
+pub async fn run(db: &DatabaseConnection, args: &[String]) -> Result<(), DbErr> {
+	match args.first().map(|v| v.as_str()) {
+		None | Some("up") => {
+			let pending = Migrator::get_pending_migrations(db).await?;
+			if pending.is_empty() {
+				info!("No pending migrations.");
+				return Ok(());
+			}
+			for migration in &pending {
+				info!("Applying: {}", migration.name());
+			}
+			Migrator::up(db, None).await?;
+			info!("Migration completed.");
+		},
+		Some("down") => {
+			let steps = match args.get(1).map(|v| v.parse::<u32>()) {
+				None => 1,
+				Some(Ok(steps)) => steps,
+				Some(Err(_)) => {
+					return Err(DbErr::Custom(format!("Invalid steps: {}", args[1])));
+				},
+			};
+			Migrator::down(db, Some(steps)).await?;
+			info!("Rolled back {} migration(s).", steps);
+		},
+		Some("status") => {
+			for migration in Migrator::get_migration_with_status(db).await? {
+				info!("{:<8} {}", migration.status().to_string(), migration.name());
+			}
+		},
+		Some(command) => {
+			return Err(DbErr::Custom(format!(
+				"Unknown migrate command: {} (up, down [steps], status)",
+				command
+			)));
+		},
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sea_orm::Database;
+
+	#[tokio::test]
+	async fn invalid_arguments_fail() {
+		let db = Database::connect("sqlite::memory:").await.unwrap();
+
+		let args = ["down".to_string(), "abc".to_string()];
+		assert!(matches!(run(&db, &args).await, Err(DbErr::Custom(_))));
+		let args = ["foo".to_string()];
+		assert!(matches!(run(&db, &args).await, Err(DbErr::Custom(_))));
+		// 失敗した場合は何も適用しない
+		assert!(!Migrator::get_pending_migrations(&db).await.unwrap().is_empty());
+	}
+}
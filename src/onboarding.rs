@@ -0,0 +1,150 @@
+use crate::components::rules_components;
+use crate::i18n::{user_args, Messages};
+use crate::state::AppState;
+use crate::utils::color;
+use entity::enums::AccountType;
+use entity::{GuildConfig, OnboardingRole};
+use log::{error, info, warn};
+use serenity::all::{ChannelId, CreateEmbed, CreateMessage, GuildId, Member, Mentionable, RoleId, User};
+use serenity::client::Context;
+
+/*
+入鯖を許可したメンバーへのオンボーディング
+アカウントタイプごとのロール付与、ウェルカムDM、ウェルカムチャンネルへのメッセージ送信を行う
+ルールへの同意が必要な場合は、同意ボタンが押されるまで認証ロール (auth_role_id) を付与しない
+ロールの付与に失敗した場合はAccountService::revert_admissionで登録を戻すので、grant_rolesはエラーを返す
+(付与済みのアカウントタイプごとのロールはrevoke_rolesで外す)
+*/
+
+// サーバーに設定されているオンボーディングロール (全アカウントタイプ分)
+pub async fn guild_roles(state: &AppState, guild_id: u64) -> Vec<OnboardingRole> {
+	state.guild_configs().onboarding_roles(guild_id).await.unwrap_or_else(|error| {
+		error!("DB Error: {:?}", error);
+		vec![]
+	})
+}
+
+pub fn filter_roles(roles: &[OnboardingRole], account_type: &AccountType) -> Vec<RoleId> {
+	roles
+		.iter()
+		.filter(|v| &v.account_type == account_type)
+		.map(|v| RoleId::new(v.role_id.get()))
+		.collect()
+}
+
+// ウェルカムメッセージで使えるプレースホルダー (user_argsに加えて {guild})
+pub fn welcome_args(ctx: &Context, guild_id: GuildId, user: &User) -> Vec<(&'static str, String)> {
+	let guild_name = guild_id
+		.to_guild_cached(&ctx.cache)
+		.map(|v| v.name.clone())
+		.unwrap_or_default();
+
+	let mut args = user_args(user);
+	args.push(("guild", guild_name));
+	args
+}
+
+fn rules_embed(msg: &Messages, args: &[(&str, String)]) -> CreateEmbed {
+	CreateEmbed::new()
+		.title(msg.format("welcome.rules.title", args))
+		.description(msg.format("welcome.rules.description", args))
+		.color(color::normal_color())
+}
+
+// アカウントタイプごとのロールと認証ロールを付与する
+pub async fn grant_roles(
+	ctx: &Context,
+	guild_config: &GuildConfig,
+	member: &Member,
+	account_type: &AccountType,
+) -> serenity::Result<()> {
+	let state = AppState::get(ctx).await;
+	let guild_id = member.guild_id;
+	let roles = filter_roles(&guild_roles(&state, guild_id.get()).await, account_type);
+	if !roles.is_empty() {
+		member.add_roles(&ctx.http, &roles).await?;
+	}
+
+	if guild_config.rules_accept {
+		info!("waiting rules accept: {}", member.user.id.get());
+	} else if let Some(role_id) = guild_config.auth_role_id {
+		member.add_role(&ctx.http, role_id.get()).await?;
+	} else {
+		warn!("auth_role_id is none");
+	}
+
+	Ok(())
+}
+
+// grant_rolesの途中で失敗した時に、付与済みのアカウントタイプごとのロールを外す
+// (認証ロールは最後に付与するので、失敗した場合は付与されていない)
+pub async fn revoke_roles(ctx: &Context, member: &Member, account_type: &AccountType) -> serenity::Result<()> {
+	let state = AppState::get(ctx).await;
+	let roles = filter_roles(&guild_roles(&state, member.guild_id.get()).await, account_type);
+	if !roles.is_empty() {
+		member.remove_roles(&ctx.http, &roles).await?;
+	}
+
+	Ok(())
+}
+
+// ウェルカムDM、ウェルカムチャンネルへのメッセージ、ルール同意ボタンを送信する
+pub async fn send_welcome(ctx: &Context, guild_config: &GuildConfig, member: &Member) {
+	let guild_id = member.guild_id;
+	let msg = Messages::for_guild(ctx, guild_id, None).await;
+	let args = welcome_args(ctx, guild_id, &member.user);
+
+	// ルール同意が不要な場合は送信済みとして扱う
+	let mut rules_sent = !guild_config.rules_accept;
+	if guild_config.welcome_dm || guild_config.rules_accept {
+		let mut message = CreateMessage::new();
+		if guild_config.welcome_dm {
+			message = message.add_embed(
+				CreateEmbed::new()
+					.title(msg.format("welcome.dm.title", &args))
+					.description(msg.format("welcome.dm.description", &args))
+					.thumbnail(member.user.face())
+					.color(color::success_color()),
+			);
+		}
+		if guild_config.rules_accept {
+			message = message
+				.add_embed(rules_embed(&msg, &args))
+				.components(rules_components(&msg, guild_id));
+		}
+
+		match member.user.direct_message(&ctx.http, message).await {
+			Ok(_) => rules_sent = true,
+			Err(error) => warn!("Cannot send welcome DM ({}): {:?}", member.user.id.get(), error),
+		}
+	}
+
+	if let Some(channel_id) = guild_config.welcome_channel_id {
+		let mut message = CreateMessage::new().content(member.mention().to_string()).add_embed(
+			CreateEmbed::new()
+				.title(msg.format("welcome.channel.title", &args))
+				.description(msg.format("welcome.channel.description", &args))
+				.thumbnail(member.user.face())
+				.color(color::success_color()),
+		);
+		// DMを受け取れないメンバー向けに、同意ボタンをウェルカムチャンネルに出す
+		if !rules_sent {
+			message = message
+				.add_embed(rules_embed(&msg, &args))
+				.components(rules_components(&msg, guild_id));
+		}
+
+		match ChannelId::new(channel_id.get()).send_message(&ctx.http, message).await {
+			Ok(_) => rules_sent = true,
+			Err(error) => error!("Error: {:?}", error),
+		}
+	}
+
+	if !rules_sent {
+		warn!(
+			"Rules accept button could not be delivered: {} ({})",
+			member.user.id.get(),
+			guild_id.get()
+		);
+	}
+}
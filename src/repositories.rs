@@ -0,0 +1,12 @@
+/*
+テーブルごとのクエリをまとめたもの
+AppStateのDB接続を借りて使うので、AppState::accounts などから取得してください
+エラーはそのまま返すので、ログの出力やユーザーへの通知は呼び出し側で行う
+*/
+mod account;
+mod guild_config;
+mod user_data;
+
+pub use account::AccountRepo;
+pub use guild_config::GuildConfigRepo;
+pub use user_data::UserDataRepo;
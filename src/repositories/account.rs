@@ -0,0 +1,144 @@
+use entity::enums::AccountType;
+use entity::{
+	confirmed_account, main_account, pending_account, sub_account, ConfirmedAccount, ConfirmedAccountBehavior,
+	MainAccount, MainAccountBehavior, PendingAccount, PendingAccountBehavior, SubAccount, SubAccountBehavior,
+};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel, ModelTrait, PaginatorTrait,
+	QueryFilter, QuerySelect,
+};
+
+/*
+アカウント (pending/confirmed/main/sub) の検索と単体の更新
+状態遷移のように複数のテーブルをまたぐ更新はAccountServiceで行ってください
+*/
+pub struct AccountRepo<'a> {
+	db: &'a DatabaseConnection,
+}
+
+impl<'a> AccountRepo<'a> {
+	pub fn new(db: &'a DatabaseConnection) -> Self {
+		Self { db }
+	}
+
+	// 申請中・承認済み・登録済みのどれかにいるか
+	pub async fn is_registered(&self, guild_id: u64, uid: u64) -> Result<bool, DbErr> {
+		let main = MainAccountBehavior::find()
+			.filter(main_account::Column::GuildId.eq(guild_id))
+			.filter(main_account::Column::Uid.eq(uid))
+			.count(self.db)
+			.await?;
+		let sub = SubAccountBehavior::find()
+			.filter(sub_account::Column::GuildId.eq(guild_id))
+			.filter(sub_account::Column::Uid.eq(uid))
+			.count(self.db)
+			.await?;
+		let confirmed = ConfirmedAccountBehavior::find()
+			.filter(confirmed_account::Column::GuildId.eq(guild_id))
+			.filter(confirmed_account::Column::Uid.eq(uid))
+			.count(self.db)
+			.await?;
+		let pending = PendingAccountBehavior::find()
+			.filter(pending_account::Column::GuildId.eq(guild_id))
+			.filter(pending_account::Column::Uid.eq(uid))
+			.count(self.db)
+			.await?;
+
+		Ok(main + sub + confirmed + pending > 0)
+	}
+
+	pub async fn pending(&self, guild_id: u64, uid: u64) -> Result<Option<PendingAccount>, DbErr> {
+		PendingAccountBehavior::find_by_id(uid)
+			.filter(pending_account::Column::GuildId.eq(guild_id))
+			.one(self.db)
+			.await
+	}
+
+	// ログチャンネルの申請メッセージから申請を探す
+	pub async fn pending_by_message(&self, guild_id: u64, message_id: u64) -> Result<Option<PendingAccount>, DbErr> {
+		PendingAccountBehavior::find()
+			.filter(pending_account::Column::GuildId.eq(guild_id))
+			.filter(pending_account::Column::MessageId.eq(message_id))
+			.one(self.db)
+			.await
+	}
+
+	// 投票中のメインアカウントの申請 (全サーバー分)
+	pub async fn pending_mains(&self) -> Result<Vec<PendingAccount>, DbErr> {
+		PendingAccountBehavior::find()
+			.filter(pending_account::Column::AccountType.eq(AccountType::Main))
+			.all(self.db)
+			.await
+	}
+
+	pub async fn insert_pending(&self, pending: PendingAccount) -> Result<PendingAccount, DbErr> {
+		pending.into_active_model().insert(self.db).await
+	}
+
+	pub async fn set_first_cert(&self, pending: PendingAccount, cert_id: u64) -> Result<PendingAccount, DbErr> {
+		let mut pending = pending.into_active_model();
+		pending.first_cert = Set(Some(cert_id.into()));
+		pending.update(self.db).await
+	}
+
+	pub async fn delete_pending(&self, pending: PendingAccount) -> Result<(), DbErr> {
+		pending.delete(self.db).await.map(|_| ())
+	}
+
+	pub async fn confirmed(&self, guild_id: u64, uid: u64) -> Result<Option<ConfirmedAccount>, DbErr> {
+		ConfirmedAccountBehavior::find_by_id(uid)
+			.filter(confirmed_account::Column::GuildId.eq(guild_id))
+			.one(self.db)
+			.await
+	}
+
+	pub async fn main(&self, guild_id: u64, uid: u64) -> Result<Option<MainAccount>, DbErr> {
+		MainAccountBehavior::find_by_id(uid)
+			.filter(main_account::Column::GuildId.eq(guild_id))
+			.one(self.db)
+			.await
+	}
+
+	pub async fn sub(&self, guild_id: u64, uid: u64) -> Result<Option<SubAccount>, DbErr> {
+		SubAccountBehavior::find_by_id(uid)
+			.filter(sub_account::Column::GuildId.eq(guild_id))
+			.one(self.db)
+			.await
+	}
+
+	pub async fn insert_main(&self, main: MainAccount) -> Result<MainAccount, DbErr> {
+		main.into_active_model().insert(self.db).await
+	}
+
+	pub async fn insert_sub(&self, sub: SubAccount) -> Result<SubAccount, DbErr> {
+		sub.into_active_model().insert(self.db).await
+	}
+
+	// 登録名の部分一致 (オートコンプリート用)
+	pub async fn search_mains(&self, guild_id: u64, name: &str, limit: u64) -> Result<Vec<MainAccount>, DbErr> {
+		MainAccountBehavior::find()
+			.filter(main_account::Column::GuildId.eq(guild_id))
+			.filter(main_account::Column::Name.contains(name))
+			.limit(limit)
+			.all(self.db)
+			.await
+	}
+
+	// 登録名の部分一致 (main_uidを指定した場合はそのメインアカウントのサブアカウントのみ)
+	pub async fn search_subs(
+		&self,
+		guild_id: u64,
+		main_uid: Option<u64>,
+		name: &str,
+		limit: u64,
+	) -> Result<Vec<SubAccount>, DbErr> {
+		let mut query = SubAccountBehavior::find()
+			.filter(sub_account::Column::GuildId.eq(guild_id))
+			.filter(sub_account::Column::Name.contains(name));
+		if let Some(main_uid) = main_uid {
+			query = query.filter(sub_account::Column::MainUid.eq(main_uid));
+		}
+		query.limit(limit).all(self.db).await
+	}
+}
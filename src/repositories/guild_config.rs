@@ -0,0 +1,184 @@
+use entity::enums::AccountType;
+use entity::{
+	guild_config, message_template, onboarding_role, tts_dictionary, voice_persona, GuildConfig, GuildConfigBehavior,
+	MessageTemplate, MessageTemplateBehavior, OnboardingRole, OnboardingRoleBehavior, TtsDictionary,
+	TtsDictionaryBehavior, VoicePersona, VoicePersonaBehavior,
+};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+	ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel, QueryFilter,
+	TransactionTrait,
+};
+
+// サーバー設定と、サーバーごとのメッセージテンプレート・オンボーディングロール・読み上げのペルソナ・読み上げ辞書
+pub struct GuildConfigRepo<'a> {
+	db: &'a DatabaseConnection,
+}
+
+impl<'a> GuildConfigRepo<'a> {
+	pub fn new(db: &'a DatabaseConnection) -> Self {
+		Self { db }
+	}
+
+	pub async fn find(&self, guild_id: u64) -> Result<Option<GuildConfig>, DbErr> {
+		GuildConfigBehavior::find_by_id(guild_id).one(self.db).await
+	}
+
+	pub async fn insert(&self, guild_config: GuildConfig) -> Result<GuildConfig, DbErr> {
+		guild_config.into_active_model().insert(self.db).await
+	}
+
+	pub async fn update(&self, guild_config: guild_config::ActiveModel) -> Result<GuildConfig, DbErr> {
+		guild_config.update(self.db).await
+	}
+
+	pub async fn templates(&self, guild_id: u64) -> Result<Vec<MessageTemplate>, DbErr> {
+		MessageTemplateBehavior::find()
+			.filter(message_template::Column::GuildId.eq(guild_id))
+			.all(self.db)
+			.await
+	}
+
+	pub async fn save_template(&self, guild_id: u64, key: &str, template: &str) -> Result<(), DbErr> {
+		MessageTemplateBehavior::insert(message_template::ActiveModel {
+			guild_id: ActiveValue::Set(guild_id.into()),
+			key: ActiveValue::Set(key.to_string()),
+			template: ActiveValue::Set(template.to_string()),
+		})
+		.on_conflict(
+			OnConflict::columns([message_template::Column::GuildId, message_template::Column::Key])
+				.update_column(message_template::Column::Template)
+				.to_owned(),
+		)
+		.exec(self.db)
+		.await
+		.map(|_| ())
+	}
+
+	pub async fn delete_template(&self, guild_id: u64, key: &str) -> Result<(), DbErr> {
+		MessageTemplateBehavior::delete_by_id((guild_id.into(), key.to_string()))
+			.exec(self.db)
+			.await
+			.map(|_| ())
+	}
+
+	// 全アカウントタイプ分
+	pub async fn onboarding_roles(&self, guild_id: u64) -> Result<Vec<OnboardingRole>, DbErr> {
+		OnboardingRoleBehavior::find()
+			.filter(onboarding_role::Column::GuildId.eq(guild_id))
+			.all(self.db)
+			.await
+	}
+
+	// アカウントタイプのロールを入れ替える (途中で失敗した場合は元のロールが残る)
+	pub async fn replace_onboarding_roles(
+		&self,
+		guild_id: u64,
+		account_type: AccountType,
+		role_ids: &[u64],
+	) -> Result<(), DbErr> {
+		let roles: Vec<onboarding_role::ActiveModel> = role_ids
+			.iter()
+			.map(|v| onboarding_role::ActiveModel {
+				guild_id: ActiveValue::Set(guild_id.into()),
+				account_type: ActiveValue::Set(account_type.clone()),
+				role_id: ActiveValue::Set((*v).into()),
+			})
+			.collect();
+
+		let txn = self.db.begin().await?;
+		OnboardingRoleBehavior::delete_many()
+			.filter(onboarding_role::Column::GuildId.eq(guild_id))
+			.filter(onboarding_role::Column::AccountType.eq(account_type))
+			.exec(&txn)
+			.await?;
+		if !roles.is_empty() {
+			OnboardingRoleBehavior::insert_many(roles)
+				.exec_without_returning(&txn)
+				.await?;
+		}
+		txn.commit().await
+	}
+
+	pub async fn voice_personas(&self, guild_id: u64) -> Result<Vec<VoicePersona>, DbErr> {
+		VoicePersonaBehavior::find()
+			.filter(voice_persona::Column::GuildId.eq(guild_id))
+			.all(self.db)
+			.await
+	}
+
+	pub async fn find_voice_persona(&self, guild_id: u64, name: &str) -> Result<Option<VoicePersona>, DbErr> {
+		VoicePersonaBehavior::find_by_id((guild_id.into(), name.to_string()))
+			.one(self.db)
+			.await
+	}
+
+	pub async fn save_voice_persona(&self, guild_id: u64, name: &str, style_id: i32) -> Result<(), DbErr> {
+		VoicePersonaBehavior::insert(voice_persona::ActiveModel {
+			guild_id: ActiveValue::Set(guild_id.into()),
+			name: ActiveValue::Set(name.to_string()),
+			style_id: ActiveValue::Set(style_id),
+		})
+		.on_conflict(
+			OnConflict::columns([voice_persona::Column::GuildId, voice_persona::Column::Name])
+				.update_column(voice_persona::Column::StyleId)
+				.to_owned(),
+		)
+		.exec(self.db)
+		.await
+		.map(|_| ())
+	}
+
+	pub async fn delete_voice_persona(&self, guild_id: u64, name: &str) -> Result<(), DbErr> {
+		VoicePersonaBehavior::delete_by_id((guild_id.into(), name.to_string()))
+			.exec(self.db)
+			.await
+			.map(|_| ())
+	}
+
+	pub async fn tts_dictionary(&self, guild_id: u64) -> Result<Vec<TtsDictionary>, DbErr> {
+		TtsDictionaryBehavior::find()
+			.filter(tts_dictionary::Column::GuildId.eq(guild_id))
+			.all(self.db)
+			.await
+	}
+
+	// 起動時に全サーバーの辞書をまとめて読み込むため
+	pub async fn all_tts_dictionary(&self) -> Result<Vec<TtsDictionary>, DbErr> {
+		TtsDictionaryBehavior::find().all(self.db).await
+	}
+
+	pub async fn save_tts_word(
+		&self,
+		guild_id: u64,
+		surface: &str,
+		pronunciation: &str,
+		accent_type: i32,
+	) -> Result<(), DbErr> {
+		TtsDictionaryBehavior::insert(tts_dictionary::ActiveModel {
+			guild_id: ActiveValue::Set(guild_id.into()),
+			surface: ActiveValue::Set(surface.to_string()),
+			pronunciation: ActiveValue::Set(pronunciation.to_string()),
+			accent_type: ActiveValue::Set(accent_type),
+		})
+		.on_conflict(
+			OnConflict::columns([tts_dictionary::Column::GuildId, tts_dictionary::Column::Surface])
+				.update_columns([
+					tts_dictionary::Column::Pronunciation,
+					tts_dictionary::Column::AccentType,
+				])
+				.to_owned(),
+		)
+		.exec(self.db)
+		.await
+		.map(|_| ())
+	}
+
+	// 削除した場合はtrue
+	pub async fn delete_tts_word(&self, guild_id: u64, surface: &str) -> Result<bool, DbErr> {
+		TtsDictionaryBehavior::delete_by_id((guild_id.into(), surface.to_string()))
+			.exec(self.db)
+			.await
+			.map(|v| v.rows_affected > 0)
+	}
+}
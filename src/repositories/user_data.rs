@@ -0,0 +1,50 @@
+use entity::{talk_history, user_data, TalkHistory, TalkHistoryBehavior, UserData, UserDataBehavior};
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel, PaginatorTrait,
+	QueryFilter, QueryOrder, QuerySelect,
+};
+
+// ユーザーごとのデータ (glacialeur、呼び名、会話数など)
+pub struct UserDataRepo<'a> {
+	db: &'a DatabaseConnection,
+}
+
+impl<'a> UserDataRepo<'a> {
+	pub fn new(db: &'a DatabaseConnection) -> Self {
+		Self { db }
+	}
+
+	pub async fn find(&self, uid: u64) -> Result<Option<UserData>, DbErr> {
+		UserDataBehavior::find_by_id(uid).one(self.db).await
+	}
+
+	// 通常は1件だが、データが壊れていると複数見つかることがある
+	pub async fn find_by_glacialeur(&self, glacialeur: &str) -> Result<Vec<UserData>, DbErr> {
+		UserDataBehavior::find()
+			.filter(user_data::Column::Glacialeur.eq(glacialeur))
+			.all(self.db)
+			.await
+	}
+
+	pub async fn insert(&self, user_data: UserData) -> Result<UserData, DbErr> {
+		user_data.into_active_model().insert(self.db).await
+	}
+
+	pub async fn update(&self, user_data: user_data::ActiveModel) -> Result<UserData, DbErr> {
+		user_data.update(self.db).await
+	}
+
+	pub async fn count(&self) -> Result<u64, DbErr> {
+		UserDataBehavior::find().count(self.db).await
+	}
+
+	// 指定したユーザーたちとの最近の会話 (新しい順)
+	pub async fn recent_talk_histories(&self, uids: &[u64], limit: u64) -> Result<Vec<TalkHistory>, DbErr> {
+		TalkHistoryBehavior::find()
+			.filter(talk_history::Column::UserId.is_in(uids.iter().copied()))
+			.order_by_desc(talk_history::Column::TalkDate)
+			.limit(limit)
+			.all(self.db)
+			.await
+	}
+}
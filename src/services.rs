@@ -0,0 +1,3 @@
+mod account;
+
+pub use account::{AccountService, Leave};
@@ -0,0 +1,290 @@
+use crate::utils::glacialeur;
+use chrono::{DateTime, Utc};
+use entity::enums::AccountType;
+use entity::unsigned::DbU8;
+use entity::{
+	main_account, sub_account, ConfirmedAccount, ConfirmedAccountBehavior, MainAccount, MainAccountBehavior,
+	PendingAccount, PendingAccountBehavior, SubAccount, SubAccountBehavior, UserData, UserDataBehavior,
+};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, IntoActiveModel,
+	QueryFilter, TransactionTrait,
+};
+
+/*
+アカウントの状態遷移 (pending → confirmed → main/sub → 退出) をまとめたもの
+各遷移は1つのトランザクションで行うので、途中で失敗した場合は何も変更されません
+Discord側の操作 (キック、ロール付与、埋め込みの編集) はコミット後に呼び出し側で行い、
+失敗した場合は revert_* で遷移前の状態に戻してください
+AppState::account_service から取得します
+*/
+pub struct AccountService<'a> {
+	db: &'a DatabaseConnection,
+}
+
+// 入鯖許可の結果 (revert_admissionで元に戻すために使う)
+pub struct Admission {
+	pub confirmed: ConfirmedAccount,
+	pub glacialeur: Option<String>,
+	user_data_created: bool,
+}
+
+pub enum Leave {
+	Main { sub_uids: Vec<u64> },
+	Sub,
+	NotFound,
+}
+
+async fn delete_pending(txn: &DatabaseTransaction, p_user: &PendingAccount) -> Result<(), DbErr> {
+	let res = PendingAccountBehavior::delete_by_id(p_user.uid)
+		.filter(entity::pending_account::Column::GuildId.eq(p_user.guild_id))
+		.exec(txn)
+		.await?;
+	// 却下などで先に消されていた場合は遷移させない
+	if res.rows_affected == 0 {
+		return Err(DbErr::RecordNotFound(format!("pending_account: {}", p_user.uid)));
+	}
+
+	Ok(())
+}
+
+// 同時に入鯖処理が走った場合に、二重に登録しないようにする
+async fn delete_confirmed(txn: &DatabaseTransaction, confirmed: &ConfirmedAccount) -> Result<(), DbErr> {
+	let res = ConfirmedAccountBehavior::delete_by_id(confirmed.uid)
+		.filter(entity::confirmed_account::Column::GuildId.eq(confirmed.guild_id))
+		.exec(txn)
+		.await?;
+	if res.rows_affected == 0 {
+		return Err(DbErr::RecordNotFound(format!("confirmed_account: {}", confirmed.uid)));
+	}
+
+	Ok(())
+}
+
+impl<'a> AccountService<'a> {
+	pub fn new(db: &'a DatabaseConnection) -> Self {
+		Self { db }
+	}
+
+	// 投票期間が終わったメインアカウントの申請を承認する
+	pub async fn approve_main(&self, p_user: &PendingAccount) -> Result<ConfirmedAccount, DbErr> {
+		let txn = self.db.begin().await?;
+		delete_pending(&txn, p_user).await?;
+		let confirmed = ConfirmedAccount {
+			uid: p_user.uid,
+			name: p_user.name.clone().unwrap_or_default(),
+			guild_id: p_user.guild_id,
+			account_type: AccountType::Main,
+			main_uid: None,
+			first_cert: None,
+			second_cert: None,
+		}
+		.into_active_model()
+		.insert(&txn)
+		.await?;
+		txn.commit().await?;
+
+		Ok(confirmed)
+	}
+
+	// 承認が揃ったサブアカウントの申請を承認する
+	pub async fn approve_sub(&self, p_user: &PendingAccount, cert_id: u64) -> Result<ConfirmedAccount, DbErr> {
+		let (first_cert, second_cert) = match p_user.first_cert {
+			Some(first_cert) => (Some(first_cert), Some(cert_id.into())),
+			None => (Some(cert_id.into()), None),
+		};
+
+		let txn = self.db.begin().await?;
+		delete_pending(&txn, p_user).await?;
+		let confirmed = ConfirmedAccount {
+			uid: p_user.uid,
+			name: p_user.name.clone().unwrap_or_default(),
+			guild_id: p_user.guild_id,
+			account_type: AccountType::Sub,
+			main_uid: p_user.main_uid,
+			first_cert,
+			second_cert,
+		}
+		.into_active_model()
+		.insert(&txn)
+		.await?;
+		txn.commit().await?;
+
+		Ok(confirmed)
+	}
+
+	// approve_main/approve_subを取り消して申請中に戻す
+	pub async fn revert_approval(&self, p_user: &PendingAccount) -> Result<(), DbErr> {
+		let txn = self.db.begin().await?;
+		ConfirmedAccountBehavior::delete_by_id(p_user.uid)
+			.filter(entity::confirmed_account::Column::GuildId.eq(p_user.guild_id))
+			.exec(&txn)
+			.await?;
+		p_user.clone().into_active_model().insert(&txn).await?;
+		txn.commit().await
+	}
+
+	// 申請を却下する
+	pub async fn reject(&self, p_user: &PendingAccount) -> Result<(), DbErr> {
+		let txn = self.db.begin().await?;
+		delete_pending(&txn, p_user).await?;
+		txn.commit().await
+	}
+
+	// rejectを取り消して申請中に戻す
+	pub async fn revert_rejection(&self, p_user: &PendingAccount) -> Result<(), DbErr> {
+		let txn = self.db.begin().await?;
+		p_user.clone().into_active_model().insert(&txn).await?;
+		txn.commit().await
+	}
+
+	// 承認済みのアカウントをメンバーとして登録する (guild_created_atはglacialeurの生成に使う)
+	pub async fn admit(
+		&self,
+		confirmed: &ConfirmedAccount,
+		join_date: DateTime<Utc>,
+		guild_created_at: i64,
+	) -> Result<Admission, DbErr> {
+		let txn = self.db.begin().await?;
+		delete_confirmed(&txn, confirmed).await?;
+
+		let glacialeur = match confirmed.account_type {
+			AccountType::Main => {
+				let main_account = MainAccount {
+					uid: confirmed.uid,
+					name: confirmed.name.clone(),
+					guild_id: confirmed.guild_id,
+					version: DbU8(1 << 3),
+					join_date,
+					is_server_creator: false,
+					is_leaved: false,
+				}
+				.into_active_model()
+				.insert(&txn)
+				.await?;
+
+				let glacialeur = glacialeur::generate(
+					main_account.uid.get(),
+					main_account.version.get(),
+					main_account.join_date.timestamp() - guild_created_at,
+				)
+				.map_err(|error| DbErr::Custom(format!("glacialeur: {} ({})", error, main_account.uid)))?;
+				Some(glacialeur)
+			},
+			AccountType::Sub => {
+				let missing =
+					|column: &str| DbErr::Custom(format!("confirmed_account.{} is none: {}", column, confirmed.uid));
+				SubAccount {
+					uid: confirmed.uid,
+					name: confirmed.name.clone(),
+					guild_id: confirmed.guild_id,
+					join_date,
+					main_uid: confirmed.main_uid.ok_or_else(|| missing("main_uid"))?,
+					first_cert: confirmed.first_cert.ok_or_else(|| missing("first_cert"))?,
+					second_cert: confirmed.second_cert,
+				}
+				.into_active_model()
+				.insert(&txn)
+				.await?;
+
+				None
+			},
+		};
+
+		// 以前に登録されていたユーザーはuser_dataが残っているので、glacialeurだけ更新する
+		let user_data = UserDataBehavior::find_by_id(confirmed.uid).one(&txn).await?;
+		let user_data_created = user_data.is_none();
+		match user_data {
+			None => {
+				UserData {
+					uid: confirmed.uid,
+					glacialeur: glacialeur.clone(),
+					call_name: None,
+					gender: None,
+					chat_message_count: None,
+				}
+				.into_active_model()
+				.insert(&txn)
+				.await?;
+			},
+			Some(user_data) if glacialeur.is_some() => {
+				let mut user_data = user_data.into_active_model();
+				user_data.glacialeur = Set(glacialeur.clone());
+				user_data.update(&txn).await?;
+			},
+			Some(_) => {},
+		}
+		txn.commit().await?;
+
+		Ok(Admission {
+			confirmed: confirmed.clone(),
+			glacialeur,
+			user_data_created,
+		})
+	}
+
+	// admitを取り消して承認済みに戻す
+	pub async fn revert_admission(&self, admission: &Admission) -> Result<(), DbErr> {
+		let confirmed = &admission.confirmed;
+
+		let txn = self.db.begin().await?;
+		match confirmed.account_type {
+			AccountType::Main => {
+				MainAccountBehavior::delete_by_id(confirmed.uid)
+					.filter(main_account::Column::GuildId.eq(confirmed.guild_id))
+					.exec(&txn)
+					.await?;
+			},
+			AccountType::Sub => {
+				SubAccountBehavior::delete_by_id(confirmed.uid)
+					.filter(sub_account::Column::GuildId.eq(confirmed.guild_id))
+					.exec(&txn)
+					.await?;
+			},
+		}
+		if admission.user_data_created {
+			UserDataBehavior::delete_by_id(confirmed.uid).exec(&txn).await?;
+		}
+		confirmed.clone().into_active_model().insert(&txn).await?;
+		txn.commit().await
+	}
+
+	// サーバーを抜けたメンバーの登録を更新する
+	// サブアカウントは削除し、メインアカウントは退出済みにして紐づくサブアカウントを返す (キックは呼び出し側で行う)
+	pub async fn leave(&self, guild_id: u64, uid: u64) -> Result<Leave, DbErr> {
+		let txn = self.db.begin().await?;
+		let sub = SubAccountBehavior::find_by_id(uid)
+			.filter(sub_account::Column::GuildId.eq(guild_id))
+			.one(&txn)
+			.await?;
+		if let Some(sub) = sub {
+			SubAccountBehavior::delete_by_id(sub.uid).exec(&txn).await?;
+			txn.commit().await?;
+			return Ok(Leave::Sub);
+		}
+
+		let main = MainAccountBehavior::find_by_id(uid)
+			.filter(main_account::Column::GuildId.eq(guild_id))
+			.one(&txn)
+			.await?;
+		let main = match main {
+			Some(v) => v,
+			None => return Ok(Leave::NotFound),
+		};
+		let sub_uids = SubAccountBehavior::find()
+			.filter(sub_account::Column::GuildId.eq(guild_id))
+			.filter(sub_account::Column::MainUid.eq(uid))
+			.all(&txn)
+			.await?
+			.into_iter()
+			.map(|v| v.uid.get())
+			.collect();
+		let mut main = main.into_active_model();
+		main.is_leaved = Set(true);
+		main.update(&txn).await?;
+		txn.commit().await?;
+
+		Ok(Leave::Main { sub_uids })
+	}
+}
@@ -0,0 +1,84 @@
+use crate::configs::ConfigData;
+use crate::repositories::{AccountRepo, GuildConfigRepo, UserDataRepo};
+use crate::services::AccountService;
+use sea_orm::DatabaseConnection;
+use serenity::all::ShardManager;
+use serenity::client::Context;
+use serenity::prelude::TypeMapKey;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/*
+Bot全体で共有する状態 (設定、DB接続、シャードマネージャー)
+Clientの作成後にserenityのTypeMapに登録し、ハンドラーからは AppState::get で取り出す
+設定とDB接続は起動後に変わらないので、ロックせずに参照できます
+DB接続はsea-ormのコネクションプールなので、リポジトリやサービスはこれを借りて使う
+*/
+pub struct AppState {
+	config: ConfigData,
+	db: DatabaseConnection,
+	shard_manager: Arc<ShardManager>,
+	// テキストチャットの前回の応答ID
+	prev_id: Mutex<Option<String>>,
+}
+
+impl TypeMapKey for AppState {
+	type Value = Arc<AppState>;
+}
+
+impl AppState {
+	pub fn new(config: ConfigData, db: DatabaseConnection, shard_manager: Arc<ShardManager>) -> Self {
+		Self {
+			config,
+			db,
+			shard_manager,
+			prev_id: Mutex::new(None),
+		}
+	}
+
+	// ready前のイベントは来ないので、登録されていない場合はバグとして扱う
+	pub async fn get(ctx: &Context) -> Arc<AppState> {
+		ctx.data
+			.read()
+			.await
+			.get::<AppState>()
+			.cloned()
+			.expect("AppState is not registered")
+	}
+
+	pub fn config(&self) -> &ConfigData {
+		&self.config
+	}
+
+	pub fn db(&self) -> &DatabaseConnection {
+		&self.db
+	}
+
+	pub fn shard_manager(&self) -> &Arc<ShardManager> {
+		&self.shard_manager
+	}
+
+	pub fn accounts(&self) -> AccountRepo<'_> {
+		AccountRepo::new(&self.db)
+	}
+
+	pub fn guild_configs(&self) -> GuildConfigRepo<'_> {
+		GuildConfigRepo::new(&self.db)
+	}
+
+	pub fn user_data(&self) -> UserDataRepo<'_> {
+		UserDataRepo::new(&self.db)
+	}
+
+	pub fn account_service(&self) -> AccountService<'_> {
+		AccountService::new(&self.db)
+	}
+
+	pub async fn prev_id(&self) -> Option<String> {
+		self.prev_id.lock().await.clone()
+	}
+
+	pub async fn set_prev_id(&self, val: String) {
+		*self.prev_id.lock().await = Some(val);
+	}
+}
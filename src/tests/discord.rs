@@ -0,0 +1,207 @@
+use super::fixtures;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio_tungstenite::tungstenite::Message;
+
+/*
+テスト用のDiscord
+HTTPはserenityのproxyの向け先として受けた呼び出しを記録し、各エンドポイントに最低限のJSONを返す
+ゲートウェイはHelloを送った後、テストから渡されたイベントをそのまま流すだけ (Identifyやheartbeatには応答しない)
+*/
+
+#[derive(Clone, Debug)]
+pub struct Request {
+	pub method: String,
+	// "/api/v10" とクエリを除いたパス
+	pub path: String,
+	pub body: Value,
+}
+
+#[derive(Default)]
+pub struct FakeDiscord {
+	requests: Mutex<Vec<Request>>,
+	failures: Mutex<Vec<(String, String)>>,
+	next_id: AtomicU64,
+}
+
+impl FakeDiscord {
+	pub fn requests(&self) -> Vec<Request> {
+		self.requests.lock().unwrap().clone()
+	}
+
+	pub fn find(&self, method: &str, path: &str) -> Option<Request> {
+		self.requests()
+			.into_iter()
+			.find(|v| v.method == method && v.path == path)
+	}
+
+	pub fn clear(&self) {
+		self.requests.lock().unwrap().clear();
+	}
+
+	// 以降の呼び出しを500で失敗させる
+	pub fn fail(&self, method: &str, path: &str) {
+		self.failures
+			.lock()
+			.unwrap()
+			.push((method.to_string(), path.to_string()));
+	}
+
+	// failで失敗させていた呼び出しを元に戻す
+	pub fn recover(&self, method: &str, path: &str) {
+		self.failures.lock().unwrap().retain(|(m, p)| m != method || p != path);
+	}
+
+	pub async fn wait_for(&self, method: &str, path: &str) -> Request {
+		for _ in 0..250 {
+			if let Some(request) = self.find(method, path) {
+				return request;
+			}
+			tokio::time::sleep(Duration::from_millis(20)).await;
+		}
+		panic!("{} {} was not called: {:#?}", method, path, self.requests());
+	}
+
+	fn respond(&self, method: &str, path: &str, body: &Value) -> (u16, Option<Value>) {
+		let is_failure = self
+			.failures
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|(m, p)| m == method && p == path);
+		if is_failure {
+			return (500, Some(json!({ "code": 0, "message": "fake error" })));
+		}
+
+		let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+		let id = |v: &str| v.parse::<u64>().unwrap_or_default();
+		let response = match (method, segments.as_slice()) {
+			("POST", ["channels", channel_id, "messages"]) => fixtures::message_json(
+				self.next_id(),
+				id(channel_id),
+				body["content"].as_str().unwrap_or_default(),
+			),
+			("GET" | "PATCH", ["channels", channel_id, "messages", message_id]) => {
+				fixtures::message_json(id(message_id), id(channel_id), "")
+			},
+			("POST", ["users", "@me", "channels"]) => {
+				let recipient_id = body["recipient_id"].as_str().map(id).unwrap_or_default();
+				json!({
+					"id": self.next_id().to_string(),
+					"type": 1,
+					"recipients": [fixtures::user_json(recipient_id, false)],
+				})
+			},
+			("GET" | "PATCH", ["webhooks", _, _, "messages", "@original"]) => {
+				fixtures::message_json(fixtures::ORIGINAL_MESSAGE_ID, fixtures::COMMAND_CHANNEL_ID, "")
+			},
+			("POST", ["webhooks", _, _]) => fixtures::message_json(self.next_id(), fixtures::COMMAND_CHANNEL_ID, ""),
+			_ => return (204, None),
+		};
+		(200, Some(response))
+	}
+
+	fn next_id(&self) -> u64 {
+		fixtures::GENERATED_ID_BASE + self.next_id.fetch_add(1, Ordering::Relaxed)
+	}
+}
+
+pub async fn serve_http(listener: TcpListener, discord: Arc<FakeDiscord>) {
+	while let Ok((stream, _)) = listener.accept().await {
+		tokio::spawn(handle_connection(stream, Arc::clone(&discord)));
+	}
+}
+
+// reqwestはコネクションを使い回すので、切断されるまで続けて読む
+async fn handle_connection(stream: TcpStream, discord: Arc<FakeDiscord>) -> std::io::Result<()> {
+	let mut reader = BufReader::new(stream);
+	loop {
+		let mut request_line = String::new();
+		if reader.read_line(&mut request_line).await? == 0 {
+			return Ok(());
+		}
+		let mut parts = request_line.split_whitespace();
+		let method = parts.next().unwrap_or_default().to_string();
+		let target = parts.next().unwrap_or_default();
+		let path = target
+			.split('?')
+			.next()
+			.unwrap_or_default()
+			.trim_start_matches("/api/v10")
+			.to_string();
+
+		let mut content_length = 0;
+		loop {
+			let mut header = String::new();
+			reader.read_line(&mut header).await?;
+			let header = header.trim_end();
+			if header.is_empty() {
+				break;
+			}
+			if let Some((name, value)) = header.split_once(':') {
+				if name.eq_ignore_ascii_case("content-length") {
+					content_length = value.trim().parse().unwrap_or_default();
+				}
+			}
+		}
+		let mut body = vec![0; content_length];
+		reader.read_exact(&mut body).await?;
+		let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+		let (status, response) = discord.respond(&method, &path, &body);
+		discord.requests.lock().unwrap().push(Request { method, path, body });
+
+		let response = response.map(|v| v.to_string()).unwrap_or_default();
+		let head = format!(
+			"HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+			status,
+			if status < 300 { "OK" } else { "Internal Server Error" },
+			response.len()
+		);
+		let stream = reader.get_mut();
+		stream.write_all(head.as_bytes()).await?;
+		stream.write_all(response.as_bytes()).await?;
+	}
+}
+
+pub async fn serve_gateway(listener: TcpListener, mut events: UnboundedReceiver<(String, Value)>) {
+	let Ok((stream, _)) = listener.accept().await else {
+		return;
+	};
+	let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+		return;
+	};
+
+	// heartbeatが必要にならないように間隔は長くしておく
+	let hello = json!({ "op": 10, "d": { "heartbeat_interval": 3_600_000 } });
+	if ws.send(Message::Text(hello.to_string())).await.is_err() {
+		return;
+	}
+
+	let mut seq = 0;
+	loop {
+		tokio::select! {
+			received = ws.next() => {
+				if !matches!(received, Some(Ok(_))) {
+					return;
+				}
+			},
+			event = events.recv() => {
+				let Some((name, data)) = event else {
+					return;
+				};
+				seq += 1;
+				let payload = json!({ "op": 0, "s": seq, "t": name, "d": data });
+				if ws.send(Message::Text(payload.to_string())).await.is_err() {
+					return;
+				}
+			},
+		}
+	}
+}
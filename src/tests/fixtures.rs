@@ -0,0 +1,197 @@
+use chrono::{DateTime, Duration, Utc};
+use entity::enums::AccountType;
+use entity::{ConfirmedAccount, GuildConfig, MainAccount, PendingAccount, SubAccount};
+use serde_json::{json, Value};
+use serenity::all::{CommandInteraction, Member, User};
+
+// テストで使うID (snowflakeとして不自然でない値にしておく)
+pub const APP_ID: u64 = 900000000000000001;
+pub const GUILD_ID: u64 = 900000000000000100;
+pub const LOG_CHANNEL_ID: u64 = 900000000000000200;
+pub const COMMAND_CHANNEL_ID: u64 = 900000000000000201;
+pub const AUTH_ROLE_ID: u64 = 900000000000000300;
+pub const BOT_ROLE_ID: u64 = 900000000000000301;
+pub const MAIN_ROLE_ID: u64 = 900000000000000302;
+pub const ADMIN_ID: u64 = 900000000000000400;
+pub const MAIN_UID: u64 = 900000000000000401;
+pub const SUB_UID: u64 = 900000000000000402;
+pub const VOTE_MESSAGE_ID: u64 = 900000000000000500;
+pub const ORIGINAL_MESSAGE_ID: u64 = 900000000000000501;
+pub const COMMAND_INTERACTION_ID: u64 = 900000000000000600;
+pub const BUTTON_INTERACTION_ID: u64 = 900000000000000601;
+pub const GENERATED_ID_BASE: u64 = 910000000000000000;
+
+pub const COMMAND_TOKEN: &str = "command-token";
+pub const BUTTON_TOKEN: &str = "button-token";
+
+const TIMESTAMP: &str = "2025-01-01T00:00:00.000000+00:00";
+
+pub fn guild_config() -> GuildConfig {
+	GuildConfig {
+		uid: GUILD_ID.into(),
+		white_list: true,
+		leave_ban: true,
+		log_channel_id: Some(LOG_CHANNEL_ID.into()),
+		auth_role_id: Some(AUTH_ROLE_ID.into()),
+		bot_role_id: Some(BOT_ROLE_ID.into()),
+		send_ai_chat_channel_id: None,
+		locale: None,
+		welcome_channel_id: None,
+		welcome_dm: false,
+		rules_accept: false,
+		voice_wake_words: None,
+		voice_interjection_rate: 20,
+		voice_idle_topic_seconds: 300,
+		voice_style_id: None,
+	}
+}
+
+pub fn pending_main(uid: u64, end_voting: DateTime<Utc>) -> PendingAccount {
+	PendingAccount {
+		uid: uid.into(),
+		name: Some("main".to_string()),
+		message_id: VOTE_MESSAGE_ID.into(),
+		end_voting: Some(end_voting),
+		guild_id: GUILD_ID.into(),
+		account_type: AccountType::Main,
+		main_uid: None,
+		first_cert: None,
+	}
+}
+
+pub fn confirmed_main(uid: u64) -> ConfirmedAccount {
+	ConfirmedAccount {
+		uid: uid.into(),
+		name: "main".to_string(),
+		guild_id: GUILD_ID.into(),
+		account_type: AccountType::Main,
+		main_uid: None,
+		first_cert: None,
+		second_cert: None,
+	}
+}
+
+pub fn main_account(uid: u64) -> MainAccount {
+	MainAccount {
+		uid: uid.into(),
+		name: "main".to_string(),
+		guild_id: GUILD_ID.into(),
+		version: (1 << 3).into(),
+		join_date: Utc::now() - Duration::days(30),
+		is_server_creator: false,
+		is_leaved: false,
+	}
+}
+
+pub fn sub_account(uid: u64, main_uid: u64) -> SubAccount {
+	SubAccount {
+		uid: uid.into(),
+		name: "sub".to_string(),
+		guild_id: GUILD_ID.into(),
+		join_date: Utc::now() - Duration::days(10),
+		main_uid: main_uid.into(),
+		first_cert: ADMIN_ID.into(),
+		second_cert: None,
+	}
+}
+
+pub fn user_json(id: u64, bot: bool) -> Value {
+	json!({
+		"id": id.to_string(),
+		"username": format!("user{}", id % 1000),
+		"global_name": null,
+		"avatar": null,
+		"bot": bot,
+	})
+}
+
+pub fn member_json(guild_id: u64, user_id: u64, bot: bool) -> Value {
+	json!({
+		"user": user_json(user_id, bot),
+		"guild_id": guild_id.to_string(),
+		"nick": null,
+		"roles": [],
+		"joined_at": TIMESTAMP,
+		"deaf": false,
+		"mute": false,
+		"flags": 0,
+	})
+}
+
+pub fn message_json(id: u64, channel_id: u64, content: &str) -> Value {
+	json!({
+		"id": id.to_string(),
+		"channel_id": channel_id.to_string(),
+		"author": user_json(APP_ID, true),
+		"content": content,
+		"timestamp": TIMESTAMP,
+		"edited_timestamp": null,
+		"tts": false,
+		"mention_everyone": false,
+		"mentions": [],
+		"mention_roles": [],
+		"attachments": [],
+		"embeds": [{ "type": "rich", "title": "vote", "fields": [] }],
+		"pinned": false,
+		"type": 0,
+	})
+}
+
+pub fn user(id: u64, bot: bool) -> User {
+	serde_json::from_value(user_json(id, bot)).unwrap()
+}
+
+pub fn member(user_id: u64, bot: bool) -> Member {
+	serde_json::from_value(member_json(GUILD_ID, user_id, bot)).unwrap()
+}
+
+fn interaction_json(id: u64, kind: u8, token: &str, data: Value) -> Value {
+	json!({
+		"id": id.to_string(),
+		"application_id": APP_ID.to_string(),
+		"type": kind,
+		"data": data,
+		"guild_id": GUILD_ID.to_string(),
+		"channel_id": COMMAND_CHANNEL_ID.to_string(),
+		"member": member_json(GUILD_ID, ADMIN_ID, false),
+		"token": token,
+		"version": 1,
+		"locale": "ja",
+		"entitlements": [],
+		"attachment_size_limit": 8388608,
+	})
+}
+
+// /estella user reserve user:<user_id> name:<name>
+pub fn reserve_command(user_id: u64, name: &str) -> CommandInteraction {
+	let data = json!({
+		"id": "900000000000000700",
+		"name": "estella",
+		"type": 1,
+		"options": [{
+			"name": "user",
+			"type": 2,
+			"options": [{
+				"name": "reserve",
+				"type": 1,
+				"options": [
+					{ "name": "user", "type": 6, "value": user_id.to_string() },
+					{ "name": "name", "type": 3, "value": name },
+				],
+			}],
+		}],
+	});
+	serde_json::from_value(interaction_json(COMMAND_INTERACTION_ID, 2, COMMAND_TOKEN, data)).unwrap()
+}
+
+// ORIGINAL_MESSAGE_IDのメッセージのボタンを押した時のINTERACTION_CREATE
+pub fn button_interaction_json(custom_id: &str) -> Value {
+	let mut interaction = interaction_json(
+		BUTTON_INTERACTION_ID,
+		3,
+		BUTTON_TOKEN,
+		json!({ "custom_id": custom_id, "component_type": 2 }),
+	);
+	interaction["message"] = message_json(ORIGINAL_MESSAGE_ID, COMMAND_CHANNEL_ID, "");
+	interaction
+}
@@ -0,0 +1,152 @@
+use super::discord::{self, FakeDiscord, Request};
+use super::fixtures;
+use crate::components;
+use crate::configs::ConfigData;
+use crate::state::AppState;
+use entity::enums::AuditAction;
+use entity::{AuditLog, AuditLogBehavior};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection, EntityTrait};
+use serde_json::Value;
+use serenity::all::{ApplicationId, GatewayIntents, ShardId, ShardInfo};
+use serenity::client::{ClientBuilder, Context};
+use serenity::gateway::{Shard, ShardMessenger, ShardRunner, ShardRunnerOptions};
+use serenity::http::HttpBuilder;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::Mutex;
+
+const TOKEN: &str = "test-token";
+
+/*
+イベントハンドラーをそのまま呼び出すためのテスト環境
+DBはマイグレーションを当てたSQLiteのインメモリ、DiscordはFakeDiscordに向けたHttpを使う
+ContextのShardMessengerはFakeDiscordのゲートウェイに繋いだShardRunnerから作るので、
+コレクター (await_component_interaction など) には dispatch でイベントを届けられる
+*/
+pub struct Harness {
+	pub ctx: Context,
+	pub state: Arc<AppState>,
+	pub discord: Arc<FakeDiscord>,
+	events: UnboundedSender<(String, Value)>,
+}
+
+impl Harness {
+	pub async fn new() -> Self {
+		let config = serde_yaml::from_str::<ConfigData>(&format!(
+			"token: {}\nbot_id: {}\nowner_id: {}\ndb_url: 'sqlite::memory:'\ndeepgram_token: ''\nchatgpt_token: ''\n",
+			TOKEN,
+			fixtures::APP_ID,
+			fixtures::ADMIN_ID
+		))
+		.unwrap();
+		components::init_secret(config.get_component_secret());
+
+		// sqlite::memory: はコネクションごとに別のDBになるが、sea-ormはSQLiteのプールを1本にするので問題ない
+		let db = Database::connect(config.get_db_url().as_str()).await.unwrap();
+		Migrator::up(&db, None).await.unwrap();
+
+		let discord = Arc::new(FakeDiscord::default());
+		let http_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let http_url = format!("http://{}", http_listener.local_addr().unwrap());
+		tokio::spawn(discord::serve_http(http_listener, Arc::clone(&discord)));
+
+		let (events, events_rx) = mpsc::unbounded_channel();
+		let gateway_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let gateway_url = format!("ws://{}", gateway_listener.local_addr().unwrap());
+		tokio::spawn(discord::serve_gateway(gateway_listener, events_rx));
+
+		let http = HttpBuilder::new(TOKEN)
+			.proxy(http_url)
+			.ratelimiter_disabled(true)
+			.application_id(ApplicationId::new(fixtures::APP_ID))
+			.build();
+		let client = ClientBuilder::new_with_http(http, GatewayIntents::all()).await.unwrap();
+
+		let shard_info = ShardInfo {
+			id: ShardId(0),
+			total: 1,
+		};
+		let shard = Shard::new(
+			Arc::new(Mutex::new(gateway_url)),
+			TOKEN,
+			shard_info,
+			GatewayIntents::all(),
+			None,
+		)
+		.await
+		.unwrap();
+		let mut runner = ShardRunner::new(ShardRunnerOptions {
+			data: Arc::clone(&client.data),
+			event_handlers: vec![],
+			raw_event_handlers: vec![],
+			framework: None,
+			manager: Arc::clone(&client.shard_manager),
+			shard,
+			voice_manager: None,
+			cache: Arc::clone(&client.cache),
+			http: Arc::clone(&client.http),
+		});
+		let ctx = Context {
+			data: Arc::clone(&client.data),
+			shard: ShardMessenger::new(&runner),
+			shard_id: shard_info.id,
+			http: Arc::clone(&client.http),
+			cache: Arc::clone(&client.cache),
+		};
+		tokio::spawn(async move { runner.run().await });
+
+		let state = Arc::new(AppState::new(config, db, Arc::clone(&client.shard_manager)));
+		client.data.write().await.insert::<AppState>(Arc::clone(&state));
+		// ClientBuilderが起動時に呼んだものは除く
+		discord.clear();
+
+		Self {
+			ctx,
+			state,
+			discord,
+			events,
+		}
+	}
+
+	pub fn db(&self) -> &DatabaseConnection {
+		self.state.db()
+	}
+
+	pub fn requests(&self) -> Vec<Request> {
+		self.discord.requests()
+	}
+
+	pub fn called(&self, method: &str, path: &str) -> bool {
+		self.discord.find(method, path).is_some()
+	}
+
+	pub async fn audit_actions(&self) -> Vec<AuditAction> {
+		AuditLogBehavior::find()
+			.all(self.db())
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|v: AuditLog| v.action)
+			.collect()
+	}
+
+	// ゲートウェイからイベントを送る
+	pub fn dispatch(&self, name: &str, data: Value) {
+		self.events.send((name.to_string(), data)).unwrap();
+	}
+
+	// コレクターが登録されるまでの待ち合わせができないので、method pathが呼ばれるまで繰り返し送る
+	pub async fn dispatch_until(&self, name: &str, data: Value, method: &str, path: &str) -> Request {
+		for _ in 0..100 {
+			self.dispatch(name, data.clone());
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			if let Some(request) = self.discord.find(method, path) {
+				return request;
+			}
+		}
+		panic!("{} {} was not called: {:#?}", method, path, self.requests());
+	}
+}
@@ -0,0 +1,149 @@
+use super::fixtures::{self, AUTH_ROLE_ID, BOT_ROLE_ID, GUILD_ID, LOG_CHANNEL_ID, MAIN_ROLE_ID, MAIN_UID};
+use super::harness::Harness;
+use crate::events::member_add_event;
+use chrono::Utc;
+use entity::enums::{AccountType, AuditAction};
+use sea_orm::{ActiveModelTrait, DbErr, IntoActiveModel};
+use serenity::all::GuildId;
+
+async fn setup() -> Harness {
+	let h = Harness::new().await;
+	h.state.guild_configs().insert(fixtures::guild_config()).await.unwrap();
+	h
+}
+
+async fn insert_confirmed(h: &Harness) {
+	fixtures::confirmed_main(MAIN_UID)
+		.into_active_model()
+		.insert(h.db())
+		.await
+		.unwrap();
+}
+
+#[tokio::test]
+async fn admits_confirmed_account() {
+	let h = setup().await;
+	insert_confirmed(&h).await;
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, false)).await;
+
+	let main = h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap();
+	assert!(main.is_some_and(|v| !v.is_leaved));
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_none());
+	let user_data = h.state.user_data().find(MAIN_UID).await.unwrap().unwrap();
+	assert!(user_data.glacialeur.is_some());
+
+	assert!(h.called(
+		"PUT",
+		&format!("/guilds/{}/members/{}/roles/{}", GUILD_ID, MAIN_UID, AUTH_ROLE_ID)
+	));
+	assert!(h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert!(!h.called("DELETE", &format!("/guilds/{}/members/{}", GUILD_ID, MAIN_UID)));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::MemberAdmit]);
+}
+
+#[tokio::test]
+async fn admit_twice_fails_without_changes() {
+	let h = setup().await;
+	insert_confirmed(&h).await;
+	let confirmed = fixtures::confirmed_main(MAIN_UID);
+	let service = h.state.account_service();
+
+	assert!(service.admit(&confirmed, Utc::now(), 0).await.is_ok());
+	// 2回目は承認済みの行が無いので、トランザクションごと戻る
+	assert!(matches!(
+		service.admit(&confirmed, Utc::now(), 0).await,
+		Err(DbErr::RecordNotFound(_))
+	));
+	assert!(h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn grants_onboarding_roles() {
+	let h = setup().await;
+	insert_confirmed(&h).await;
+	h.state
+		.guild_configs()
+		.replace_onboarding_roles(GUILD_ID, AccountType::Main, &[MAIN_ROLE_ID])
+		.await
+		.unwrap();
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, false)).await;
+
+	for role_id in [MAIN_ROLE_ID, AUTH_ROLE_ID] {
+		assert!(h.called(
+			"PUT",
+			&format!("/guilds/{}/members/{}/roles/{}", GUILD_ID, MAIN_UID, role_id)
+		));
+	}
+	assert!(h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn kicks_unconfirmed_member() {
+	let h = setup().await;
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, false)).await;
+
+	assert!(h.called("DELETE", &format!("/guilds/{}/members/{}", GUILD_ID, MAIN_UID)));
+	assert!(h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert!(h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+	assert_eq!(h.audit_actions().await, vec![AuditAction::MemberKick]);
+}
+
+#[tokio::test]
+async fn gives_bot_role_to_bot() {
+	let h = setup().await;
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, true)).await;
+
+	assert!(h.called(
+		"PUT",
+		&format!("/guilds/{}/members/{}/roles/{}", GUILD_ID, MAIN_UID, BOT_ROLE_ID)
+	));
+	assert!(!h.called("DELETE", &format!("/guilds/{}/members/{}", GUILD_ID, MAIN_UID)));
+	assert!(h.audit_actions().await.is_empty());
+}
+
+#[tokio::test]
+async fn reverts_admission_when_role_grant_fails() {
+	let h = setup().await;
+	insert_confirmed(&h).await;
+	h.discord.fail(
+		"PUT",
+		&format!("/guilds/{}/members/{}/roles/{}", GUILD_ID, MAIN_UID, AUTH_ROLE_ID),
+	);
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, false)).await;
+
+	// 承認済みに戻り、次の入鯖で再度処理できる
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_some());
+	assert!(h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+	assert!(h.state.user_data().find(MAIN_UID).await.unwrap().is_none());
+	assert!(!h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert!(h.audit_actions().await.is_empty());
+}
+
+#[tokio::test]
+async fn ignores_guild_without_white_list() {
+	let h = Harness::new().await;
+	let mut guild_config = fixtures::guild_config();
+	guild_config.white_list = false;
+	h.state.guild_configs().insert(guild_config).await.unwrap();
+
+	member_add_event::execute(h.ctx.clone(), GuildId::new(GUILD_ID), fixtures::member(MAIN_UID, false)).await;
+
+	assert!(h.requests().is_empty());
+}
@@ -0,0 +1,105 @@
+use super::fixtures::{self, ADMIN_ID, GUILD_ID, LOG_CHANNEL_ID, MAIN_UID, SUB_UID};
+use super::harness::Harness;
+use crate::events::member_remove_event;
+use entity::enums::AuditAction;
+use serenity::all::GuildId;
+
+async fn setup() -> Harness {
+	let h = Harness::new().await;
+	h.state.guild_configs().insert(fixtures::guild_config()).await.unwrap();
+	// サブアカウントの承認者 (first_cert) もメインアカウントである必要がある
+	h.state
+		.accounts()
+		.insert_main(fixtures::main_account(ADMIN_ID))
+		.await
+		.unwrap();
+	h.state
+		.accounts()
+		.insert_main(fixtures::main_account(MAIN_UID))
+		.await
+		.unwrap();
+	h.state
+		.accounts()
+		.insert_sub(fixtures::sub_account(SUB_UID, MAIN_UID))
+		.await
+		.unwrap();
+	h
+}
+
+#[tokio::test]
+async fn main_leave_bans_and_kicks_subs() {
+	let h = setup().await;
+
+	member_remove_event::execute(
+		h.ctx.clone(),
+		GuildId::new(GUILD_ID),
+		fixtures::user(MAIN_UID, false),
+		Some(fixtures::member(MAIN_UID, false)),
+	)
+	.await;
+
+	let main = h.state.accounts().main(GUILD_ID, MAIN_UID).await.unwrap().unwrap();
+	assert!(main.is_leaved);
+	// サブアカウントの登録はキックだけでは消えない (退出イベントで消える)
+	assert!(h.state.accounts().sub(GUILD_ID, SUB_UID).await.unwrap().is_some());
+
+	assert!(h.called("DELETE", &format!("/guilds/{}/members/{}", GUILD_ID, SUB_UID)));
+	assert!(h.called("PUT", &format!("/guilds/{}/bans/{}", GUILD_ID, MAIN_UID)));
+	assert!(h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert_eq!(
+		h.audit_actions().await,
+		vec![AuditAction::MemberKick, AuditAction::MemberBan]
+	);
+}
+
+#[tokio::test]
+async fn main_leave_without_member_data_is_not_banned() {
+	let h = setup().await;
+
+	member_remove_event::execute(
+		h.ctx.clone(),
+		GuildId::new(GUILD_ID),
+		fixtures::user(MAIN_UID, false),
+		None,
+	)
+	.await;
+
+	assert!(
+		h.state
+			.accounts()
+			.main(GUILD_ID, MAIN_UID)
+			.await
+			.unwrap()
+			.unwrap()
+			.is_leaved
+	);
+	assert!(!h.called("PUT", &format!("/guilds/{}/bans/{}", GUILD_ID, MAIN_UID)));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::MemberKick]);
+}
+
+#[tokio::test]
+async fn sub_leave_removes_account() {
+	let h = setup().await;
+
+	member_remove_event::execute(
+		h.ctx.clone(),
+		GuildId::new(GUILD_ID),
+		fixtures::user(SUB_UID, false),
+		Some(fixtures::member(SUB_UID, false)),
+	)
+	.await;
+
+	assert!(h.state.accounts().sub(GUILD_ID, SUB_UID).await.unwrap().is_none());
+	assert!(
+		!h.state
+			.accounts()
+			.main(GUILD_ID, MAIN_UID)
+			.await
+			.unwrap()
+			.unwrap()
+			.is_leaved
+	);
+	assert!(!h.called("PUT", &format!("/guilds/{}/bans/{}", GUILD_ID, SUB_UID)));
+	assert!(h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::MemberRemove]);
+}
@@ -0,0 +1,12 @@
+/*
+FakeDiscordとSQLiteのインメモリDBを使った結合テスト
+Harness::new で環境を作り、イベントハンドラーを直接呼び出してDBの行とDiscordへのリクエストを確認する
+*/
+mod discord;
+mod fixtures;
+mod harness;
+
+mod member_add;
+mod member_remove;
+mod reserve;
+mod vote;
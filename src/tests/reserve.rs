@@ -0,0 +1,103 @@
+use super::fixtures::{
+	self, APP_ID, BUTTON_INTERACTION_ID, BUTTON_TOKEN, COMMAND_INTERACTION_ID, COMMAND_TOKEN, GUILD_ID, LOG_CHANNEL_ID,
+	MAIN_UID,
+};
+use super::harness::Harness;
+use crate::commands;
+use crate::components::{make_custom_id, RejectVotePayload};
+use crate::events::ready_event::ADD_PENDING_USERS;
+use chrono::{Duration, Utc};
+use entity::enums::AccountType;
+use tokio::task::JoinHandle;
+
+fn original(token: &str) -> String {
+	format!("/webhooks/{}/{}/messages/@original", APP_ID, token)
+}
+
+fn callback(interaction_id: u64, token: &str) -> String {
+	format!("/interactions/{}/{}/callback", interaction_id, token)
+}
+
+async fn setup() -> Harness {
+	let h = Harness::new().await;
+	h.state.guild_configs().insert(fixtures::guild_config()).await.unwrap();
+	h
+}
+
+fn start_reserve(h: &Harness) -> JoinHandle<()> {
+	tokio::spawn(commands::interaction_route(
+		h.ctx.clone(),
+		fixtures::reserve_command(MAIN_UID, "main"),
+	))
+}
+
+// 確認メッセージが出るのを待ってからボタンを押す
+async fn press(h: &Harness, task: JoinHandle<()>, custom_id: &str) {
+	h.discord.wait_for("GET", &original(COMMAND_TOKEN)).await;
+	h.dispatch_until(
+		"INTERACTION_CREATE",
+		fixtures::button_interaction_json(custom_id),
+		"POST",
+		&callback(BUTTON_INTERACTION_ID, BUTTON_TOKEN),
+	)
+	.await;
+	tokio::time::timeout(std::time::Duration::from_secs(5), task)
+		.await
+		.unwrap()
+		.unwrap();
+}
+
+#[tokio::test]
+async fn reserve_starts_vote() {
+	let h = setup().await;
+
+	let task = start_reserve(&h);
+	press(&h, task, &format!("ok_{}", MAIN_UID)).await;
+
+	assert!(h.called("POST", &callback(COMMAND_INTERACTION_ID, COMMAND_TOKEN)));
+	let vote = h
+		.discord
+		.find("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID))
+		.expect("vote message is not sent");
+	assert_eq!(
+		vote.body["components"][0]["components"][0]["custom_id"],
+		make_custom_id(&RejectVotePayload(MAIN_UID))
+	);
+	assert!(h.called("PATCH", &original(BUTTON_TOKEN)));
+
+	let pending = h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().unwrap();
+	assert_eq!(pending.name.as_deref(), Some("main"));
+	assert_eq!(pending.account_type, AccountType::Main);
+	assert!(pending.message_id.get() >= fixtures::GENERATED_ID_BASE);
+	assert!(pending.end_voting.unwrap() > Utc::now() + Duration::days(6));
+	assert!(ADD_PENDING_USERS.lock().await.iter().any(|v| v.uid == MAIN_UID));
+}
+
+#[tokio::test]
+async fn reserve_cancel_does_nothing() {
+	let h = setup().await;
+
+	let task = start_reserve(&h);
+	press(&h, task, &format!("cancel_{}", MAIN_UID)).await;
+
+	assert!(!h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn reserve_rejects_registered_user() {
+	let h = setup().await;
+	h.state
+		.accounts()
+		.insert_main(fixtures::main_account(MAIN_UID))
+		.await
+		.unwrap();
+
+	start_reserve(&h).await.unwrap();
+
+	assert!(h.called("PATCH", &original(COMMAND_TOKEN)));
+	// 確認ボタンは出さない
+	assert!(!h.called("GET", &original(COMMAND_TOKEN)));
+	assert!(!h.called("POST", &format!("/channels/{}/messages", LOG_CHANNEL_ID)));
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+}
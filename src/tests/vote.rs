@@ -0,0 +1,137 @@
+use super::fixtures::{self, ADMIN_ID, GUILD_ID, LOG_CHANNEL_ID, MAIN_UID, VOTE_MESSAGE_ID};
+use super::harness::Harness;
+use crate::components;
+use crate::events::ready_event::{self, DEL_PENDING_USERS};
+use chrono::{Duration, Utc};
+use entity::enums::{AccountType, AuditAction};
+use entity::PendingAccount;
+
+fn vote_message() -> String {
+	format!("/channels/{}/messages/{}", LOG_CHANNEL_ID, VOTE_MESSAGE_ID)
+}
+
+async fn setup() -> (Harness, PendingAccount) {
+	let h = Harness::new().await;
+	h.state.guild_configs().insert(fixtures::guild_config()).await.unwrap();
+	let pending = h
+		.state
+		.accounts()
+		.insert_pending(fixtures::pending_main(MAIN_UID, Utc::now() - Duration::minutes(1)))
+		.await
+		.unwrap();
+	(h, pending)
+}
+
+#[tokio::test]
+async fn end_vote_approves_main() {
+	let (h, pending) = setup().await;
+
+	ready_event::end_vote_main_process(&h.ctx, &pending).await;
+
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+	let confirmed = h.state.accounts().confirmed(GUILD_ID, MAIN_UID).await.unwrap().unwrap();
+	assert_eq!(confirmed.account_type, AccountType::Main);
+	assert_eq!(confirmed.name, "main");
+
+	assert!(h.called("GET", &vote_message()));
+	let edit = h
+		.discord
+		.find("PATCH", &vote_message())
+		.expect("vote message is not edited");
+	assert_eq!(edit.body["components"], serde_json::json!([]));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::VoteApprove]);
+}
+
+#[tokio::test]
+async fn end_vote_reverts_when_edit_fails() {
+	let (h, pending) = setup().await;
+	h.discord.fail("PATCH", &vote_message());
+
+	ready_event::end_vote_main_process(&h.ctx, &pending).await;
+
+	// 申請中に戻る
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_some());
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_none());
+	assert!(h.audit_actions().await.is_empty());
+}
+
+#[tokio::test]
+async fn end_votes_retries_when_edit_fails() {
+	let (h, pending) = setup().await;
+	let mut pending_users = vec![pending];
+	h.discord.fail("PATCH", &vote_message());
+
+	// 失敗した投票は残る
+	ready_event::end_votes(&h.ctx, &mut pending_users).await;
+	assert_eq!(pending_users.len(), 1);
+
+	// 次の確認で承認される
+	h.discord.recover("PATCH", &vote_message());
+	ready_event::end_votes(&h.ctx, &mut pending_users).await;
+	assert!(pending_users.is_empty());
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_some());
+	assert_eq!(h.audit_actions().await, vec![AuditAction::VoteApprove]);
+}
+
+#[tokio::test]
+async fn reject_vote_deletes_pending() {
+	let (h, _) = setup().await;
+
+	ready_event::reject_vote_process(&h.ctx, GUILD_ID, MAIN_UID, ADMIN_ID).await;
+
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_none());
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_none());
+	assert!(h.called("PATCH", &vote_message()));
+	assert!(DEL_PENDING_USERS.lock().await.contains(&MAIN_UID));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::VoteReject]);
+}
+
+#[tokio::test]
+async fn end_vote_after_reject_is_ignored() {
+	let (h, pending) = setup().await;
+
+	ready_event::reject_vote_process(&h.ctx, GUILD_ID, MAIN_UID, ADMIN_ID).await;
+	h.discord.clear();
+	ready_event::end_vote_main_process(&h.ctx, &pending).await;
+
+	assert!(h
+		.state
+		.accounts()
+		.confirmed(GUILD_ID, MAIN_UID)
+		.await
+		.unwrap()
+		.is_none());
+	assert!(!h.called("PATCH", &vote_message()));
+	assert_eq!(h.audit_actions().await, vec![AuditAction::VoteReject]);
+}
+
+#[tokio::test]
+async fn legacy_reject_on_other_message_is_ignored() {
+	let (h, _) = setup().await;
+
+	// ORIGINAL_MESSAGE_IDのメッセージに付いた、署名の無いreject_ボタン
+	let mc = serde_json::from_value(fixtures::button_interaction_json(&format!("reject_{}", MAIN_UID))).unwrap();
+	components::component_route(h.ctx.clone(), mc).await;
+
+	assert!(h.state.accounts().pending(GUILD_ID, MAIN_UID).await.unwrap().is_some());
+	assert!(!h.called("PATCH", &vote_message()));
+	assert!(h.audit_actions().await.is_empty());
+}
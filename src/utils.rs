@@ -1,4 +1,3 @@
-pub mod client;
 pub mod color;
 pub mod convert;
 pub mod enums;
@@ -1,5 +1,7 @@
 use num_traits::cast::ToPrimitive;
 use sha3::{Digest, Sha3_256};
+use std::error::Error;
+use std::fmt::Display;
 
 /*
 	Glacialeur
@@ -9,8 +11,66 @@ use sha3::{Digest, Sha3_256};
 	2 => Silence
 	4 => Avespoir
 	8 => Estella
+
+	IDの中身 (64bit、discord_idとXORしてから36進数にする)
+	<comp_discord_id: 8bit><join_unixtime: 32bit><version: 8bit><check_hash: 16bit>
+	join_unixtimeはサーバー作成日からの経過秒数
 */
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlacialeurError {
+	// サーバー作成日より前、またはu32に収まらない入鯖時間
+	JoinTimeOutOfRange(i64),
+	// 36進数として読めない、またはu64に収まらない
+	InvalidFormat,
+	// 別のユーザーのGlacialeur
+	DiscordIdMismatch,
+	// チェックハッシュが一致しない
+	HashMismatch,
+	// 正しいGlacialeurだが、登録されている入鯖日やバージョンと一致しない
+	InfoMismatch {
+		expected: GlacialeurInfo,
+		actual: GlacialeurInfo,
+	},
+}
+
+impl Error for GlacialeurError {}
+
+impl Display for GlacialeurError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			GlacialeurError::JoinTimeOutOfRange(v) => write!(f, "入鯖時間が範囲外です: {}", v),
+			GlacialeurError::InvalidFormat => write!(f, "Glacialeurの形式が正しくありません"),
+			GlacialeurError::DiscordIdMismatch => write!(f, "ユーザーが一致しません"),
+			GlacialeurError::HashMismatch => write!(f, "チェックハッシュが一致しません"),
+			GlacialeurError::InfoMismatch { expected, actual } => write!(
+				f,
+				"登録情報と一致しません (入鯖時間: {} / {}, バージョン: {} / {})",
+				actual.join_unixtime, expected.join_unixtime, actual.version, expected.version
+			),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlacialeurInfo {
+	pub version: u8,
+	// サーバー作成日からの経過秒数
+	pub join_unixtime: i64,
+}
+
+impl GlacialeurInfo {
+	pub fn version_name(&self) -> &'static str {
+		match self.version {
+			1 => "None",
+			2 => "Silence",
+			4 => "Avespoir",
+			8 => "Estella",
+			_ => "Unknown",
+		}
+	}
+}
+
 fn rotr16(value: u16, shift: u16) -> u16 {
 	(value >> shift) | (value << (16 - shift))
 }
@@ -128,17 +188,35 @@ fn to_string_36(id: u64) -> String {
 	return result.chars().rev().collect();
 }
 
-pub fn generate(discord_id: u64, version: u8, join_unixtime_: i64) -> String {
-	let join_unixtime = join_unixtime_.to_u32().unwrap();
-	let comp_discord_id = gen_comp_disco_id(&discord_id);
+fn from_string_36(id: &str) -> Option<u64> {
+	if id.is_empty() {
+		return None;
+	}
+
+	let mut result: u64 = 0;
+	for c in id.chars() {
+		result = result.checked_mul(36)?.checked_add(c.to_digit(36)?.to_u64().unwrap())?;
+	}
+
+	return Some(result);
+}
 
+fn calc_check_hash(discord_id: u64, comp_discord_id: u8, join_unixtime: u32, version: u8) -> u16 {
 	let mut check_hash_id: u128 = comp_discord_id.to_u128().unwrap() << 120;
 	check_hash_id |= discord_id.to_u128().unwrap() << 56;
 	check_hash_id |= join_unixtime.to_u128().unwrap() << 24;
 	check_hash_id |= version.to_u128().unwrap() << 16;
 	check_hash_id >>= 2 * 8;
 
-	let check_hash = gen_check_hash(check_hash_id);
+	return gen_check_hash(check_hash_id);
+}
+
+pub fn generate(discord_id: u64, version: u8, join_unixtime_: i64) -> Result<String, GlacialeurError> {
+	let join_unixtime = join_unixtime_
+		.to_u32()
+		.ok_or(GlacialeurError::JoinTimeOutOfRange(join_unixtime_))?;
+	let comp_discord_id = gen_comp_disco_id(&discord_id);
+	let check_hash = calc_check_hash(discord_id, comp_discord_id, join_unixtime, version);
 
 	let mut result_id: u64 = comp_discord_id.to_u64().unwrap() << 56;
 	result_id |= join_unixtime.to_u64().unwrap() << 24;
@@ -147,5 +225,185 @@ pub fn generate(discord_id: u64, version: u8, join_unixtime_: i64) -> String {
 
 	result_id ^= discord_id;
 
-	return to_string_36(result_id);
+	return Ok(to_string_36(result_id));
+}
+
+pub fn decode(id: &str, discord_id: u64) -> Result<GlacialeurInfo, GlacialeurError> {
+	let result_id = from_string_36(id.trim()).ok_or(GlacialeurError::InvalidFormat)? ^ discord_id;
+
+	let comp_discord_id = (result_id >> 56).to_u8().unwrap();
+	let join_unixtime = ((result_id >> 24) & 0xFFFF_FFFF).to_u32().unwrap();
+	let version = ((result_id >> 16) & 0xFF).to_u8().unwrap();
+	let check_hash = (result_id & 0xFFFF).to_u16().unwrap();
+
+	if comp_discord_id != gen_comp_disco_id(&discord_id) {
+		return Err(GlacialeurError::DiscordIdMismatch);
+	}
+	if check_hash != calc_check_hash(discord_id, comp_discord_id, join_unixtime, version) {
+		return Err(GlacialeurError::HashMismatch);
+	}
+
+	return Ok(GlacialeurInfo {
+		version,
+		join_unixtime: join_unixtime.into(),
+	});
+}
+
+// decodeした上で、登録されている入鯖時間とバージョンから作られたものか確認する
+pub fn verify(id: &str, discord_id: u64, version: u8, join_unixtime: i64) -> Result<GlacialeurInfo, GlacialeurError> {
+	let actual = decode(id, discord_id)?;
+	let expected = GlacialeurInfo { version, join_unixtime };
+	if actual != expected {
+		return Err(GlacialeurError::InfoMismatch { expected, actual });
+	}
+
+	return Ok(actual);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::rngs::StdRng;
+	use rand::{Rng, SeedableRng};
+
+	const CASES: usize = 2000;
+
+	fn rng() -> StdRng {
+		StdRng::seed_from_u64(0x6c61_6369_616c_6575)
+	}
+
+	#[test]
+	fn generate_is_stable() {
+		// 既に保存されているGlacialeurと同じ値になること
+		assert_eq!(generate(900000000000000401, 8, 86400).unwrap(), "YRANLS3OFR28");
+		assert_eq!(generate(123456789012345678, 8, 0).unwrap(), "34MB7Z32QU7A7");
+		assert_eq!(generate(1, 1, 4294967295).unwrap(), "XXXHOQ40RUE6");
+		assert_eq!(generate(u64::MAX, 255, 123456789).unwrap(), "8QRQD340D3IC");
+	}
+
+	#[test]
+	fn round_trip() {
+		let mut rng = rng();
+		for _ in 0..CASES {
+			let discord_id = rng.random::<u64>();
+			let version = rng.random::<u8>();
+			let join_unixtime = rng.random_range(0..=u32::MAX as i64);
+
+			let id = generate(discord_id, version, join_unixtime).unwrap();
+			assert_eq!(
+				decode(&id, discord_id),
+				Ok(GlacialeurInfo { version, join_unixtime }),
+				"{} {} {} {}",
+				discord_id,
+				version,
+				join_unixtime,
+				id
+			);
+			assert_eq!(
+				decode(&id.to_lowercase(), discord_id).map(|v| v.join_unixtime),
+				Ok(join_unixtime)
+			);
+		}
+	}
+
+	#[test]
+	fn round_trip_join_unixtime_bounds() {
+		let mut rng = rng();
+		for _ in 0..CASES {
+			let discord_id = rng.random::<u64>();
+			for join_unixtime in [0, 1, u32::MAX as i64 - 1, u32::MAX as i64] {
+				let id = generate(discord_id, 8, join_unixtime).unwrap();
+				assert_eq!(decode(&id, discord_id).map(|v| v.join_unixtime), Ok(join_unixtime));
+			}
+		}
+	}
+
+	#[test]
+	fn rejects_join_unixtime_out_of_range() {
+		let mut rng = rng();
+		for join_unixtime in [-1, i64::MIN, u32::MAX as i64 + 1, i64::MAX] {
+			assert_eq!(
+				generate(rng.random(), 8, join_unixtime),
+				Err(GlacialeurError::JoinTimeOutOfRange(join_unixtime))
+			);
+		}
+		for _ in 0..CASES {
+			let join_unixtime = if rng.random() {
+				rng.random_range(i64::MIN..0)
+			} else {
+				rng.random_range(u32::MAX as i64 + 1..=i64::MAX)
+			};
+			assert_eq!(
+				generate(rng.random(), rng.random(), join_unixtime),
+				Err(GlacialeurError::JoinTimeOutOfRange(join_unixtime))
+			);
+		}
+	}
+
+	#[test]
+	fn rejects_other_discord_id() {
+		let mut rng = rng();
+		for _ in 0..CASES {
+			let discord_id = rng.random::<u64>();
+			let other_id = rng.random::<u64>();
+			if discord_id == other_id {
+				continue;
+			}
+
+			let id = generate(discord_id, rng.random(), rng.random_range(0..=u32::MAX as i64)).unwrap();
+			assert!(decode(&id, other_id).is_err(), "{} {} {}", discord_id, other_id, id);
+		}
+	}
+
+	#[test]
+	fn rejects_tampered_id() {
+		let mut rng = rng();
+		for _ in 0..CASES {
+			let discord_id = rng.random::<u64>();
+			let id = generate(discord_id, rng.random(), rng.random_range(0..=u32::MAX as i64)).unwrap();
+
+			let mut chars: Vec<char> = id.chars().collect();
+			let index = rng.random_range(0..chars.len());
+			let digit = (chars[index].to_digit(36).unwrap() + rng.random_range(1..36)) % 36;
+			chars[index] = std::char::from_digit(digit, 36).unwrap().to_ascii_uppercase();
+			let tampered: String = chars.into_iter().collect();
+
+			assert!(
+				decode(&tampered, discord_id).is_err(),
+				"{} {} {}",
+				discord_id,
+				id,
+				tampered
+			);
+		}
+	}
+
+	#[test]
+	fn verify_detects_info_mismatch() {
+		let mut rng = rng();
+		for _ in 0..CASES {
+			let discord_id = rng.random::<u64>();
+			let version = rng.random::<u8>();
+			let join_unixtime = rng.random_range(1..u32::MAX as i64);
+			let id = generate(discord_id, version, join_unixtime).unwrap();
+
+			assert!(verify(&id, discord_id, version, join_unixtime).is_ok());
+			assert!(matches!(
+				verify(&id, discord_id, version, join_unixtime + 1),
+				Err(GlacialeurError::InfoMismatch { .. })
+			));
+			assert!(matches!(
+				verify(&id, discord_id, version.wrapping_add(1), join_unixtime),
+				Err(GlacialeurError::InfoMismatch { .. })
+			));
+		}
+	}
+
+	#[test]
+	fn rejects_invalid_format() {
+		assert_eq!(decode("", 1), Err(GlacialeurError::InvalidFormat));
+		assert_eq!(decode("YRANLS3-OFR28", 1), Err(GlacialeurError::InvalidFormat));
+		// u64::MAXは36進数で13桁
+		assert_eq!(decode("ZZZZZZZZZZZZZZ", 1), Err(GlacialeurError::InvalidFormat));
+	}
 }
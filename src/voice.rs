@@ -1,16 +1,22 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::{Arc, LazyLock}};
 
 use receive::Receiver;
-use serenity::all::{ChannelId, Context, GuildId};
+use serenity::all::{ChannelId, Context, GuildId, VoiceState};
 use songbird::{CoreEvent, Songbird};
+use tokio::sync::RwLock;
 
-use crate::STATIC_COMPONENTS;
+use crate::state::AppState;
 
+pub(crate) mod addressing;
+mod preprocess;
 mod receive;
-mod speak2text;
+pub(crate) mod speak2text;
 mod text_talk;
 pub(crate) mod text2speak;
 
+// 接続中のセッション (voice_state_updateをルーティングするため)
+static RECEIVERS: LazyLock<RwLock<HashMap<GuildId, Receiver>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
 pub(crate) async fn connect_voice_channel(
 	ctx: &Context,
 	target_guild_id: GuildId,
@@ -25,19 +31,20 @@ pub(crate) async fn connect_voice_channel(
 		let handler_lock = manager.get_or_insert(target_guild_id);
 		let mut handler = handler_lock.lock().await;
 
-		let comp_lock = STATIC_COMPONENTS.lock().await;
-		let token = comp_lock.get_config().get_deepgram_token().clone();
-		std::mem::drop(comp_lock);
+		let state = AppState::get(ctx).await;
 
 		let evt_receiver = Receiver::new(
-			Arc::downgrade(&manager),
-			target_guild_id,
+			target_channel_id,
 			Arc::clone(&ctx.http),
 			Arc::downgrade(&handler_lock),
-			token
+			state
 		).await;
 		evt_receiver.data.write().await.start(target_guild_id).await;
 
+		// すでにVCにいる人のミュート状態 (以降はvoice_state_updateで更新する)
+		evt_receiver.move_channel(target_channel_id, &guild_voice_states(ctx, target_guild_id)).await;
+		RECEIVERS.write().await.insert(target_guild_id, evt_receiver.clone());
+
 		handler.add_global_event(CoreEvent::SpeakingStateUpdate.into(), evt_receiver.clone());
 		handler.add_global_event(CoreEvent::VoiceTick.into(), evt_receiver.clone());
 		handler.add_global_event(CoreEvent::ClientDisconnect.into(), evt_receiver);
@@ -48,6 +55,7 @@ pub(crate) async fn connect_voice_channel(
 	} else {
 		// Although we failed to join, we need to clear out existing event handlers on the call.
 		_ = manager.remove(target_guild_id.clone()).await;
+		RECEIVERS.write().await.remove(&target_guild_id);
 
 		Err("Error joining the channel")
 	}
@@ -63,6 +71,8 @@ pub(crate) async fn disconnect_voice_channel(ctx: &Context, target_guild_id: Gui
 }
 
 pub(crate) async fn disconnect_voice_channel_from_manager(manager: &Arc<Songbird>, target_guild_id: GuildId) -> Result<(), String> {
+	RECEIVERS.write().await.remove(&target_guild_id);
+
 	let has_handler = manager.get(target_guild_id).is_some();
 
 	if has_handler {
@@ -75,3 +85,66 @@ pub(crate) async fn disconnect_voice_channel_from_manager(manager: &Arc<Songbird
 		Err("どこにも接続していません。".to_string())
 	}
 }
+
+// キャッシュにあるサーバーのVCの状態 (Bot自身は除く)
+fn guild_voice_states(ctx: &Context, guild_id: GuildId) -> Vec<VoiceState> {
+	let bot_id = ctx.cache.current_user().id;
+	ctx.cache.guild(guild_id)
+		.map(|guild| guild.voice_states.values().filter(|v| v.user_id != bot_id).cloned().collect())
+		.unwrap_or_default()
+}
+
+// ミュートや移動などを、接続中のセッションに伝える
+// 全員がいなくなったら抜けて、全員が別のチャンネルに移動したらついていく
+pub(crate) async fn voice_state_update(ctx: &Context, voice_state: &VoiceState) {
+	let Some(guild_id) = voice_state.guild_id else {
+		return;
+	};
+	let receiver = RECEIVERS.read().await.get(&guild_id).cloned();
+	let Some(receiver) = receiver else {
+		return;
+	};
+
+	let manager = songbird::get(ctx)
+		.await
+		.expect("Songbird Voice client placed in at initialisation.")
+		.clone();
+
+	// Bot自身が移動させられた、もしくは切断された
+	if voice_state.user_id == ctx.cache.current_user().id {
+		match voice_state.channel_id {
+			Some(channel_id) if channel_id != receiver.channel_id().await => {
+				receiver.move_channel(channel_id, &guild_voice_states(ctx, guild_id)).await;
+			},
+			Some(_) => {},
+			None => {
+				let _ = disconnect_voice_channel_from_manager(&manager, guild_id).await;
+			},
+		}
+		return;
+	}
+
+	let left = receiver.voice_state_update(voice_state).await;
+	if !left || !receiver.is_empty().await {
+		return;
+	}
+
+	match voice_state.channel_id {
+		// 最後の一人が別のチャンネルに移動した
+		Some(channel_id) => {
+			log::info!("follow to {}", channel_id);
+			if let Err(e) = manager.join(guild_id, channel_id).await {
+				log::error!("{:?}", e);
+				let _ = disconnect_voice_channel_from_manager(&manager, guild_id).await;
+				return;
+			}
+			receiver.move_channel(channel_id, &guild_voice_states(ctx, guild_id)).await;
+		},
+		// 誰もいなくなった
+		None => {
+			if let Err(e) = disconnect_voice_channel_from_manager(&manager, guild_id).await {
+				log::error!("{}", e);
+			}
+		},
+	}
+}
@@ -0,0 +1,171 @@
+/*
+複数人のボイスチャンネルで、エステラに話しかけているかどうかの判定
+- ウェイクワード (サーバーごとに追加できる)
+- 名前の聞き間違い (音声認識はカタカナ/ひらがな/漢字が揺れるので、かなに揃えてから編集距離で比べる)
+- 直接の質問 (二人称を含む疑問文)
+*/
+
+// どのサーバーでも反応する
+pub(crate) const DEFAULT_WAKE_WORDS: [&str; 2] = ["エステラ", "Estella"];
+// 聞き間違いを許容する名前 (ひらがな)
+const BOT_NAMES: [&str; 1] = ["えすてら"];
+// 名前の編集距離の許容値
+const NAME_MAX_DISTANCE: usize = 1;
+
+const SECOND_PERSON_WORDS: [&str; 6] = ["あなた", "あんた", "きみ", "君", "おまえ", "お前"];
+const QUESTION_ENDINGS: [&str; 7] = ["?", "？", "か", "かな", "の", "なに", "何"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Addressing {
+	WakeWord,
+	Name,
+	Question,
+}
+
+// サーバーの設定 (カンマ区切り) とデフォルトのウェイクワードを合わせる
+pub(crate) fn wake_words(guild_wake_words: Option<&str>) -> Vec<String> {
+	DEFAULT_WAKE_WORDS
+		.iter()
+		.map(|v| v.to_string())
+		.chain(parse_wake_words(guild_wake_words.unwrap_or_default()))
+		.collect()
+}
+
+pub(crate) fn parse_wake_words(text: &str) -> Vec<String> {
+	text.split([',', '、', '，'])
+		.map(|v| v.trim())
+		.filter(|v| !v.is_empty())
+		.map(|v| v.to_string())
+		.collect()
+}
+
+pub(crate) fn detect(text: &str, wake_words: &[String]) -> Option<Addressing> {
+	let normalized = normalize(text);
+	if normalized.is_empty() {
+		return None;
+	}
+
+	if wake_words
+		.iter()
+		.map(|v| normalize(v))
+		.any(|v| !v.is_empty() && normalized.contains(&v))
+	{
+		return Some(Addressing::WakeWord);
+	}
+
+	let chars: Vec<char> = normalized.chars().collect();
+	if BOT_NAMES
+		.iter()
+		.any(|v| contains_fuzzy(&chars, &v.chars().collect::<Vec<_>>(), NAME_MAX_DISTANCE))
+	{
+		return Some(Addressing::Name);
+	}
+
+	if is_direct_question(text, &normalized) {
+		return Some(Addressing::Question);
+	}
+
+	None
+}
+
+fn is_direct_question(text: &str, normalized: &str) -> bool {
+	// normalizeでは?が消えるので、語尾は元のテキストで見る
+	let text = text.trim_end_matches(['。', '.', '!', '！', ' ', '　']);
+	let is_question = QUESTION_ENDINGS.iter().any(|v| text.ends_with(v));
+
+	is_question && SECOND_PERSON_WORDS.iter().any(|v| normalized.contains(&normalize(v)))
+}
+
+// カタカナ・全角英数をひらがな・半角小文字に揃えて、空白や記号、長音を取り除く
+pub(crate) fn normalize(text: &str) -> String {
+	text.chars()
+		.filter_map(|c| {
+			let c = match c {
+				// ァ-ヶ
+				'\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+				// ！-～
+				'\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+				_ => c,
+			};
+			if c.is_whitespace() || c.is_ascii_punctuation() || matches!(c, 'ー' | '～' | '〜' | '、' | '。' | '・' | '「' | '」')
+			{
+				return None;
+			}
+			Some(c.to_ascii_lowercase())
+		})
+		.collect()
+}
+
+// textの中に、wordと編集距離がmax_distance以下の部分があるか
+fn contains_fuzzy(text: &[char], word: &[char], max_distance: usize) -> bool {
+	if word.is_empty() {
+		return false;
+	}
+
+	// 短い部分だと別の言葉に当たりやすいので、word以上の長さだけを見る
+	(word.len()..=word.len() + max_distance).any(|len| {
+		text.windows(len)
+			.any(|window| edit_distance(window, word) <= max_distance)
+	})
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+	let mut prev: Vec<usize> = (0..=b.len()).collect();
+	let mut current = vec![0; b.len() + 1];
+	for (i, ca) in a.iter().enumerate() {
+		current[0] = i + 1;
+		for (j, cb) in b.iter().enumerate() {
+			let cost = if ca == cb { 0 } else { 1 };
+			current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+		}
+		std::mem::swap(&mut prev, &mut current);
+	}
+	prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn defaults() -> Vec<String> {
+		wake_words(None)
+	}
+
+	#[test]
+	fn normalize_kana_and_width() {
+		assert_eq!(normalize("エステラー、元気？"), "えすてら元気");
+		assert_eq!(normalize("ＥＳＴＥＬＬＡ"), "estella");
+	}
+
+	#[test]
+	fn detects_wake_words() {
+		assert_eq!(detect("ねえエステラ、今日の天気は", &defaults()), Some(Addressing::WakeWord));
+		assert_eq!(detect("estella 聞いてる", &defaults()), Some(Addressing::WakeWord));
+
+		let words = wake_words(Some("ステラちゃん, お姫様"));
+		assert_eq!(detect("お姫様ーこっち来て", &words), Some(Addressing::WakeWord));
+	}
+
+	#[test]
+	fn detects_misrecognized_name() {
+		assert_eq!(detect("えすでらさん", &defaults()), Some(Addressing::Name));
+		assert_eq!(detect("エスエラ聞いて", &defaults()), Some(Addressing::Name));
+		// 名前の一部だけでは反応しない
+		assert_eq!(detect("ステラって誰だっけ", &defaults()), None);
+	}
+
+	#[test]
+	fn detects_direct_question() {
+		assert_eq!(detect("君はどう思う？", &defaults()), Some(Addressing::Question));
+		assert_eq!(detect("あなたの好きな食べ物は何", &defaults()), Some(Addressing::Question));
+		// 二人称がない質問や、質問でない二人称は対象外
+		assert_eq!(detect("明日って雨だっけ？", &defaults()), None);
+		assert_eq!(detect("君の言う通りだね", &defaults()), None);
+	}
+
+	#[test]
+	fn parse_guild_wake_words() {
+		assert_eq!(parse_wake_words(" a,b 、,c "), vec!["a", "b", "c"]);
+		assert!(parse_wake_words("").is_empty());
+	}
+}
@@ -0,0 +1,299 @@
+use std::collections::VecDeque;
+
+use rubato::{FftFixedInOut, Resampler};
+
+/*
+音声認識に流す前の処理 (ユーザーごと)
+Discordから届く20msの48kHzステレオを16kHzモノラルにして、VADで発話中と判定された間だけ音声認識に流す
+発話の頭が切れないように、開始前の数フレームは残しておいて発話開始時にまとめて流す
+*/
+
+pub(crate) const INPUT_SAMPLE_RATE: usize = 48000;
+pub(crate) const OUTPUT_SAMPLE_RATE: usize = 16000;
+// voice_tickは20msごと
+const FRAME_MS: usize = 20;
+const INPUT_FRAME_SAMPLES: usize = INPUT_SAMPLE_RATE * FRAME_MS / 1000;
+pub(crate) const OUTPUT_FRAME_SAMPLES: usize = OUTPUT_SAMPLE_RATE * FRAME_MS / 1000;
+
+// これより小さい音は発話とみなさない (約-40dBFS)
+const MIN_SPEECH_RMS: f32 = 0.01;
+// ノイズレベルの何倍で発話とみなすか (約+10dB)
+const SPEECH_RATIO: f32 = 3.0;
+// ノイズレベルの追従速度
+const NOISE_ADAPT_RATE: f32 = 0.05;
+// 発話中のノイズレベルの追従速度
+// 最初から大きい定常ノイズ (ファンなど) を発話とみなし続けないように、発話中もゆっくり追従させる (0.02のノイズで約2秒)
+const VOICED_NOISE_ADAPT_RATE: f32 = 0.002;
+const MIN_NOISE_FLOOR: f32 = 0.001;
+// 連続してこのフレーム数発話していたら開始 (40ms)
+const ATTACK_FRAMES: u32 = 2;
+// 発話が途切れてからこのフレーム数は続ける (300ms)
+const HANGOVER_FRAMES: u32 = 15;
+// 発話開始前に残しておくフレーム数 (200ms)
+const PRE_ROLL_FRAMES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VadState {
+	Silent,
+	Started,
+	Speaking,
+	Ended,
+}
+
+// 音量とノイズレベルの差で判定するVAD
+pub(crate) struct Vad {
+	noise_floor: f32,
+	speech_frames: u32,
+	hangover: u32,
+	is_speaking: bool,
+}
+
+impl Vad {
+	pub(crate) fn new() -> Self {
+		Self {
+			noise_floor: MIN_SPEECH_RMS / SPEECH_RATIO,
+			speech_frames: 0,
+			hangover: 0,
+			is_speaking: false,
+		}
+	}
+
+	pub(crate) fn update(&mut self, rms: f32) -> VadState {
+		let voiced = rms > MIN_SPEECH_RMS && rms > self.noise_floor * SPEECH_RATIO;
+		if rms < self.noise_floor {
+			self.noise_floor = rms.max(MIN_NOISE_FLOOR);
+		} else if !voiced {
+			self.noise_floor += (rms - self.noise_floor) * NOISE_ADAPT_RATE;
+		} else {
+			self.noise_floor += (rms - self.noise_floor) * VOICED_NOISE_ADAPT_RATE;
+		}
+
+		if self.is_speaking {
+			if voiced {
+				self.hangover = HANGOVER_FRAMES;
+			} else if self.hangover > 0 {
+				self.hangover -= 1;
+			} else {
+				self.is_speaking = false;
+				return VadState::Ended;
+			}
+			return VadState::Speaking;
+		}
+
+		if !voiced {
+			self.speech_frames = 0;
+			return VadState::Silent;
+		}
+
+		self.speech_frames += 1;
+		if self.speech_frames < ATTACK_FRAMES {
+			return VadState::Silent;
+		}
+
+		self.speech_frames = 0;
+		self.hangover = HANGOVER_FRAMES;
+		self.is_speaking = true;
+		VadState::Started
+	}
+
+	pub(crate) fn is_speaking(&self) -> bool {
+		self.is_speaking
+	}
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ProcessedVoice {
+	// VADの判定結果
+	pub(crate) speaking: bool,
+	// 発話が終わった (音声認識に区切りを伝える)
+	pub(crate) ended: bool,
+	// 音声認識に流す16kHzモノラルのi16 (20msごと)
+	pub(crate) frames: Vec<Vec<i16>>,
+}
+
+pub(crate) struct VoiceProcessor {
+	resampler: FftFixedInOut<f32>,
+	vad: Vad,
+	pre_roll: VecDeque<Vec<i16>>,
+}
+
+impl VoiceProcessor {
+	pub(crate) fn new() -> Self {
+		let resampler = FftFixedInOut::<f32>::new(INPUT_SAMPLE_RATE, OUTPUT_SAMPLE_RATE, INPUT_FRAME_SAMPLES, 1)
+			.expect("Invalid resampler parameters");
+
+		Self {
+			resampler,
+			vad: Vad::new(),
+			pre_roll: VecDeque::with_capacity(PRE_ROLL_FRAMES + 1),
+		}
+	}
+
+	// 48kHzステレオのフレームを処理する (パケットが届かなかった場合はNone)
+	pub(crate) fn process(&mut self, frame: Option<&[i16]>) -> ProcessedVoice {
+		let (frame, rms) = match frame {
+			Some(frame) => {
+				let mono = downmix(frame);
+				let rms = rms(&mono);
+				(self.resample(&mono), rms)
+			},
+			None => (vec![0; OUTPUT_FRAME_SAMPLES], 0.0),
+		};
+
+		match self.vad.update(rms) {
+			VadState::Silent => {
+				self.pre_roll.push_back(frame);
+				if self.pre_roll.len() > PRE_ROLL_FRAMES {
+					self.pre_roll.pop_front();
+				}
+				ProcessedVoice::default()
+			},
+			VadState::Started => {
+				let mut frames: Vec<_> = self.pre_roll.drain(..).collect();
+				frames.push(frame);
+				ProcessedVoice {
+					speaking: true,
+					ended: false,
+					frames,
+				}
+			},
+			VadState::Speaking => ProcessedVoice {
+				speaking: true,
+				ended: false,
+				frames: vec![frame],
+			},
+			VadState::Ended => ProcessedVoice {
+				speaking: false,
+				ended: true,
+				frames: vec![frame],
+			},
+		}
+	}
+
+	pub(crate) fn is_speaking(&self) -> bool {
+		self.vad.is_speaking()
+	}
+
+	fn resample(&mut self, mono: &[f32]) -> Vec<i16> {
+		match self.resampler.process(&[mono], None) {
+			Ok(mut output) => output.remove(0).into_iter().map(to_i16).collect(),
+			Err(e) => {
+				log::error!("Resample error: {:?}", e);
+				vec![0; OUTPUT_FRAME_SAMPLES]
+			},
+		}
+	}
+}
+
+// ステレオ (LRLR...) をモノラルにする (長さが足りない場合は無音で埋める)
+fn downmix(frame: &[i16]) -> Vec<f32> {
+	let mut mono: Vec<f32> = frame
+		.chunks_exact(2)
+		.take(INPUT_FRAME_SAMPLES)
+		.map(|v| (v[0] as f32 + v[1] as f32) / 2.0 / i16::MAX as f32)
+		.collect();
+	mono.resize(INPUT_FRAME_SAMPLES, 0.0);
+	mono
+}
+
+fn rms(samples: &[f32]) -> f32 {
+	if samples.is_empty() {
+		return 0.0;
+	}
+	(samples.iter().map(|v| v * v).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn to_i16(sample: f32) -> i16 {
+	(sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// 440Hzのステレオ20ms
+	fn tone(amplitude: f32, offset: usize) -> Vec<i16> {
+		(0..INPUT_FRAME_SAMPLES)
+			.flat_map(|i| {
+				let t = (offset * INPUT_FRAME_SAMPLES + i) as f32 / INPUT_SAMPLE_RATE as f32;
+				let v = ((t * 440.0 * std::f32::consts::TAU).sin() * amplitude * i16::MAX as f32) as i16;
+				[v, v]
+			})
+			.collect()
+	}
+
+	#[test]
+	fn downmix_and_resample_to_16k_mono() {
+		let mut processor = VoiceProcessor::new();
+		for i in 0..ATTACK_FRAMES as usize + 5 {
+			for frame in processor.process(Some(&tone(0.5, i))).frames {
+				assert_eq!(frame.len(), OUTPUT_FRAME_SAMPLES);
+			}
+		}
+	}
+
+	#[test]
+	fn opens_only_during_speech() {
+		let mut processor = VoiceProcessor::new();
+
+		// 無音は流さない
+		for _ in 0..PRE_ROLL_FRAMES * 2 {
+			let output = processor.process(None);
+			assert!(!output.speaking);
+			assert!(output.frames.is_empty());
+		}
+		// 小さいノイズも流さない
+		for i in 0..50 {
+			assert!(!processor.process(Some(&tone(0.002, i))).speaking);
+		}
+
+		// 発話開始時は残しておいたフレームもまとめて流す
+		let mut started = None;
+		for i in 0..ATTACK_FRAMES as usize {
+			started = Some(processor.process(Some(&tone(0.5, i))));
+		}
+		let started = started.unwrap();
+		assert!(started.speaking);
+		assert_eq!(started.frames.len(), PRE_ROLL_FRAMES + 1);
+	}
+
+	#[test]
+	fn keeps_open_during_hangover() {
+		let mut processor = VoiceProcessor::new();
+		for i in 0..10 {
+			processor.process(Some(&tone(0.5, i)));
+		}
+		assert!(processor.is_speaking());
+
+		for _ in 0..HANGOVER_FRAMES {
+			let output = processor.process(None);
+			assert!(output.speaking);
+			assert_eq!(output.frames.len(), 1);
+		}
+
+		let output = processor.process(None);
+		assert!(!output.speaking);
+		assert!(output.ended);
+		assert!(processor.process(None).frames.is_empty());
+	}
+
+	#[test]
+	fn short_noise_does_not_open() {
+		let mut vad = Vad::new();
+		for _ in 0..10 {
+			assert_eq!(vad.update(0.5), VadState::Silent);
+			assert_eq!(vad.update(0.0), VadState::Silent);
+		}
+	}
+
+	#[test]
+	fn constant_noise_closes() {
+		let mut vad = Vad::new();
+		// 最初は発話とみなしても、5秒以内に閉じる
+		let ended = (0..250).any(|_| vad.update(0.02) == VadState::Ended);
+		assert!(ended);
+		for _ in 0..250 {
+			assert_eq!(vad.update(0.02), VadState::Silent);
+		}
+	}
+}
@@ -1,40 +1,72 @@
-use std::{collections::HashMap, error::Error, fmt::Display, sync::{Arc, Weak}};
+use std::{collections::HashMap, sync::{Arc, Mutex as StdMutex, Weak}};
 
-use bytes::{BufMut, BytesMut};
-use deepgram::Deepgram;
-use serenity::{all::{GuildId, Http}, async_trait};
+use serenity::{all::{ChannelId, GuildId, Http, VoiceState}, async_trait};
 use songbird::{
-	events::context_data::VoiceTick, model::{id::UserId, payload::{ClientDisconnect, Speaking}}, Call, Event, EventContext, EventHandler, Songbird
+	events::context_data::VoiceTick, model::{id::UserId, payload::{ClientDisconnect, Speaking}}, Call, Event, EventContext, EventHandler
 };
 use tokio::sync::{Mutex, RwLock};
 
-use crate::utils::atomic::TimeoutAtomicBool;
+use crate::{state::AppState, utils::atomic::TimeoutAtomicBool};
 
-use super::{disconnect_voice_channel_from_manager, speak2text::Speak2TextStream, text_talk::TextTalk};
+use super::{preprocess::VoiceProcessor, speak2text::{self, SpeechToTextStream}, text_talk::TextTalk};
 
-#[derive(Debug)]
-struct UnknownError;
+struct UserStream {
+	stream: Box<dyn SpeechToTextStream>,
+	// ダウンミックス、リサンプル、VADの状態 (voice_tickでしか触らないのでstdのMutexで十分)
+	processor: StdMutex<VoiceProcessor>,
+}
+
+// VCにいるユーザーのミュート状態 (voice_state_updateで更新する)
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct UserVoiceState {
+	pub(crate) self_mute: bool,
+	pub(crate) self_deaf: bool,
+	// サーバーミュート
+	pub(crate) mute: bool,
+	// サーバースピーカーミュート
+	pub(crate) deaf: bool,
+}
+
+impl UserVoiceState {
+	// 話せない
+	pub(crate) fn is_muted(&self) -> bool {
+		self.self_mute || self.mute
+	}
 
-impl Error for UnknownError {
-	fn description(&self) -> &str {
-		"Unknown error"
+	// 聞こえていない
+	pub(crate) fn is_deafened(&self) -> bool {
+		self.self_deaf || self.deaf
+	}
+
+	// 話さない、もしくは聞こえていない
+	pub(crate) fn is_away(&self) -> bool {
+		self.is_muted() || self.is_deafened()
 	}
 }
 
-impl Display for UnknownError {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "Unknown error")
+impl From<&VoiceState> for UserVoiceState {
+	fn from(value: &VoiceState) -> Self {
+		Self {
+			self_mute: value.self_mute,
+			self_deaf: value.self_deaf,
+			mute: value.mute,
+			deaf: value.deaf,
+		}
 	}
 }
 
 pub(crate) struct ReceiverData {
 	ssrc2user: HashMap<u32, UserId>,
-	client: Deepgram,
-	user_stream: HashMap<UserId, Speak2TextStream<UnknownError>>,
+	user_stream: HashMap<UserId, UserStream>,
 	user_speaking_state: Arc<RwLock<HashMap<UserId, TimeoutAtomicBool>>>,
+	// 話していない人も含めたVCの参加者 (Bot自身は除く)
+	user_voice_state: Arc<RwLock<HashMap<UserId, UserVoiceState>>>,
 	talking_service: Option<Arc<TextTalk>>,
 	vc_handler: Weak<Mutex<Call>>,
 	http: Arc<Http>,
+	state: Arc<AppState>,
+	// 接続しているチャンネル (ついていった場合は変わる)
+	channel_id: ChannelId,
 }
 
 impl ReceiverData {
@@ -47,7 +79,7 @@ impl ReceiverData {
 			}
 		}
 
-		let talking_service = TextTalk::new(guild_id, Arc::clone(&self.http), Weak::clone(&self.vc_handler.clone()), Arc::clone(&self.user_speaking_state)).await;
+		let talking_service = TextTalk::new(guild_id, Arc::clone(&self.http), Arc::clone(&self.state), Weak::clone(&self.vc_handler.clone()), Arc::clone(&self.user_speaking_state), Arc::clone(&self.user_voice_state)).await;
 		self.talking_service = Some(talking_service);
 	}
 
@@ -57,8 +89,11 @@ impl ReceiverData {
 		}
 
 		let sender = self.talking_service.as_ref().unwrap().create_sender();
-		let stream = Speak2TextStream::new(&self.client, user, sender).await;
-		self.user_stream.insert(user, stream);
+		let stream = speak2text::speech_to_text().create_stream(user, sender).await?;
+		self.user_stream.insert(user, UserStream {
+			stream,
+			processor: StdMutex::new(VoiceProcessor::new()),
+		});
 		self.user_speaking_state.write().await.insert(user, TimeoutAtomicBool::new());
 
 		Ok(())
@@ -85,27 +120,69 @@ impl Drop for ReceiverData {
 #[derive(Clone)]
 pub(crate) struct Receiver {
 	pub(crate) data: Arc<RwLock<ReceiverData>>,
-	manager: Weak<Songbird>,
-	guild_id: GuildId,
 }
 
 impl Receiver {
-	pub async fn new(manager: Weak<Songbird>, guild_id: GuildId, http: Arc<Http>, handler: Weak<Mutex<Call>>, token: String) -> Self {
+	pub async fn new(channel_id: ChannelId, http: Arc<Http>, handler: Weak<Mutex<Call>>, state: Arc<AppState>) -> Self {
 		Self {
 			data: Arc::new(RwLock::new(ReceiverData {
 				ssrc2user: HashMap::new(),
-				client: Deepgram::new(token).unwrap(),
 				user_stream: HashMap::new(),
 				user_speaking_state: Arc::new(RwLock::new(HashMap::new())),
+				user_voice_state: Arc::new(RwLock::new(HashMap::new())),
 				talking_service: None,
 				vc_handler: handler,
 				http,
+				state,
+				channel_id,
 			})),
-			manager,
-			guild_id,
 		}
 	}
 
+	pub(crate) async fn channel_id(&self) -> ChannelId {
+		self.data.read().await.channel_id
+	}
+
+	// 参加者がいない (Bot自身は含まない)
+	pub(crate) async fn is_empty(&self) -> bool {
+		self.data.read().await.user_voice_state.read().await.is_empty()
+	}
+
+	// 接続しているチャンネルにいる間だけ管理する
+	// チャンネルから出ていった (退出、移動) 場合はtrue
+	pub(crate) async fn voice_state_update(&self, voice_state: &VoiceState) -> bool {
+		let user_id = UserId(voice_state.user_id.get());
+		let data = self.data.read().await;
+		let mut lock = data.user_voice_state.write().await;
+		if voice_state.channel_id == Some(data.channel_id) {
+			lock.insert(user_id, UserVoiceState::from(voice_state));
+			false
+		} else {
+			lock.remove(&user_id).is_some()
+		}
+	}
+
+	// Botが別のチャンネルに移動した (参加者の状態は移動先のものに入れ替える)
+	pub(crate) async fn move_channel(&self, channel_id: ChannelId, voice_states: &[VoiceState]) {
+		let mut data = self.data.write().await;
+		data.channel_id = channel_id;
+
+		let user_voice_state: HashMap<_, _> = voice_states
+			.iter()
+			.filter(|v| v.channel_id == Some(channel_id))
+			.map(|v| (UserId(v.user_id.get()), UserVoiceState::from(v)))
+			.collect();
+
+		// 移動先にいない人のストリームは閉じる
+		let left_users: Vec<UserId> = data.user_stream.keys().filter(|v| !user_voice_state.contains_key(v)).copied().collect();
+		for user_id in left_users {
+			data.remove_stream(user_id).await;
+			data.ssrc2user.retain(|_, v| v != &user_id);
+		}
+
+		*data.user_voice_state.write().await = user_voice_state;
+	}
+
 	// これはもともとstate更新用なのかもしれない、でもClientConnectが無いのでここで管理するしか無い
 	async fn state_update(&self, speaking: &Speaking) {
 		if let Some(user) = speaking.user_id {
@@ -118,7 +195,10 @@ impl Receiver {
 				return;
 			}
 
-			data.create_stream(user).await.unwrap();
+			if let Err(e) = data.create_stream(user).await {
+				log::error!("Speech to text stream error: {}", e);
+				return;
+			}
 			data.ssrc2user.insert(speaking.ssrc, user);
 		}
 	}
@@ -129,24 +209,19 @@ impl Receiver {
 		data.remove_stream(user_id).await;
 		data.ssrc2user.retain(|_, v| v != &user_id);
 
-		// いなくなったら
-		if data.ssrc2user.len() == 0 {
-			if let Some(manager) = self.manager.upgrade() {
-				disconnect_voice_channel_from_manager(&manager, self.guild_id).await.unwrap();
-			}
-		}
+		// 誰もいなくなったかはvoice_state_updateで判定する (全員が移動した場合はついていくため)
 	}
 
 	async fn voice_tick(&self, voice_tick: &VoiceTick) {
-		let speaking_count = voice_tick.speaking.len();
 		// speaking の逆が silent、つまり speaking + silentをすると全体のVCの人数と等しくなる
-		// let total_connect_vc_count = speaking_count + voice_tick.silent.len();
-		if speaking_count == 0 {
-			return;
-		}
+		// 発話の終わり (VADのハングオーバー) を判定するため、silentもパケット無しとして処理する
+		// decoded_voiceがNoneになるのはDecodeModeを見直す必要がある
+		let packets = voice_tick.speaking.iter()
+			.map(|(ssrc, data)| (ssrc, data.decoded_voice.as_deref()))
+			.chain(voice_tick.silent.iter().map(|ssrc| (ssrc, None)));
 
 		let lock = self.data.read().await;
-		for (ssrc, data) in &voice_tick.speaking {
+		for (ssrc, decoded_voice) in packets {
 			let user_id = lock.ssrc2user.get(ssrc);
 			if user_id.is_none() {
 				continue;
@@ -154,36 +229,23 @@ impl Receiver {
 			let user_id = user_id.unwrap();
 
 			// ssrc2userに存在するならば、streamも存在するはずである。
-			let stream = lock.user_stream.get(user_id).unwrap();
+			let user_stream = lock.user_stream.get(user_id).unwrap();
+			let output = user_stream.processor.lock().unwrap().process(decoded_voice);
 
+			// 発話中かどうかはパケットの有無ではなくVADの結果を使う
 			let state_lock = lock.user_speaking_state.read().await;
 			let state = state_lock.get(user_id).unwrap();
-			state.set(true).await;
+			if output.speaking || state.get() {
+				state.set(output.speaking).await;
+			}
 			std::mem::drop(state_lock);
 
-			// 基本的にこのifは通るはずである。通らないのはDecodeModeを見直す必要がある
-			if let Some(decoded_voice_bytes) = data.decoded_voice.as_ref() {
-				let mut bytes = BytesMut::with_capacity(decoded_voice_bytes.len() * 2);
-				for sample in decoded_voice_bytes {
-					bytes.put_i16_le(*sample);
-				}
-
-				if let Err(e) = stream.sender().send(Ok(bytes.freeze())).await {
-					log::error!("{:?}", e);
-				}
+			for frame in &output.frames {
+				user_stream.stream.send(frame).await;
 			}
-		}
-
-		for ssrc in &voice_tick.silent {
-			let user_id = lock.ssrc2user.get(ssrc);
-			if user_id.is_none() {
-				continue;
+			if output.ended {
+				user_stream.stream.end_of_speech().await;
 			}
-			let user_id = user_id.unwrap();
-			let state_lock = lock.user_speaking_state.read().await;
-			let state = state_lock.get(user_id).unwrap();
-			state.set(false).await;
-			std::mem::drop(state_lock);
 		}
 	}
 }
@@ -198,7 +260,8 @@ impl EventHandler for Receiver {
 			// 20ミリ秒ごとに受信されるオーディオパケットの並び替えとデコード。
 			EventContext::VoiceTick(voice_tick) => self.voice_tick(voice_tick).await,
 			EventContext::ClientDisconnect(client_disconnect) => self.client_disconnect(client_disconnect).await,
-			_ => unimplemented!(),
+			// 登録していないイベントは来ないはずだが、来ても無視する
+			_ => log::debug!("unhandled voice event"),
 		};
 
 		None
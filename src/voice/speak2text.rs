@@ -1,154 +1,66 @@
-use std::error::Error;
+use std::sync::{Arc, OnceLock};
 
-use bytes::Bytes;
-use deepgram::{
-	common::{options::{Encoding, Endpointing, Keyword, Language, Model, Options}, stream_response::StreamResponse},
-	listen::websocket::TranscriptionStream,
-	Deepgram,
-};
-use log::info;
+use serenity::async_trait;
 use songbird::model::id::UserId;
-use tokio::{sync::mpsc::{self, Sender}, task::JoinHandle};
-use tokio_stream::{wrappers::ReceiverStream, StreamExt};
-use tokio_util::sync::CancellationToken;
-
-pub(crate) struct Speak2TextStream<E> where E: Error + Send + Sync + 'static {
-	sender: Sender<Result<Bytes, E>>,
-	user_id: UserId,
-	cancel_token: CancellationToken,
-	convert_task: Option<JoinHandle<()>>,
+use tokio::sync::mpsc::Sender;
+
+use crate::configs::{ConfigData, SpeechToTextBackend};
+
+pub(crate) mod deepgram;
+#[cfg(feature = "whisper")]
+pub(crate) mod whisper;
+
+/*
+音声認識のバックエンド
+voice_tickで受け取った音声をpreprocessで16kHzモノラルにし、VADで発話中と判定された分だけユーザーごとのストリームに流す
+文字起こしの結果はTranscriptとしてTextTalkに送る
+どのバックエンドを使うかは設定ファイルの speech_to_text.backend で選ぶ
+*/
+
+#[derive(Debug, Clone)]
+pub(crate) struct Transcript {
+	pub(crate) user_id: UserId,
+	pub(crate) text: String,
+	// falseの場合は途中経過 (後で確定したものが送られる)
+	pub(crate) is_final: bool,
+	// 発話の終わり (これまでに確定したテキストで1つの発話になる)
+	pub(crate) speech_final: bool,
 }
 
-impl<E> Speak2TextStream<E> where E: Error + Send + Sync + 'static {
-	pub(crate) async fn new(client: &Deepgram, user_id: UserId, to_talk_sender: Sender<(UserId, String)>) -> Self {
-		let transcription = client.transcription();
-		let options = Options::builder()
-			.model(Model::Nova2)
-			.language(Language::ja)
-			.punctuate(true)
-			.smart_format(true)
-			.keywords_with_intensifiers([])
-			.build();
-
-		let (wx, rx) = mpsc::channel(1);
-
-		let stream = transcription
-			.stream_request_with_options(options)
-			.encoding(Encoding::Linear16)
-			.sample_rate(48000)
-			.channels(2)
-			.keep_alive()
-			.no_delay(true)
-			.endpointing(Endpointing::CustomDurationMs(40))
-			.stream(ReceiverStream::new(rx))
-			.await
-			.unwrap();
-
-		info!("Deepgram Request ID: {}", stream.request_id());
-
-		let mut this = Self {
-			sender: wx,
-			user_id,
-			cancel_token: CancellationToken::new(),
-			convert_task: None,
-		};
-
-		this.start_stream(stream, to_talk_sender).await;
-
-		this
-	}
-
-	pub(crate) fn sender(&self) -> &Sender<Result<Bytes, E>> {
-		&self.sender
-	}
-
-	async fn start_stream(&mut self, mut stream: TranscriptionStream, wx: Sender<(UserId, String)>) {
-		if self.convert_task.is_some() {
-			if self.exited().await {
-				self.convert_task = None;
-			} else {
-				return;
-			}
-		}
-
-		let task_cancel = self.cancel_token.clone();
-		let user_id = self.user_id;
-		self.convert_task = Some(tokio::spawn(async move {
-			loop {
-				tokio::select! {
-					_ = task_cancel.cancelled() => {
-						break;
-					}
-					Some(result) = stream.next() => {
-						if let Ok(result) = result {
-							if let Err(e) = wx.send((user_id, Self::convert_responce(result))).await {
-								log::error!("{:?}", e);
-							}
-						} else {
-							let err = result.unwrap_err();
-							log::error!("err: {err}");
-						}
-					}
-				}
-			}
-		}));
-	}
-
-	fn convert_responce(res: StreamResponse) -> String {
-		log::debug!("speak responce data: {:?}", res);
-
-		if let StreamResponse::TranscriptResponse {
-			channel,
-			type_field: _,
-			start: _,
-			duration: _,
-			is_final: _,
-			speech_final: _,
-			from_finalize: _,
-			metadata: _,
-			channel_index: _,
-		} = res {
-			let mut text = String::new();
-			for alt in channel.alternatives {
-				text += &alt.transcript;
-			}
-
-			text.split_ascii_whitespace().collect::<Vec<_>>().join("")
-		} else {
-			log::debug!("other res: {:?}", res);
-			String::new()
-		}
-	}
-
-	pub fn cancel(&self) {
-		self.cancel_token.cancel();
-	}
+#[async_trait]
+pub(crate) trait SpeechToText: Send + Sync {
+	// ユーザーごとのストリームを作る (結果はsenderに送られる)
+	async fn create_stream(&self, user_id: UserId, sender: Sender<Transcript>) -> Result<Box<dyn SpeechToTextStream>, String>;
+}
 
-	pub async fn exited(&self) -> bool {
-		self.cancel_token.is_cancelled() &&
-		self.convert_task.as_ref().map_or(true, |v| v.is_finished())
-	}
+#[async_trait]
+pub(crate) trait SpeechToTextStream: Send + Sync {
+	// 16kHzモノラルのi16 (20ms分)
+	async fn send(&self, frame: &[i16]);
 
-	pub fn is_canceled(&self) -> bool {
-		self.cancel_token.is_cancelled()
-	}
+	// VADが発話の終わりを検出した
+	async fn end_of_speech(&self) {}
+}
 
-	pub async fn waiting_inner_task(&mut self) {
-		if let Some(v) = self.convert_task.take() {
-			v.await.unwrap();
-		}
-	}
+static SPEECH_TO_TEXT: OnceLock<Arc<dyn SpeechToText>> = OnceLock::new();
+
+pub(crate) fn init_speech_to_text(config: &ConfigData) -> Result<(), String> {
+	let stt_config = config.get_speech_to_text();
+	let backend: Arc<dyn SpeechToText> = match stt_config.get_backend() {
+		SpeechToTextBackend::Deepgram => Arc::new(deepgram::DeepgramSpeechToText::new(config.get_deepgram_token())?),
+		#[cfg(feature = "whisper")]
+		SpeechToTextBackend::Whisper => Arc::new(whisper::WhisperSpeechToText::new(stt_config)?),
+		#[cfg(not(feature = "whisper"))]
+		SpeechToTextBackend::Whisper => {
+			return Err("whisper backend is not enabled. Build with `--features whisper`.".to_string())
+		},
+	};
+
+	SPEECH_TO_TEXT
+		.set(backend)
+		.map_err(|_| "Speech to text is already initialized.".to_string())
 }
 
-impl<E> Drop for Speak2TextStream<E> where E: Error + Send + Sync + 'static {
-	fn drop(&mut self) {
-		tokio::task::block_in_place(move || {
-			tokio::runtime::Handle::current().block_on(async {
-				if !self.is_canceled() {
-					self.cancel();
-				}
-				self.waiting_inner_task().await;
-			});
-		});
-	}
+pub(crate) fn speech_to_text() -> Arc<dyn SpeechToText> {
+	Arc::clone(SPEECH_TO_TEXT.get().expect("Speech to text is not initialized."))
 }
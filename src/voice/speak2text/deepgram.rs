@@ -0,0 +1,228 @@
+use std::{error::Error, fmt::Display};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use deepgram::{
+	common::{options::{Encoding, Endpointing, Language, Model, Options}, stream_response::StreamResponse},
+	listen::websocket::TranscriptionStream,
+	Deepgram,
+};
+use log::info;
+use serenity::async_trait;
+use songbird::model::id::UserId;
+use tokio::{sync::mpsc::{self, Sender}, task::JoinHandle};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::voice::preprocess::OUTPUT_SAMPLE_RATE;
+
+use super::{SpeechToText, SpeechToTextStream, Transcript};
+
+#[derive(Debug)]
+pub(crate) struct UnknownError;
+
+impl Error for UnknownError {
+	fn description(&self) -> &str {
+		"Unknown error"
+	}
+}
+
+impl Display for UnknownError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Unknown error")
+	}
+}
+
+// Deepgram (Nova2) のwebsocketストリーミング
+pub(crate) struct DeepgramSpeechToText {
+	client: Deepgram,
+}
+
+impl DeepgramSpeechToText {
+	pub(crate) fn new(token: &String) -> Result<Self, String> {
+		let client = Deepgram::new(token).map_err(|e| format!("Deepgram Error: {:?}", e))?;
+
+		Ok(Self { client })
+	}
+}
+
+#[async_trait]
+impl SpeechToText for DeepgramSpeechToText {
+	async fn create_stream(&self, user_id: UserId, sender: Sender<Transcript>) -> Result<Box<dyn SpeechToTextStream>, String> {
+		let stream = DeepgramStream::<UnknownError>::new(&self.client, user_id, sender).await?;
+
+		Ok(Box::new(stream))
+	}
+}
+
+pub(crate) struct DeepgramStream<E> where E: Error + Send + Sync + 'static {
+	sender: Sender<Result<Bytes, E>>,
+	user_id: UserId,
+	cancel_token: CancellationToken,
+	convert_task: Option<JoinHandle<()>>,
+}
+
+impl<E> DeepgramStream<E> where E: Error + Send + Sync + 'static {
+	pub(crate) async fn new(client: &Deepgram, user_id: UserId, to_talk_sender: Sender<Transcript>) -> Result<Self, String> {
+		let transcription = client.transcription();
+		let options = Options::builder()
+			.model(Model::Nova2)
+			.language(Language::ja)
+			.punctuate(true)
+			.smart_format(true)
+			.keywords_with_intensifiers([])
+			.build();
+
+		let (wx, rx) = mpsc::channel(1);
+
+		let stream = transcription
+			.stream_request_with_options(options)
+			.encoding(Encoding::Linear16)
+			.sample_rate(OUTPUT_SAMPLE_RATE as u32)
+			.channels(1)
+			.keep_alive()
+			.no_delay(true)
+			.endpointing(Endpointing::CustomDurationMs(40))
+			// 途中経過は割り込みの判定に使う
+			.interim_results(true)
+			// 雑音でspeech_finalが来ない場合でも発話を区切る
+			.utterance_end_ms(1000)
+			.stream(ReceiverStream::new(rx))
+			.await
+			.map_err(|e| format!("Deepgram Error: {:?}", e))?;
+
+		info!("Deepgram Request ID: {}", stream.request_id());
+
+		let mut this = Self {
+			sender: wx,
+			user_id,
+			cancel_token: CancellationToken::new(),
+			convert_task: None,
+		};
+
+		this.start_stream(stream, to_talk_sender).await;
+
+		Ok(this)
+	}
+
+	async fn start_stream(&mut self, mut stream: TranscriptionStream, wx: Sender<Transcript>) {
+		if self.convert_task.is_some() {
+			if self.exited().await {
+				self.convert_task = None;
+			} else {
+				return;
+			}
+		}
+
+		let task_cancel = self.cancel_token.clone();
+		let user_id = self.user_id;
+		self.convert_task = Some(tokio::spawn(async move {
+			loop {
+				tokio::select! {
+					_ = task_cancel.cancelled() => {
+						break;
+					}
+					Some(result) = stream.next() => {
+						if let Ok(result) = result {
+							let Some(transcript) = Self::convert_responce(user_id, result) else {
+								continue;
+							};
+							if let Err(e) = wx.send(transcript).await {
+								log::error!("{:?}", e);
+							}
+						} else {
+							let err = result.unwrap_err();
+							log::error!("err: {err}");
+						}
+					}
+				}
+			}
+		}));
+	}
+
+	fn convert_responce(user_id: UserId, res: StreamResponse) -> Option<Transcript> {
+		log::debug!("speak responce data: {:?}", res);
+
+		match res {
+			StreamResponse::TranscriptResponse {
+				channel,
+				type_field: _,
+				start: _,
+				duration: _,
+				is_final,
+				speech_final,
+				from_finalize: _,
+				metadata: _,
+				channel_index: _,
+			} => {
+				// alternativesは同じ発話の候補なので、一番可能性の高い先頭だけを使う
+				let text = channel.alternatives
+					.first()
+					.map(|alt| alt.transcript.split_ascii_whitespace().collect::<Vec<_>>().join(""))
+					.unwrap_or_default();
+
+				Some(Transcript {
+					user_id,
+					text,
+					is_final,
+					speech_final,
+				})
+			},
+			StreamResponse::UtteranceEndResponse { .. } => Some(Transcript {
+				user_id,
+				text: String::new(),
+				is_final: true,
+				speech_final: true,
+			}),
+			_ => {
+				log::debug!("other res: {:?}", res);
+				None
+			},
+		}
+	}
+
+	pub fn cancel(&self) {
+		self.cancel_token.cancel();
+	}
+
+	pub async fn exited(&self) -> bool {
+		self.cancel_token.is_cancelled() &&
+		self.convert_task.as_ref().map_or(true, |v| v.is_finished())
+	}
+
+	pub fn is_canceled(&self) -> bool {
+		self.cancel_token.is_cancelled()
+	}
+
+	pub async fn waiting_inner_task(&mut self) {
+		if let Some(v) = self.convert_task.take() {
+			v.await.unwrap();
+		}
+	}
+}
+
+#[async_trait]
+impl<E> SpeechToTextStream for DeepgramStream<E> where E: Error + Send + Sync + 'static {
+	async fn send(&self, frame: &[i16]) {
+		let mut bytes = BytesMut::with_capacity(frame.len() * 2);
+		for sample in frame {
+			bytes.put_i16_le(*sample);
+		}
+
+		if let Err(e) = self.sender.send(Ok(bytes.freeze())).await {
+			log::error!("{:?}", e);
+		}
+	}
+}
+
+impl<E> Drop for DeepgramStream<E> where E: Error + Send + Sync + 'static {
+	fn drop(&mut self) {
+		tokio::task::block_in_place(move || {
+			tokio::runtime::Handle::current().block_on(async {
+				if !self.is_canceled() {
+					self.cancel();
+				}
+				self.waiting_inner_task().await;
+			});
+		});
+	}
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use log::info;
+use serenity::async_trait;
+use songbird::model::id::UserId;
+use tokio::{sync::mpsc::{self, Receiver, Sender}, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperError};
+
+use crate::{configs::SpeechToTextConfig, voice::preprocess::OUTPUT_SAMPLE_RATE};
+
+use super::{SpeechToText, SpeechToTextStream, Transcript};
+
+// 短すぎる音声は雑音として捨てる (VADの前後の余白を含む)
+const MIN_SPEECH_SAMPLES: usize = OUTPUT_SAMPLE_RATE * 3 / 4;
+// whisperは30秒ごとに処理するので、それ以上は区切る
+const MAX_SPEECH_SAMPLES: usize = OUTPUT_SAMPLE_RATE * 30;
+
+// 20msのフレームを溜めておける数 (文字起こし中も受け取れるように)
+const FRAME_QUEUE_SIZE: usize = 50;
+
+// whisper.cpp (whisper-rs) によるCPUでの文字起こし
+pub(crate) struct WhisperSpeechToText {
+	context: Arc<WhisperContext>,
+	threads: i32,
+}
+
+impl WhisperSpeechToText {
+	pub(crate) fn new(config: &SpeechToTextConfig) -> Result<Self, String> {
+		let model_path = config.get_whisper_model_path();
+		info!("Whisper model loading: {}", model_path);
+
+		let context = WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+			.map_err(|e| format!("Whisper Error: {:?}", e))?;
+		let threads = match config.get_whisper_threads() {
+			0 => std::thread::available_parallelism().map_or(1, |v| v.get() as i32),
+			v => v as i32,
+		};
+
+		Ok(Self {
+			context: Arc::new(context),
+			threads,
+		})
+	}
+}
+
+#[async_trait]
+impl SpeechToText for WhisperSpeechToText {
+	async fn create_stream(&self, user_id: UserId, sender: Sender<Transcript>) -> Result<Box<dyn SpeechToTextStream>, String> {
+		Ok(Box::new(WhisperStream::new(Arc::clone(&self.context), self.threads, user_id, sender)))
+	}
+}
+
+enum WhisperInput {
+	Frame(Vec<i16>),
+	EndOfSpeech,
+}
+
+pub(crate) struct WhisperStream {
+	sender: Sender<WhisperInput>,
+	cancel_token: CancellationToken,
+	// フレームを溜めて発話ごとに区切る
+	buffer_task: Option<JoinHandle<()>>,
+	// 区切られた発話を順番に文字起こしする
+	transcribe_task: Option<JoinHandle<()>>,
+}
+
+impl WhisperStream {
+	fn new(context: Arc<WhisperContext>, threads: i32, user_id: UserId, to_talk_sender: Sender<Transcript>) -> Self {
+		let (wx, rx) = mpsc::channel(FRAME_QUEUE_SIZE);
+		let (speech_wx, speech_rx) = mpsc::unbounded_channel();
+		let cancel_token = CancellationToken::new();
+
+		let buffer_task = tokio::spawn(Self::buffer_task(rx, speech_wx, cancel_token.clone()));
+		let transcribe_task = tokio::spawn(Self::transcribe_task(
+			context,
+			threads,
+			user_id,
+			speech_rx,
+			to_talk_sender,
+			cancel_token.clone(),
+		));
+
+		Self {
+			sender: wx,
+			cancel_token,
+			buffer_task: Some(buffer_task),
+			transcribe_task: Some(transcribe_task),
+		}
+	}
+
+	async fn buffer_task(mut rx: Receiver<WhisperInput>, speech_wx: mpsc::UnboundedSender<Vec<f32>>, cancel_token: CancellationToken) {
+		let mut buffer = Vec::<f32>::new();
+		loop {
+			let input = tokio::select! {
+				_ = cancel_token.cancelled() => {
+					break;
+				}
+				input = rx.recv() => input,
+			};
+
+			match input {
+				Some(WhisperInput::Frame(frame)) => {
+					buffer.extend(frame.iter().map(|v| *v as f32 / i16::MAX as f32));
+					if buffer.len() >= MAX_SPEECH_SAMPLES {
+						let _ = speech_wx.send(std::mem::take(&mut buffer));
+					}
+				},
+				Some(WhisperInput::EndOfSpeech) => {
+					let speech = std::mem::take(&mut buffer);
+					if speech.len() >= MIN_SPEECH_SAMPLES {
+						let _ = speech_wx.send(speech);
+					}
+				},
+				None => break,
+			}
+		}
+	}
+
+	async fn transcribe_task(
+		context: Arc<WhisperContext>,
+		threads: i32,
+		user_id: UserId,
+		mut speech_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+		wx: Sender<Transcript>,
+		cancel_token: CancellationToken,
+	) {
+		loop {
+			let speech = tokio::select! {
+				_ = cancel_token.cancelled() => {
+					break;
+				}
+				Some(speech) = speech_rx.recv() => speech,
+			};
+
+			let context = Arc::clone(&context);
+			let result = tokio::task::spawn_blocking(move || transcribe(&context, threads, &speech)).await.unwrap();
+			match result {
+				Ok(text) if !text.is_empty() => {
+					// whisperは発話ごとにまとめて処理するので、常に確定した発話になる
+					let transcript = Transcript {
+						user_id,
+						text,
+						is_final: true,
+						speech_final: true,
+					};
+					if let Err(e) = wx.send(transcript).await {
+						log::error!("{:?}", e);
+					}
+				},
+				Ok(_) => {},
+				Err(e) => log::error!("Whisper Error: {:?}", e),
+			}
+		}
+	}
+
+	pub fn cancel(&self) {
+		self.cancel_token.cancel();
+	}
+
+	pub fn is_canceled(&self) -> bool {
+		self.cancel_token.is_cancelled()
+	}
+
+	pub async fn waiting_inner_task(&mut self) {
+		if let Some(v) = self.buffer_task.take() {
+			v.await.unwrap();
+		}
+		if let Some(v) = self.transcribe_task.take() {
+			v.await.unwrap();
+		}
+	}
+}
+
+#[async_trait]
+impl SpeechToTextStream for WhisperStream {
+	async fn send(&self, frame: &[i16]) {
+		if self.sender.send(WhisperInput::Frame(frame.to_vec())).await.is_err() {
+			log::error!("Whisper stream is closed.");
+		}
+	}
+
+	async fn end_of_speech(&self) {
+		if self.sender.send(WhisperInput::EndOfSpeech).await.is_err() {
+			log::error!("Whisper stream is closed.");
+		}
+	}
+}
+
+impl Drop for WhisperStream {
+	fn drop(&mut self) {
+		tokio::task::block_in_place(move || {
+			tokio::runtime::Handle::current().block_on(async {
+				if !self.is_canceled() {
+					self.cancel();
+				}
+				self.waiting_inner_task().await;
+			});
+		});
+	}
+}
+
+fn transcribe(context: &WhisperContext, threads: i32, speech: &[f32]) -> Result<String, WhisperError> {
+	let mut state = context.create_state()?;
+
+	let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+	params.set_language(Some("ja"));
+	params.set_n_threads(threads);
+	// 発話ごとに独立して処理する
+	params.set_no_context(true);
+	params.set_print_special(false);
+	params.set_print_progress(false);
+	params.set_print_realtime(false);
+	params.set_print_timestamps(false);
+
+	state.full(params, speech)?;
+
+	let mut text = String::new();
+	for i in 0..state.full_n_segments()? {
+		text += &state.full_get_segment_text(i)?;
+	}
+
+	Ok(text.split_whitespace().collect::<Vec<_>>().join(""))
+}
@@ -1,24 +1,161 @@
-use std::sync::LazyLock;
+use std::{collections::HashMap, path::PathBuf, sync::{LazyLock, OnceLock}};
 
+use entity::TtsDictionary;
 use tokio::sync::RwLock;
-use vvclient::{TtsAudioOption, TtsAudioOptionBuilder, VoiceVoxTtsClient, VoiceVoxWord, WavData};
+use vvclient::{TtsAudioOption, TtsAudioOptionBuilder, VoiceVoxSpeaker, VoiceVoxTtsClient, VoiceVoxWord, WavData};
+
+use crate::state::AppState;
 
 pub(crate) mod vvclient;
 
 const JTALK_DIR_PATH: &'static str = "voicevox_core/dict/open_jtalk_dic_utf_8-1.11";
 const RUNTIME_PATH: &'static str = "voicevox_core/onnxruntime/lib/voicevox_onnxruntime.dll";
-const MODEL_PATH: &'static str = "voicevox_core/models/vvms/.vvm";
-pub(crate) const MODEL_STYLE_ID: u32 = 0;
+// このディレクトリの.vvmを全て読み込む
+const MODEL_DIR_PATH: &'static str = "voicevox_core/models/vvms";
+// スタイルが設定されていない、または設定されたスタイルが読み込まれていない場合に使う
+pub(crate) const DEFAULT_STYLE_ID: u32 = 0;
 
 pub(super) static VOICE_VOX_CLIENT: LazyLock<RwLock<VoiceVoxTtsClient>> = LazyLock::new(|| {
 	RwLock::new(VoiceVoxTtsClient::new(JTALK_DIR_PATH, Some(RUNTIME_PATH), None))
 });
 
+// 読み込んだモデルで使えるスタイル (モデルは起動時にしか読み込まないので、その時に作る)
+static VOICE_STYLES: OnceLock<Vec<VoiceStyle>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VoiceStyle {
+	pub(crate) id: u32,
+	pub(crate) speaker: String,
+	pub(crate) name: String,
+}
+
+impl VoiceStyle {
+	pub(crate) fn label(&self) -> String {
+		format!("{} ({})", self.speaker, self.name)
+	}
+}
+
 pub(crate) async fn init_voicevox() {
 	let mut client = VOICE_VOX_CLIENT.write().await;
-	client.load_model(MODEL_PATH).await;
+	for path in model_paths() {
+		if client.load_model(path.to_string_lossy().to_string()).await {
+			log::info!("VoiceVox model loaded: {}", path.display());
+		} else {
+			log::error!("VoiceVox model load failed: {}", path.display());
+		}
+	}
+
+	let styles = talk_styles(client.metas().await);
+	log::info!("VoiceVox styles: {}", styles.len());
+	let _ = VOICE_STYLES.set(styles);
+}
+
+// 起動時に全サーバーの読み上げ辞書を読み込む
+pub(crate) async fn load_dictionaries(state: &AppState) {
+	let entries = match state.guild_configs().all_tts_dictionary().await {
+		Ok(v) => v,
+		Err(error) => {
+			log::error!("DB Error: {:?}", error);
+			return;
+		},
+	};
+
+	let mut client = VOICE_VOX_CLIENT.write().await;
+	for (guild_id, words) in guild_dictionaries(&entries) {
+		client.set_dict(guild_id, words).await;
+	}
+	log::info!("VoiceVox user dict loaded: {}", entries.len());
+}
+
+// サーバーの読み上げ辞書を読み込み直す (他のサーバーの辞書はそのまま)
+pub(crate) async fn reload_dictionary(state: &AppState, guild_id: u64) {
+	let entries = match state.guild_configs().tts_dictionary(guild_id).await {
+		Ok(v) => v,
+		Err(error) => {
+			log::error!("DB Error: {:?}", error);
+			return;
+		},
+	};
+	let words = entries.iter().map(dictionary_word).collect();
+
+	let mut client = VOICE_VOX_CLIENT.write().await;
+	client.set_dict(guild_id, words).await;
+	log::info!("VoiceVox user dict loaded: {} ({})", entries.len(), guild_id);
+}
+
+// 辞書はサーバーごとに分けて、合成する時にそのサーバーのものだけを使う
+fn guild_dictionaries(entries: &[TtsDictionary]) -> HashMap<u64, Vec<VoiceVoxWord>> {
+	let mut dictionaries: HashMap<u64, Vec<VoiceVoxWord>> = HashMap::new();
+	for entry in entries {
+		dictionaries.entry(entry.guild_id.get()).or_default().push(dictionary_word(entry));
+	}
+	dictionaries
+}
+
+pub(crate) fn dictionary_word(entry: &TtsDictionary) -> VoiceVoxWord {
+	VoiceVoxWord::new(entry.surface.clone(), entry.pronunciation.clone(), entry.accent_type.max(0) as usize)
+}
+
+// 辞書の読みはカタカナしか使えないので、ひらがなで入力されたものは変換する
+pub(crate) fn to_katakana(text: &str) -> String {
+	text.chars()
+		.map(|c| match c {
+			// ぁ-ゖ
+			'\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+			_ => c,
+		})
+		.collect()
+}
+
+fn model_paths() -> Vec<PathBuf> {
+	let entries = match std::fs::read_dir(MODEL_DIR_PATH) {
+		Ok(v) => v,
+		Err(e) => {
+			log::error!("VoiceVox model dir error: {:?}", e);
+			return vec![];
+		},
+	};
+
+	let mut paths: Vec<PathBuf> = entries
+		.filter_map(|v| v.ok().map(|v| v.path()))
+		.filter(|v| v.extension().is_some_and(|v| v == "vvm"))
+		.collect();
+	paths.sort();
+	paths
+}
 
-	client.set_dict(vec![]).await;
+// 読み上げに使えるスタイルだけを、ID順に並べる
+fn talk_styles(speakers: Vec<VoiceVoxSpeaker>) -> Vec<VoiceStyle> {
+	let mut styles: Vec<VoiceStyle> = speakers
+		.into_iter()
+		.flat_map(|speaker| {
+			speaker
+				.styles
+				.into_iter()
+				.filter(|v| v.style_type == "talk")
+				.map(move |v| VoiceStyle {
+					id: v.id,
+					speaker: speaker.name.clone(),
+					name: v.name,
+				})
+		})
+		.collect();
+	styles.sort_by_key(|v| v.id);
+	styles
+}
+
+pub(crate) fn voice_styles() -> &'static [VoiceStyle] {
+	VOICE_STYLES.get().map_or(&[], |v| v.as_slice())
+}
+
+// 候補を順に見て、最初に使えるスタイルを選ぶ (ペルソナ → サーバー の順に渡す)
+pub(crate) fn select_style_id(styles: &[VoiceStyle], candidates: &[Option<i32>]) -> u32 {
+	candidates
+		.iter()
+		.flatten()
+		.filter_map(|v| u32::try_from(*v).ok())
+		.find(|v| styles.iter().any(|style| style.id == *v))
+		.unwrap_or(DEFAULT_STYLE_ID)
 }
 
 pub(crate) fn create_tts_option() -> Option<TtsAudioOption> {
@@ -29,3 +166,141 @@ pub(crate) fn create_tts_option() -> Option<TtsAudioOption> {
 			.build()
 	)
 }
+
+// 文の区切り (区切り文字は前の文に含める)
+const SENTENCE_DELIMITERS: [char; 8] = ['。', '！', '？', '!', '?', '♪', '…', '\n'];
+// 短すぎる文は次の文とまとめて合成する (細切れだと抑揚が不自然になる)
+const MIN_SENTENCE_CHARS: usize = 6;
+
+// 読み上げるテキストを文ごとに区切る
+// 少しずつ届くテキスト (ストリーミング) にも使えるように、区切りまで届いた分だけを返す
+#[derive(Default)]
+pub(crate) struct SentenceSplitter {
+	buffer: String,
+	// 区切り文字まで届いた (「！？」のように続く場合があるので、次の文字を見てから区切る)
+	ended: bool,
+}
+
+impl SentenceSplitter {
+	pub(crate) fn push(&mut self, text: &str) -> Vec<String> {
+		let mut sentences = Vec::new();
+		for c in text.chars() {
+			let is_delimiter = SENTENCE_DELIMITERS.contains(&c);
+			if self.ended && !is_delimiter {
+				sentences.extend(self.take());
+			}
+
+			self.buffer.push(c);
+			if is_delimiter && self.buffer.trim().chars().count() >= MIN_SENTENCE_CHARS {
+				self.ended = true;
+			}
+		}
+		sentences
+	}
+
+	// 最後に残った分
+	pub(crate) fn finish(&mut self) -> Option<String> {
+		self.take()
+	}
+
+	fn take(&mut self) -> Option<String> {
+		self.ended = false;
+		let sentence = std::mem::take(&mut self.buffer).trim().to_string();
+		if sentence.is_empty() {
+			None
+		} else {
+			Some(sentence)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn styles() -> Vec<VoiceStyle> {
+		let json = r#"[
+			{"name": "四国めたん", "styles": [{"name": "ノーマル", "id": 2, "type": "talk"}, {"name": "あまあま", "id": 0}], "speaker_uuid": "", "version": "0.16.0"},
+			{"name": "ずんだもん", "styles": [{"name": "ノーマル", "id": 3, "type": "talk"}, {"name": "ハミング", "id": 3001, "type": "frame_decode"}], "speaker_uuid": "", "version": "0.16.0"}
+		]"#;
+		talk_styles(serde_json::from_str(json).unwrap())
+	}
+
+	fn split_sentences(text: &str) -> Vec<String> {
+		let mut splitter = SentenceSplitter::default();
+		let mut sentences = splitter.push(text);
+		sentences.extend(splitter.finish());
+		sentences
+	}
+
+	#[test]
+	fn split_into_sentences() {
+		assert_eq!(
+			split_sentences("こんにちは、エステラだよ。今日はいい天気だね！？ 散歩でもどうかな"),
+			vec!["こんにちは、エステラだよ。", "今日はいい天気だね！？", "散歩でもどうかな"]
+		);
+		assert!(split_sentences("  ").is_empty());
+	}
+
+	#[test]
+	fn merge_short_sentences() {
+		assert_eq!(split_sentences("うん。そうだね。わかった、やってみるね。"), vec!["うん。そうだね。", "わかった、やってみるね。"]);
+	}
+
+	#[test]
+	fn split_streaming_text() {
+		let mut splitter = SentenceSplitter::default();
+		assert!(splitter.push("ねえねえ、聞い").is_empty());
+		assert!(splitter.push("てよ！").is_empty());
+		assert_eq!(splitter.push("？あのね"), vec!["ねえねえ、聞いてよ！？"]);
+		assert_eq!(splitter.finish(), Some("あのね".to_string()));
+		assert_eq!(splitter.finish(), None);
+	}
+
+	#[test]
+	fn list_talk_styles() {
+		let labels: Vec<(u32, String)> = styles().iter().map(|v| (v.id, v.label())).collect();
+		assert_eq!(
+			labels,
+			vec![
+				(0, "四国めたん (あまあま)".to_string()),
+				(2, "四国めたん (ノーマル)".to_string()),
+				(3, "ずんだもん (ノーマル)".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn select_available_style() {
+		let styles = styles();
+		assert_eq!(select_style_id(&styles, &[Some(3), Some(2)]), 3);
+		// 読み込まれていないスタイルは飛ばす
+		assert_eq!(select_style_id(&styles, &[Some(3001), Some(2)]), 2);
+		assert_eq!(select_style_id(&styles, &[None, Some(-1)]), DEFAULT_STYLE_ID);
+	}
+
+	#[test]
+	fn convert_pronunciation_to_katakana() {
+		assert_eq!(to_katakana("えすてら"), "エステラ");
+		assert_eq!(to_katakana("ぎゅうにゅう ゔぁ"), "ギュウニュウ ヴァ");
+		assert_eq!(to_katakana("ステラー"), "ステラー");
+	}
+
+	#[test]
+	fn dictionary_is_not_shared_between_guilds() {
+		let entry = |guild_id: u64, surface: &str| TtsDictionary {
+			guild_id: guild_id.into(),
+			surface: surface.to_string(),
+			pronunciation: "エステラ".to_string(),
+			accent_type: 0,
+		};
+		let dictionaries = guild_dictionaries(&[entry(1, "estella"), entry(2, "stella"), entry(1, "ester")]);
+
+		let surfaces = |guild_id: u64| -> Vec<String> {
+			dictionaries.get(&guild_id).map(|v| v.iter().map(|v| v.surface().to_string()).collect()).unwrap_or_default()
+		};
+		assert_eq!(surfaces(1), vec!["estella", "ester"]);
+		assert_eq!(surfaces(2), vec!["stella"]);
+		assert!(surfaces(3).is_empty());
+	}
+}
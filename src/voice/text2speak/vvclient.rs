@@ -2,7 +2,7 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-use std::ffi::{c_char, CStr, CString};
+use std::{collections::HashMap, ffi::{c_char, CStr, CString}, sync::Mutex as StdMutex};
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
@@ -32,7 +32,10 @@ impl VoiceVoxModel {
 
 impl Drop for VoiceVoxModel {
 	fn drop(&mut self) {
-		unsafe { voicevox_voice_model_file_delete(self.model) };
+		// 開けなかった場合はnullのまま
+		if !self.model.is_null() {
+			unsafe { voicevox_voice_model_file_delete(self.model) };
+		}
 	}
 }
 
@@ -43,13 +46,19 @@ pub(crate) struct VoiceVoxTtsClient {
 	// 参照先のメモリは他スレッドからアクセス中ではない。
 	synthesizer: *mut VoicevoxSynthesizer,
 	jtalk: *mut OpenJtalkRc,
-	dict: Option<*mut VoicevoxUserDict>,
-	current_model: Option<VoiceVoxModel>,
+	// サーバーごとの辞書 (jtalkには1つしか使えないので、合成する時にそのサーバーのものに入れ替える)
+	dicts: HashMap<u64, *mut VoicevoxUserDict>,
+	// 辞書が無いサーバーで使う
+	empty_dict: *mut VoicevoxUserDict,
+	// jtalkが今使っている辞書のサーバー (Noneは空の辞書)
+	dict_guild: StdMutex<Option<u64>>,
+	// 読み込んだモデル (synthesizerが使っている間は破棄してはならない)
+	models: Vec<VoiceVoxModel>,
 	sem: Semaphore,
 }
 
 // safety:
-// synthesizer/jtalk/current_modelにアクセスをしうる関数には必ずその時点でセマフォを取得しなければならない。
+// synthesizer/jtalk/modelsにアクセスをしうる関数には必ずその時点でセマフォを取得しなければならない。
 unsafe impl Send for VoiceVoxTtsClient {}
 unsafe impl Sync for VoiceVoxTtsClient {}
 
@@ -85,64 +94,150 @@ impl VoiceVoxTtsClient {
 			Self {
 				synthesizer,
 				jtalk,
-				dict: None,
-				current_model: None,
+				dicts: HashMap::new(),
+				empty_dict: voicevox_user_dict_new(),
+				dict_guild: StdMutex::new(None),
+				models: Vec::new(),
 				sem: Semaphore::new(1),
 			}
 		}
 	}
 
-	pub(crate) async fn load_model<P: Into<Vec<u8>>>(&mut self, file_path: P) {
-		// もし読み込み済みのモデルが存在した場合、そのモデルを破棄しなければならないが、
+	// モデルを追加で読み込む (読み込み済みのモデルはそのまま使える)
+	pub(crate) async fn load_model<P: Into<Vec<u8>>>(&mut self, file_path: P) -> bool {
+		let model = VoiceVoxModel::new(file_path);
+		if model.model.is_null() {
+			return false;
+		}
+
 		// 仮にsynthesizerが使用されていた場合、モデルの読み込みが処理の合間に起こってしまう
 		// ので、ロックする
 		let perm = self.sem.acquire().await.unwrap();
+		let result = unsafe { voicevox_synthesizer_load_voice_model(self.synthesizer, model.model) };
+		std::mem::drop(perm);
 
-		if let Some(m) = self.current_model.take() {
-			std::mem::drop(m);
+		if result != VoicevoxResultCode_VOICEVOX_RESULT_OK {
+			return false;
 		}
 
-		let model = VoiceVoxModel::new(file_path);
-		unsafe { voicevox_synthesizer_load_voice_model(self.synthesizer, model.model); }
-
-		self.current_model = Some(model);
+		self.models.push(model);
+		true
+	}
 
+	// 読み込み済みのモデルに含まれるキャラクターとスタイルの一覧
+	pub(crate) async fn metas(&self) -> Vec<VoiceVoxSpeaker> {
+		// synthesizerアクセス
+		let perm = self.sem.acquire().await.unwrap();
+		let json_ptr = unsafe { voicevox_synthesizer_create_metas_json(self.synthesizer) };
 		std::mem::drop(perm);
+
+		// CString::from_rawのように、Rust側が開放権をもつような動作にしてはならない(アロケータ破損)
+		let json_c = unsafe { CStr::from_ptr(json_ptr) };
+		let metas = serde_json::from_str(json_c.to_str().unwrap_or_default()).unwrap_or_default();
+		// safety:
+		// json_ptrはこの関数内のみで処理/消費されるポインタであるため問題はない
+		unsafe { voicevox_json_free(json_ptr) };
+
+		metas
 	}
 
-	pub(crate) async fn set_dict(&mut self, words: Vec<VoiceVoxWord>) {
-		let dict = match self.dict {
-			Some(v) => v,
-			None => {
-				let d = unsafe { voicevox_user_dict_new() };
-				self.dict = Some(d);
-				d
-			},
+	// サーバーの辞書を入れ替える (追加できなかった単語は読み飛ばす)
+	pub(crate) async fn set_dict(&mut self, guild_id: u64, words: Vec<VoiceVoxWord>) {
+		// jtalkのアクセスが必要であるためセマフォが必要
+		// (辞書のポインタはSendではないので、awaitより後に作る)
+		let perm = self.sem.acquire().await.unwrap();
+
+		let old = if words.is_empty() {
+			self.dicts.remove(&guild_id)
+		} else {
+			let dict = unsafe { voicevox_user_dict_new() };
+			for word in words {
+				if let Err(e) = Self::add_word(dict, &word) {
+					log::error!("VoiceVox user dict error: {} {}", word.surface(), e);
+				}
+			}
+			self.dicts.insert(guild_id, dict)
 		};
 
-		for word in words {
-			let surface = CString::new(word.0).unwrap();
-			let pronunciation = CString::new(word.1).unwrap();
-			let w = unsafe { voicevox_user_dict_word_make(surface.as_ptr(), pronunciation.as_ptr(), word.2) };
+		// 使用中の辞書だった場合は、新しいものに入れ替えておく
+		if *self.dict_guild.get_mut().unwrap() == Some(guild_id) {
+			self.apply_dict(guild_id);
+		}
+		std::mem::drop(perm);
 
-			let mut id: [u8; 16] = [0; 16];
-			// 各引数はこのforループスコープ内でのみ有効であるため問題はない
-			unsafe { voicevox_user_dict_add_word(dict, &w, &mut id) };
+		// jtalkには辞書の内容がコピーされるので、前の辞書は破棄してよい
+		if let Some(old) = old {
+			unsafe { voicevox_user_dict_delete(old) };
 		}
+	}
 
-		// この時点でjtalkのアクセスが必要であるためセマフォが必要
-		let perm = self.sem.acquire().await.unwrap();
-		unsafe { voicevox_open_jtalk_rc_use_user_dict(self.jtalk, dict); }
-		std::mem::drop(perm);
+	// jtalkの辞書を合成するサーバーのものにする (前回と同じサーバーなら何もしない)
+	// safety: セマフォを取得している間に呼ばなければならない
+	fn use_dict(&self, guild_id: u64) {
+		let key = self.dicts.contains_key(&guild_id).then_some(guild_id);
+		if *self.dict_guild.lock().unwrap() != key {
+			self.apply_dict(guild_id);
+		}
 	}
 
-	pub(crate) async fn tts<T: Into<Vec<u8>>>(&self, text: T, style_id: VoicevoxStyleId, option: Option<TtsAudioOption>, synthesis_options: Option<VoicevoxSynthesisOptions>) -> WavData {
+	// safety: セマフォを取得している間に呼ばなければならない
+	fn apply_dict(&self, guild_id: u64) {
+		let mut dict_guild = self.dict_guild.lock().unwrap();
+		let (key, dict) = match self.dicts.get(&guild_id) {
+			Some(dict) => (Some(guild_id), *dict),
+			None => (None, self.empty_dict),
+		};
+
+		let result = unsafe { voicevox_open_jtalk_rc_use_user_dict(self.jtalk, dict) };
+		if result == VoicevoxResultCode_VOICEVOX_RESULT_OK {
+			*dict_guild = key;
+			return;
+		}
+		log::error!("VoiceVox user dict error: {} ({})", error_message(result), guild_id);
+
+		// 前のサーバーの辞書が残らないように、空の辞書に戻す
+		let result = unsafe { voicevox_open_jtalk_rc_use_user_dict(self.jtalk, self.empty_dict) };
+		if result == VoicevoxResultCode_VOICEVOX_RESULT_OK {
+			*dict_guild = None;
+		} else {
+			log::error!("VoiceVox user dict error: {}", error_message(result));
+		}
+	}
+
+	// 辞書に追加できる単語か (読みがカタカナでない、アクセント型がモーラ数を超えているなど)
+	pub(crate) fn check_word(word: &VoiceVoxWord) -> Result<(), String> {
+		let dict = unsafe { voicevox_user_dict_new() };
+		let result = Self::add_word(dict, word);
+		unsafe { voicevox_user_dict_delete(dict) };
+
+		result
+	}
+
+	fn add_word(dict: *mut VoicevoxUserDict, word: &VoiceVoxWord) -> Result<(), String> {
+		let surface = CString::new(word.0.as_str()).map_err(|e| e.to_string())?;
+		let pronunciation = CString::new(word.1.as_str()).map_err(|e| e.to_string())?;
+		let w = unsafe { voicevox_user_dict_word_make(surface.as_ptr(), pronunciation.as_ptr(), word.2) };
+
+		let mut id: [u8; 16] = [0; 16];
+		// 各引数はこの関数内でのみ有効であるため問題はない
+		let result = unsafe { voicevox_user_dict_add_word(dict, &w, &mut id) };
+		if result == VoicevoxResultCode_VOICEVOX_RESULT_OK {
+			Ok(())
+		} else {
+			Err(error_message(result))
+		}
+	}
+
+	pub(crate) async fn tts<T: Into<Vec<u8>>>(&self, guild_id: u64, text: T, style_id: VoicevoxStyleId, option: Option<TtsAudioOption>, synthesis_options: Option<VoicevoxSynthesisOptions>) -> WavData {
 		let textc = CString::new(text).unwrap();
 
 		// synthesizerアクセス
 		let perm = self.sem.acquire().await.unwrap();
 		// selfはmove出来ないのでspawn_blockingは使用できない
 		let mut audio_query = tokio::task::block_in_place(move || {
+			// 読み方はaudio_queryを作る時に決まるので、ここでサーバーの辞書にする
+			self.use_dict(guild_id);
+
 			let mut json_ptr = std::ptr::null_mut::<c_char>();
 			unsafe { voicevox_synthesizer_create_audio_query(self.synthesizer, textc.as_ptr(), style_id, &mut json_ptr) };
 			std::mem::drop(perm);
@@ -239,9 +334,10 @@ impl Drop for VoiceVoxTtsClient {
 				let perm = self.sem.acquire().await.unwrap();
 
 				unsafe {
-					if let Some(dict) = self.dict {
-						voicevox_user_dict_delete(dict);
+					for dict in self.dicts.values() {
+						voicevox_user_dict_delete(*dict);
 					}
+					voicevox_user_dict_delete(self.empty_dict);
 					voicevox_open_jtalk_rc_delete(self.jtalk);
 					voicevox_synthesizer_delete(self.synthesizer);
 				};
@@ -254,12 +350,42 @@ impl Drop for VoiceVoxTtsClient {
 	}
 }
 
+// voicevox_synthesizer_create_metas_jsonのキャラクター
+#[derive(Debug, Deserialize)]
+pub(crate) struct VoiceVoxSpeaker {
+	pub(crate) name: String,
+	pub(crate) styles: Vec<VoiceVoxStyle>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct VoiceVoxStyle {
+	pub(crate) name: String,
+	pub(crate) id: VoicevoxStyleId,
+	// talk以外 (歌唱用) はttsに使えない
+	#[serde(rename = "type", default = "default_style_type")]
+	pub(crate) style_type: String,
+}
+
+fn default_style_type() -> String {
+	"talk".to_string()
+}
+
+fn error_message(result: VoicevoxResultCode) -> String {
+	// 静的な文字列なので解放しない
+	let message = unsafe { CStr::from_ptr(voicevox_error_result_to_message(result)) };
+	message.to_string_lossy().to_string()
+}
+
 pub(crate) struct VoiceVoxWord(String, String, usize);
 
 impl VoiceVoxWord {
 	pub(crate) fn new<S: Into<String>, P: Into<String>>(surface: S, pronunciation: P, accent_type: usize) -> Self {
 		Self(surface.into(), pronunciation.into(), accent_type)
 	}
+
+	pub(crate) fn surface(&self) -> &str {
+		&self.0
+	}
 }
 
 pub(crate) struct WavData {
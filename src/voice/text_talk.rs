@@ -1,62 +1,96 @@
-use std::{collections::HashMap, sync::{atomic::{AtomicBool, Ordering}, Arc, Weak}};
+use std::{collections::HashMap, sync::{atomic::{AtomicBool, Ordering}, Arc, Weak}, time::{Duration, Instant}};
 
-use entity::{GuildConfig, GuildConfigBehavior, UserData, UserDataBehavior};
+use entity::{GuildConfig, UserData};
 use futures::StreamExt;
 use openai_dive::v1::resources::response::request::ResponseInputItem;
 use rand::Rng;
-use sea_orm::{EntityTrait, IntoActiveModel, Set};
+use sea_orm::{IntoActiveModel, Set};
 use serenity::{all::{ChannelId, CreateMessage, GuildId, Http}, async_trait};
 use songbird::{model::id::UserId, tracks::TrackHandle, Call, Event, EventContext, EventHandler, TrackEvent};
-use tokio::{sync::{mpsc::{self, Receiver, Sender}, Mutex, RwLock}, task::JoinHandle};
+use tokio::{sync::{mpsc::{self, Receiver, Sender, UnboundedReceiver}, Mutex, Notify, RwLock}, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
-use sea_orm::ActiveModelTrait;
 
-use crate::{chat::{create_multi_user_message, create_user_message, getchat_responce, param::{calculate_likability_level_from_message_count, ResponseAction, ResponseData}}, utils::atomic::TimeoutAtomicBool, STATIC_COMPONENTS};
+use crate::{chat::{create_multi_user_message, create_persona_instructions, create_topic_message, create_user_message, getchat_responce_stream, param::{calculate_likability_level_from_message_count, ResponseAction, ResponseData}}, state::AppState, utils::atomic::TimeoutAtomicBool};
 
-use super::text2speak::{create_tts_option, MODEL_STYLE_ID, VOICE_VOX_CLIENT};
+use super::{addressing, receive::UserVoiceState, speak2text::Transcript, text2speak::{create_tts_option, select_style_id, voice_styles, SentenceSplitter, VOICE_VOX_CLIENT}};
 
 // さすがに200人以上超えたらdiscordもおかしくなると思うので現実的な数値
 const MAX_USER_SIZE: usize = 200;
 
+// サーバーの設定が取れなかった場合の、呼ばれていなくても話に入る確率
 const POLLING_RAND: f32 = 0.2f32;
 
+// 誰も話していないかを確認する間隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// 話題を考える時に渡す会話履歴の数
+const TOPIC_HISTORY_SIZE: u64 = 10;
+
+// 確定した文字起こし (ユーザーごと)
+#[derive(Default)]
+struct Utterance {
+	text: String,
+	// speech_finalを受け取った (話し終わっている)
+	completed: bool,
+}
+
 pub(crate) struct TextTalk {
 	cancel_token: CancellationToken,
 	// トーク内容を受け取る
 	io_task: Arc<Mutex<Option<JoinHandle<()>>>>,
-	// 1秒毎の定期実行
-	interval_task: Arc<Mutex<Option<JoinHandle<()>>>>,
-	text_queue: Arc<Mutex<HashMap<UserId, String>>>,
+	// 発話が終わるごとに実行
+	talk_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+	talk_notify: Notify,
+	text_queue: Arc<Mutex<HashMap<UserId, Utterance>>>,
+	// 再生キューに入れた最後の音声 (話し始めたら止める)
+	playing_track: Mutex<Option<TrackHandle>>,
+	// 読み上げの途中で割り込まれた (残りの文は読まない)
+	interrupted: AtomicBool,
 	// 操作送信用
 	http: Arc<Http>,
+	state: Arc<AppState>,
 	// ボイス送信用
 	vc_handler: Weak<Mutex<Call>>,
 	// グローバルブロードキャストリーダー
-	speaking_result_sender: Sender<(UserId, String)>,
+	speaking_result_sender: Sender<Transcript>,
 	user_speaking_state: Arc<RwLock<HashMap<UserId, TimeoutAtomicBool>>>,
+	user_voice_state: Arc<RwLock<HashMap<UserId, UserVoiceState>>>,
+	// 最後に誰か (自分を含む) が話した時間
+	last_activity: Mutex<Instant>,
+	// 話題を出してから、まだ誰も話していない (同じ沈黙の間に何度も話題を出さない)
+	topic_started: AtomicBool,
 	talk_execute_state: AtomicBool,
 	target_talk_user: Mutex<Option<UserId>>,
 	target_guild_id: GuildId,
 	prev_message_id: Mutex<Option<String>>,
+	// 話しているペルソナ (change_voiceで切り替わる、Noneの場合はサーバーの声)
+	persona: Mutex<Option<String>>,
 }
 
 impl TextTalk {
-	pub(crate) async fn new(target_guild_id: GuildId, http: Arc<Http>, vc_handler: Weak<Mutex<Call>>, user_speaking_state: Arc<RwLock<HashMap<UserId, TimeoutAtomicBool>>>) -> Arc<Self> {
-		let (wx, rx) = mpsc::channel::<(UserId, String)>(MAX_USER_SIZE + 1);
+	pub(crate) async fn new(target_guild_id: GuildId, http: Arc<Http>, state: Arc<AppState>, vc_handler: Weak<Mutex<Call>>, user_speaking_state: Arc<RwLock<HashMap<UserId, TimeoutAtomicBool>>>, user_voice_state: Arc<RwLock<HashMap<UserId, UserVoiceState>>>) -> Arc<Self> {
+		let (wx, rx) = mpsc::channel::<Transcript>(MAX_USER_SIZE + 1);
 
 		let this = Arc::new(Self {
 			cancel_token: CancellationToken::new(),
 			io_task: Arc::new(Mutex::new(None)),
-			interval_task: Arc::new(Mutex::new(None)),
+			talk_task: Arc::new(Mutex::new(None)),
+			talk_notify: Notify::new(),
 			text_queue: Arc::new(Mutex::new(HashMap::new())),
+			playing_track: Mutex::new(None),
+			interrupted: AtomicBool::new(false),
 			http,
+			state,
 			vc_handler,
 			speaking_result_sender: wx,
 			user_speaking_state,
+			user_voice_state,
+			last_activity: Mutex::new(Instant::now()),
+			topic_started: AtomicBool::new(false),
 			talk_execute_state: AtomicBool::new(false),
 			target_talk_user: Mutex::new(None),
 			target_guild_id,
 			prev_message_id: Mutex::new(None),
+			persona: Mutex::new(None),
 		});
 
 		this.make_task(rx).await;
@@ -64,7 +98,7 @@ impl TextTalk {
 		this
 	}
 
-	pub(crate) fn create_sender(&self) -> Sender<(UserId, String)> {
+	pub(crate) fn create_sender(&self) -> Sender<Transcript> {
 		self.speaking_result_sender.clone()
 	}
 
@@ -75,7 +109,7 @@ impl TextTalk {
 	pub async fn exited(&self) -> bool {
 		self.cancel_token.is_cancelled() &&
 		self.io_task.lock().await.as_ref().map_or(true, |v| v.is_finished()) &&
-		self.interval_task.lock().await.as_ref().map_or(true, |v| v.is_finished())
+		self.talk_task.lock().await.as_ref().map_or(true, |v| v.is_finished())
 	}
 
 	pub fn is_canceled(&self) -> bool {
@@ -86,12 +120,12 @@ impl TextTalk {
 		if let Some(v) = self.io_task.lock().await.take() {
 			v.await.unwrap();
 		}
-		if let Some(v) = self.interval_task.lock().await.take() {
+		if let Some(v) = self.talk_task.lock().await.take() {
 			v.await.unwrap();
 		}
 	}
 
-	pub async fn make_task(self: &Arc<Self>, mut rx: Receiver<(UserId, String)>) {
+	pub async fn make_task(self: &Arc<Self>, mut rx: Receiver<Transcript>) {
 		let in_thread_self = Arc::clone(&self);
 		let io_thread = tokio::spawn(async move {
 			loop {
@@ -99,39 +133,107 @@ impl TextTalk {
 					_ = in_thread_self.cancel_token.cancelled() => {
 						break;
 					}
-					Some((user_id, result)) = rx.recv() => {
-						in_thread_self.action(user_id, result).await;
+					Some(transcript) = rx.recv() => {
+						in_thread_self.action(transcript).await;
 					}
 				}
 			}
 		});
 
+		// 返答の生成と再生は時間がかかるので、受け取りとは別のタスクで行う (再生中も割り込みを受け付けるため)
 		let in_thread_self = Arc::clone(&self);
-		let interval_task = tokio::spawn(async move {
+		let talk_task = tokio::spawn(async move {
+			let mut idle_interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
 			loop {
 				tokio::select! {
 					_ = in_thread_self.cancel_token.cancelled() => {
 						break;
 					}
-					_ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {
+					_ = in_thread_self.talk_notify.notified() => {
 						in_thread_self.interval_task().await;
 					}
+					_ = idle_interval.tick() => {
+						in_thread_self.idle_task().await;
+					}
 				}
 			}
 		});
 
 		let mut lock = self.io_task.lock().await;
 		*lock = Some(io_thread);
-		let mut lock = self.interval_task.lock().await;
-		*lock = Some(interval_task);
+		let mut lock = self.talk_task.lock().await;
+		*lock = Some(talk_task);
 	}
 
-	async fn action(&self, user_id: UserId, text: String) {
+	async fn action(&self, transcript: Transcript) {
+		let text = transcript.text.trim();
+		if !text.is_empty() {
+			self.barge_in(transcript.user_id).await;
+			*self.last_activity.lock().await = Instant::now();
+			self.topic_started.store(false, Ordering::Release);
+		}
+
+		// 途中経過は確定したものに置き換わるので、割り込みの判定にだけ使う
+		if !transcript.is_final {
+			return;
+		}
+
 		let mut lock = self.text_queue.lock().await;
-		let mut queue_text = lock.get(&user_id).map(|v| v.clone()).unwrap_or_default();
-		queue_text += &text;
+		if text.is_empty() {
+			// 空の確定 (UtteranceEndなど) は話し終わりの合図にだけ使い、新しく積まない
+			let Some(utterance) = lock.get_mut(&transcript.user_id) else {
+				return;
+			};
+			utterance.completed |= transcript.speech_final;
+		} else {
+			let utterance = lock.entry(transcript.user_id).or_default();
+			utterance.text += text;
+			utterance.completed = transcript.speech_final;
+		}
+		std::mem::drop(lock);
 
-		lock.insert(user_id, queue_text);
+		if transcript.speech_final {
+			self.talk_notify.notify_one();
+		}
+	}
+
+	// 話している途中にユーザーが話し始めたら、再生を止める
+	// 聞き取りターゲットがいる場合は、その人以外の会話では止めない
+	async fn barge_in(&self, user_id: UserId) {
+		let target = *self.target_talk_user.lock().await;
+		if target.is_some_and(|v| v != user_id) {
+			return;
+		}
+
+		if self.playing_track.lock().await.take().is_some() {
+			log::debug!("barge-in: {}", user_id);
+			self.interrupted.store(true, Ordering::Release);
+			// 再生中の文と、キューに入っている残りの文をまとめて止める
+			if let Some(vc_handler) = self.vc_handler.upgrade() {
+				vc_handler.lock().await.queue().stop();
+			}
+		}
+	}
+
+	// 処理しなかったテキストを戻す (その間に届いた分は後ろにつける)
+	async fn requeue(&self, user_id: UserId, text: &str, completed: bool) {
+		let mut lock = self.text_queue.lock().await;
+		let mut utterance = Utterance {
+			text: text.to_owned(),
+			completed,
+		};
+		if let Some(queued) = lock.remove(&user_id) {
+			utterance.text += &queued.text;
+			utterance.completed = queued.completed;
+		}
+		lock.insert(user_id, utterance);
+	}
+
+	// 話している間に話し終わった人がいれば、続けて処理する
+	async fn notify_if_completed(&self) {
+		if self.text_queue.lock().await.values().any(|v| v.completed) {
+			self.talk_notify.notify_one();
+		}
 	}
 
 	async fn interval_task(&self) {
@@ -147,35 +249,39 @@ impl TextTalk {
 		let execute_state= self.talk_execute_state.load(Ordering::Acquire);
 		let none_execute_target = self.target_talk_user.lock().await.is_none();
 
-		let random_polling = tokio::task::spawn_blocking(|| {
+		// 話に入る確率とウェイクワードはサーバーごとに設定できる
+		let guild_config = self.get_target_guild_config().await;
+		let polling_rand = guild_config.as_ref().map_or(POLLING_RAND, |v| v.voice_interjection_rate as f32 / 100.0);
+		let wake_words = addressing::wake_words(guild_config.as_ref().and_then(|v| v.voice_wake_words.as_deref()));
+
+		let random_polling = tokio::task::spawn_blocking(move || {
 			// 確率によるポーリング (全体的な)
 			let mut rng = rand::rng();
 			let random_state: f32 = rng.random();
-			random_state < POLLING_RAND
+			random_state < polling_rand
 		}).await.unwrap();
 
 		let is_multi_users = self.user_speaking_state.read().await.len() != 1;
 		let mut speaking_count = 0;
-		for (user_id, text) in &user_text {
+		for (user_id, utterance) in &user_text {
 			let user_id = *user_id;
-			let text = text.trim();
+			let text = utterance.text.trim();
 			if text.len() == 0 {
 				continue;
 			}
 
 			log::debug!("{} text: {}", user_id, text);
 
-			// もしかしたら、dropされた後に追加された文章後にspeakがfalseになる可能性がある？
-			let current_speak = self.user_speaking_state.read().await
-				.get(&user_id)
-				.map_or(false, |v| v.get());
+			// speech_finalを受け取るまでは、まだ話している
+			let current_speak = !utterance.completed;
+			let voice_state = self.user_voice_state.read().await.get(&user_id).copied().unwrap_or_default();
 
 			// 実行中なのにターゲットが存在していない場合、それは全体の受付ができる状態を表している
 			if execute_state && none_execute_target {
 				// ここでは、一旦何もしない。for終了後に考える
 				// ただし、ステートの判断用に処理はする
-				// TODO: ミュートを考慮する必要がある
-				if current_speak {
+				// ミュートした人は話し終わっていなくても、これ以上話さないので数えない
+				if current_speak && !voice_state.is_muted() {
 					speaking_count += 1;
 				}
 			}
@@ -189,27 +295,20 @@ impl TextTalk {
 			// 実行中で、聞き取りターゲットだけど、まだ喋ってる状態 (待ってあげる)
 			if (execute_state && execute_target) && current_speak {
 				// まだ喋ってるなら、これ以上に追加されることがあるかもしれないので、戻しとく
-				let mut lock = self.text_queue.lock().await;
-				let mut queue_text = lock.get(&user_id).map(|v| v.clone()).unwrap_or_default();
-				queue_text = text.to_owned() + &queue_text;
-
-				lock.insert(user_id, queue_text);
+				self.requeue(user_id, text, false).await;
 				continue;
 			}
 
 			// この時点でもうすでに話したい内容が確定する (それ以上話すことがないのでレス)
 			if execute_state && execute_target {
-				let data = self.send_ai_answer_get_for_target(text).await;
+				let (data, track) = self.send_ai_answer_get_for_target(text).await;
 
 				log::debug!("{:?}", data);
 
-				let track = self.send_voice(&data.message).await;
 				self.execute_actions(&data.actions).await;
 
 				// voiceのawaitをする
-				let (_track_waiter, wait) = TrackHandleWaiter::new(track);
-				wait.await.unwrap();
-				log::debug!("waited!!!");
+				self.wait_voice(track).await;
 
 				self.after_action(&data.actions).await;
 				// 空白で終わる場合は、相槌だけで終わっているということなので、全体の受付に戻る
@@ -222,6 +321,7 @@ impl TextTalk {
 				if self.target_talk_user.lock().await.is_none() {
 					self.talk_execute_state.store(false, Ordering::Release);
 				}
+				self.notify_if_completed().await;
 				continue;
 			}
 
@@ -234,11 +334,7 @@ impl TextTalk {
 
 			if current_speak {
 				// まだ喋ってるなら、これ以上に追加されることがあるかもしれないので、戻しとく
-				let mut lock = self.text_queue.lock().await;
-				let mut queue_text = lock.get(&user_id).map(|v| v.clone()).unwrap_or_default();
-				queue_text = text.to_owned() + &queue_text;
-
-				lock.insert(user_id, queue_text);
+				self.requeue(user_id, text, false).await;
 				continue;
 			}
 
@@ -246,13 +342,19 @@ impl TextTalk {
 			// なので、基本的に全部流すことになりそう？
 			// 1-1のときは基本的に全部の言葉を流すようにする
 			// 1-多のときは特定文字のポーリングかつ、テキストに対して確率的な流し込みを行う
-			// TODO:    その時、一定時間何も話していない場合(テキスト化できていない)は、確率的に話題を出す
-			// TODO:     -> 全員がミュートだった時、話す意味がないのでどうしよう
+			// 一定時間何も話していない場合は、idle_taskで話題を出す (全員がミュートの場合は出さない)
 
 			// 1:1じゃなければ、条件判定
 			let mut specified_polling: bool = false;
 			if is_multi_users {
-				// 特定文字のポーリング
+				// 特定文字のポーリング (名前を呼ばれた、直接質問された)
+				// スピーカーミュートしている人には返事が聞こえないので、呼ばれても反応しない
+				if voice_state.is_deafened() {
+					log::debug!("{} is deafened", user_id);
+				} else if let Some(addressing) = addressing::detect(text, &wake_words) {
+					log::debug!("{} addressing: {:?}", user_id, addressing);
+					specified_polling = true;
+				}
 
 				if !(specified_polling || random_polling) {
 					continue;
@@ -266,10 +368,8 @@ impl TextTalk {
 			//self.send_voice(&"どうしたの？".to_string()).await;
 			// リアルタイム会話に齟齬がないように、戻しとく
 			// つまり、ここではステートの管理だけをし、実際の実行は次のターンに回す
-			let mut lock = self.text_queue.lock().await;
-			let mut queue_text = lock.get(&user_id).map(|v| v.clone()).unwrap_or_default();
-			queue_text = text.to_owned() + &queue_text;
-			lock.insert(user_id, queue_text);
+			self.requeue(user_id, text, true).await;
+			self.talk_notify.notify_one();
 
 			// 一人だけ もしくは マルチ時に特定の文字を検知したのであれば、ターゲット
 			if !is_multi_users || specified_polling {
@@ -284,19 +384,14 @@ impl TextTalk {
 		// この実行中ステートは変更前のステートなのでfor内で変更があっても問題はないはずである
 		if execute_state && none_execute_target {
 			if speaking_count > 1 {
-				// 2人以上で喋ってたら待つ
-				for (user_id, text) in &user_text {
-					let user_id = *user_id;
-					let text = text.trim();
+				// 2人以上で喋ってたら待つ (次に誰かが話し終わった時に再度判定する)
+				for (user_id, utterance) in &user_text {
+					let text = utterance.text.trim();
 					if text.len() == 0 {
 						continue;
 					}
 
-					let mut lock = self.text_queue.lock().await;
-					let mut queue_text = lock.get(&user_id).map(|v| v.clone()).unwrap_or_default();
-					queue_text = text.to_owned() + &queue_text;
-
-					lock.insert(user_id, queue_text);
+					self.requeue(*user_id, text, utterance.completed).await;
 				}
 			} else {
 				// 誰か一人でも喋ってない人が居るならば、
@@ -305,7 +400,7 @@ impl TextTalk {
 
 				let user_messages: Vec<_> = futures::stream::iter(user_text.into_iter())
 					.filter_map(|(i, t)| async move {
-						let t = t.trim();
+						let t = t.text.trim();
 						if t.len() == 0 {
 							None
 						} else {
@@ -314,23 +409,26 @@ impl TextTalk {
 					})
 					.collect().await;
 
+				// 空の文字起こし (咳など) しか無かった場合は、話した人がいないので返事をしない
+				// 話題を出した後であれば、そのまま返事を待つ
+				if user_messages.is_empty() {
+					return;
+				}
+
 				let users_message_item = create_multi_user_message(
 					user_messages.iter().map(|(v,_,_)| v).collect()
 				);
 
 				// 実行フェーズ
 
-				let data = self.send_ai_answer_get_for_multi_user(user_messages, users_message_item).await;
+				let (data, track) = self.send_ai_answer_get_for_multi_user(user_messages, users_message_item).await;
 
 				log::debug!("{:?}", data);
 
-				let track = self.send_voice(&data.message).await;
 				self.execute_actions(&data.actions).await;
 
 				// voiceのawaitをする
-				let (_track_waiter, wait) = TrackHandleWaiter::new(track);
-				wait.await.unwrap();
-				log::debug!("waited!!!");
+				self.wait_voice(track).await;
 
 				self.after_action(&data.actions).await;
 				// 空白で終わる場合は、相槌だけで終わっているということなので、全体の受付に戻る
@@ -343,8 +441,69 @@ impl TextTalk {
 				if self.target_talk_user.lock().await.is_none() {
 					self.talk_execute_state.store(false, Ordering::Release);
 				}
+				self.notify_if_completed().await;
+			}
+		}
+	}
+
+	// 一定時間誰も話していない場合は、話題を出す
+	async fn idle_task(&self) {
+		if !self.text_queue.lock().await.is_empty() || self.user_speaking_state.read().await.values().any(|v| v.get()) {
+			return;
+		}
+
+		let Some(guild_config) = self.get_target_guild_config().await else {
+			return;
+		};
+		if guild_config.voice_idle_topic_seconds <= 0 {
+			return;
+		}
+		let idle_time = Duration::from_secs(guild_config.voice_idle_topic_seconds as u64);
+		if self.last_activity.lock().await.elapsed() < idle_time {
+			return;
+		}
+
+		if self.talk_execute_state.load(Ordering::Acquire) {
+			// 話題を出しても誰も答えなかったので、受付に戻る (次に誰かが話すまで話題は出さない)
+			if self.topic_started.load(Ordering::Acquire) && self.target_talk_user.lock().await.is_none() {
+				self.talk_execute_state.store(false, Ordering::Release);
 			}
+			return;
 		}
+		if self.topic_started.load(Ordering::Acquire) {
+			return;
+		}
+
+		// 全員がミュートかスピーカーミュートだった時は、話す意味がないので出さない
+		let participants: Vec<UserId> = self.user_voice_state.read().await
+			.iter()
+			.filter(|(_, v)| !v.is_away())
+			.map(|(user_id, _)| *user_id)
+			.collect();
+		if participants.is_empty() {
+			return;
+		}
+
+		self.topic_started.store(true, Ordering::Release);
+		// 話題に答えた人の話を聞けるように、全体の受付ができる状態にする (話し始めは生成中なので先に変える)
+		self.talk_execute_state.store(true, Ordering::Release);
+
+		let (data, track) = self.send_ai_topic_get(&participants).await;
+		let Some(data) = data.filter(|v| !v.message.is_empty()) else {
+			self.wait_voice(track).await;
+			self.talk_execute_state.store(false, Ordering::Release);
+			return;
+		};
+
+		log::debug!("{:?}", data);
+
+		self.execute_actions(&data.actions).await;
+
+		// voiceのawaitをする
+		self.wait_voice(track).await;
+
+		self.after_action(&data.actions).await;
+		self.notify_if_completed().await;
 	}
 
 	async fn execute_actions(&self, actions: &Vec<ResponseAction>) {
@@ -365,7 +524,7 @@ impl TextTalk {
 				let ai_chat_channel = ai_chat_channel.unwrap();
 
 				if let Some(serde_json::Value::String(v)) = action.params.get("text") {
-					let ch = self.http.get_channel(ChannelId::new(ai_chat_channel)).await.unwrap();
+					let ch = self.http.get_channel(ChannelId::new(ai_chat_channel.get())).await.unwrap();
 					ch.guild().unwrap().send_message(&self.http, CreateMessage::new().content(v)).await.unwrap();
 				}
 			}
@@ -387,34 +546,105 @@ impl TextTalk {
 				let mut user = self.target_talk_user.lock().await;
 				*user = None;
 				self.talk_execute_state.store(false, Ordering::Release);
+			} else if action.name == "change_voice" {
+				// 次の返答からペルソナの声で話す (空の場合はサーバーの声に戻す)
+				let persona = match action.params.get("persona") {
+					Some(serde_json::Value::String(v)) if !v.is_empty() => Some(v.clone()),
+					_ => None,
+				};
+				log::debug!("change voice: {:?}", persona);
+
+				let mut lock = self.persona.lock().await;
+				*lock = persona;
 			}
 		}
 	}
 
-	async fn send_voice(&self, text: &String) -> TrackHandle {
+	// 届いたテキストを文ごとに区切って合成し、できたものから再生キューに入れる (次の文の合成は前の文の再生中に行う)
+	// 最後に入れた音声を返す (何も読まなかった場合はNone)
+	async fn speak_stream(&self, mut rx: UnboundedReceiver<String>) -> Option<TrackHandle> {
+		self.interrupted.store(false, Ordering::Release);
+		let style_id = self.style_id().await;
+
+		let mut splitter = SentenceSplitter::default();
+		let mut last_track = None;
+		while let Some(text) = rx.recv().await {
+			for sentence in splitter.push(&text) {
+				// 割り込まれた後も、生成が終わるまでは受け取り続ける
+				if self.interrupted.load(Ordering::Acquire) {
+					continue;
+				}
+				last_track = Some(self.enqueue_voice(&sentence, style_id).await);
+			}
+		}
+		if let Some(sentence) = splitter.finish() {
+			if !self.interrupted.load(Ordering::Acquire) {
+				last_track = Some(self.enqueue_voice(&sentence, style_id).await);
+			}
+		}
+
+		last_track
+	}
+
+	// ペルソナの声 → サーバーの声 → デフォルトの順で、読み込まれているスタイルを選ぶ
+	async fn style_id(&self) -> u32 {
+		let persona = self.persona.lock().await.clone();
+		let persona_style = match persona {
+			Some(name) => self.state.guild_configs().find_voice_persona(self.target_guild_id.get(), &name).await.unwrap_or_else(|e| {
+				log::error!("DB Error: {:?}", e);
+				None
+			}).map(|v| v.style_id),
+			None => None,
+		};
+		let guild_style = self.get_target_guild_config().await.and_then(|v| v.voice_style_id);
+
+		select_style_id(voice_styles(), &[persona_style, guild_style])
+	}
+
+	async fn enqueue_voice(&self, sentence: &str, style_id: u32) -> TrackHandle {
 		let client = VOICE_VOX_CLIENT.read().await;
 
 		// ～とかーは1こだと短すぎるので、2こに増やしておく
-		let text = text.replace("ー", "ーー").replace("～", "～～");
+		let text = sentence.replace("ー", "ーー").replace("～", "～～");
 
 		let wav = client.tts(
+			self.target_guild_id.get(),
 			text,
-			MODEL_STYLE_ID,
+			style_id,
 			create_tts_option(),
 			None
 		).await;
+		std::mem::drop(client);
 		let wav = wav.as_ref();
 
 		if let Some(vc_handler) = self.vc_handler.upgrade() {
 			let mut handler_lock = vc_handler.lock().await;
-			let track = handler_lock.play_only_input(wav.to_vec().into());
+			let track = handler_lock.enqueue_input(wav.to_vec().into()).await;
 			std::mem::drop(handler_lock);
+
+			// 合成している間に割り込まれていたら、読まずに止める
+			if self.interrupted.load(Ordering::Acquire) {
+				let _ = track.stop();
+			} else {
+				*self.playing_track.lock().await = Some(track.clone());
+			}
 			track
 		} else {
 			unreachable!();
 		}
 	}
 
+	// 読み上げが終わるまで待つ (最後に入れた音声が終われば、それより前の文も終わっている)
+	async fn wait_voice(&self, track: Option<TrackHandle>) {
+		if let Some(track) = track {
+			let (_track_waiter, wait) = TrackHandleWaiter::new(track);
+			wait.await.unwrap();
+		}
+		self.playing_track.lock().await.take();
+		*self.last_activity.lock().await = Instant::now();
+		log::debug!("waited!!!");
+	}
+
 	async fn create_single_user_message(&self, text: &str, user_id: UserId) -> (ResponseInputItem, UserData, u32) {
 		let user_data = self.get_user_data(user_id).await;
 		if user_data.is_none() {
@@ -429,11 +659,11 @@ impl TextTalk {
 			unimplemented!();
 		}
 
-		let user_message_count = user_data.chat_message_count.unwrap_or_default();
+		let user_message_count = user_data.chat_message_count.unwrap_or_default().get();
 		let user_message = create_user_message(
 			text,
 			calculate_likability_level_from_message_count(user_message_count),
-			user_data.uid,
+			user_data.uid.get(),
 			user_data.call_name.as_ref().map(|c| c.clone()).unwrap(),
 			user_data.gender.as_ref().map(|g| g.clone()).unwrap(),
 			&chrono::Local::now()
@@ -450,70 +680,101 @@ impl TextTalk {
 		self.create_single_user_message(text, user_id).await
 	}
 
-	async fn send_ai_answer_get_for_target(&self, text: &str) -> ResponseData {
-		let prev_id = self.prev_message_id.lock().await.as_ref().map(|v| v.clone());
-
+	// 返答を生成しながら、messageを読み上げる (失敗した場合は空の返答になり、全体の受付に戻る)
+	async fn send_ai_answer_get_for_target(&self, text: &str) -> (ResponseData, Option<TrackHandle>) {
 		let (user_message, user_data, user_message_count) = self.create_target_user_message(text).await;
-		let (data, id) = getchat_responce(
-			user_data.uid,
-			user_message,
-			prev_id
-		).await.unwrap();
-
-		// 前回の会話idを保持
-		let mut prev_lock = self.prev_message_id.lock().await;
-		*prev_lock = Some(id);
-		std::mem::drop(prev_lock);
+		let (data, track) = self.generate_and_speak(user_data.uid.get(), user_message).await;
+		let Some(data) = data else {
+			return (ResponseData::default(), track);
+		};
 
 		// ユーザーのメッセージカウントをインクリメント
 		let mut user_data = user_data.into_active_model();
-		user_data.chat_message_count = Set(Some(user_message_count + 1));
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let mysql_client = lsc.get_sql_client();
-		let _ = user_data.update(mysql_client).await.inspect_err(|e| {
+		user_data.chat_message_count = Set(Some((user_message_count + 1).into()));
+		let _ = self.state.user_data().update(user_data).await.inspect_err(|e| {
 			log::error!("{:?}", e);
 		});
-		std::mem::drop(lsc);
 
-		data
+		(data, track)
 	}
 
-	async fn send_ai_answer_get_for_multi_user(&self, users: Vec<(ResponseInputItem, UserData, u32)>, message: ResponseInputItem) -> ResponseData {
-		let prev_id = self.prev_message_id.lock().await.as_ref().map(|v| v.clone());
-
-		let (data, id) = getchat_responce(
-			// 代表者1名
-			users.first().unwrap().1.uid,
-			message,
-			prev_id
-		).await.unwrap();
-
-		// 前回の会話idを保持
-		let mut prev_lock = self.prev_message_id.lock().await;
-		*prev_lock = Some(id);
-		std::mem::drop(prev_lock);
+	async fn send_ai_answer_get_for_multi_user(&self, users: Vec<(ResponseInputItem, UserData, u32)>, message: ResponseInputItem) -> (ResponseData, Option<TrackHandle>) {
+		// 代表者1名 (話した人がいない場合は返事をしない)
+		let Some(representative) = users.first().map(|v| v.1.uid.get()) else {
+			return (ResponseData::default(), None);
+		};
+		let (data, track) = self.generate_and_speak(representative, message).await;
+		let Some(data) = data else {
+			return (ResponseData::default(), track);
+		};
 
 		// ユーザーのメッセージカウントをインクリメント
 		for (_, user_data, user_message_count) in users {
 			let mut user_data = user_data.into_active_model();
-			user_data.chat_message_count = Set(Some(user_message_count + 1));
-			let lsc = STATIC_COMPONENTS.lock().await;
-			let mysql_client = lsc.get_sql_client();
-			let _ = user_data.update(mysql_client).await.inspect_err(|e| {
+			user_data.chat_message_count = Set(Some((user_message_count + 1).into()));
+			let _ = self.state.user_data().update(user_data).await.inspect_err(|e| {
 				log::error!("{:?}", e);
 			});
-			std::mem::drop(lsc);
 		}
 
-		data
+		(data, track)
 	}
 
-	async fn get_user_data(&self, user_id: UserId) -> Option<UserData> {
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let mysql_client = lsc.get_sql_client();
+	async fn send_ai_topic_get(&self, participants: &[UserId]) -> (Option<ResponseData>, Option<TrackHandle>) {
+		let mut users = Vec::new();
+		for user_id in participants {
+			if let Some(user_data) = self.get_user_data(*user_id).await {
+				users.push(user_data);
+			}
+		}
+		// 会話履歴はユーザーに紐づけて保存するので、登録されている人がいない場合は出さない
+		let Some(representative) = users.first().map(|v| v.uid.get()) else {
+			return (None, None);
+		};
+
+		let uids: Vec<u64> = users.iter().map(|v| v.uid.get()).collect();
+		let histories = self.state.user_data().recent_talk_histories(&uids, TOPIC_HISTORY_SIZE).await.unwrap_or_else(|e| {
+			log::error!("DB Error: {:?}", e);
+			vec![]
+		});
+
+		let message = create_topic_message(&users, &histories, &chrono::Local::now());
+		self.generate_and_speak(representative, message).await
+	}
+
+	// 返答の生成と読み上げを同時に行う (生成途中のmessageを文ごとに読み上げ、actionsは生成が終わってから返す)
+	// 生成に失敗した場合、途中まで読み上げた音声だけを返す
+	async fn generate_and_speak(&self, user_id: u64, message: ResponseInputItem) -> (Option<ResponseData>, Option<TrackHandle>) {
+		let prev_id = self.prev_message_id.lock().await.clone();
+
+		// このサーバーで使えるペルソナを伝えて、change_voiceで切り替えられるようにする
+		let personas: Vec<String> = self.state.guild_configs().voice_personas(self.target_guild_id.get()).await.unwrap_or_else(|e| {
+			log::error!("DB Error: {:?}", e);
+			vec![]
+		}).into_iter().map(|v| v.name).collect();
+		let instructions = create_persona_instructions(&personas);
+
+		let (wx, rx) = mpsc::unbounded_channel();
+		let (result, track) = tokio::join!(
+			getchat_responce_stream(&self.state, user_id, message, prev_id, &instructions, wx),
+			self.speak_stream(rx)
+		);
+
+		match result {
+			Ok((data, id)) => {
+				// 前回の会話idを保持
+				*self.prev_message_id.lock().await = Some(id);
+				(Some(data), track)
+			},
+			Err(e) => {
+				log::error!("{:?}", e);
+				(None, track)
+			},
+		}
+	}
 
-		let user_data = UserDataBehavior::find_by_id(user_id.0).one(mysql_client).await;
-		std::mem::drop(lsc);
+	async fn get_user_data(&self, user_id: UserId) -> Option<UserData> {
+		let user_data = self.state.user_data().find(user_id.0).await;
 
 		if let Err(error) = user_data {
 			log::error!("DB Error: {:?}", error);
@@ -527,11 +788,7 @@ impl TextTalk {
 	}
 
 	async fn get_target_guild_config(&self) -> Option<GuildConfig> {
-		let lsc = STATIC_COMPONENTS.lock().await;
-		let mysql_client = lsc.get_sql_client();
-
-		let guild_config = GuildConfigBehavior::find_by_id(self.target_guild_id.get()).one(mysql_client).await;
-		std::mem::drop(lsc);
+		let guild_config = self.state.guild_configs().find(self.target_guild_id.get()).await;
 
 		if let Err(error) = guild_config {
 			log::error!("DB Error: {:?}", error);
@@ -573,13 +830,7 @@ impl TrackHandleWaiter {
 		let (sender, mut receiver) = mpsc::channel(1);
 
 		let waiting_task = tokio::spawn(async move {
-			loop {
-				tokio::select! {
-					Some(_) = receiver.recv() => {
-						break;
-					}
-				}
-			}
+			receiver.recv().await;
 		});
 
 		let this = Self {
@@ -589,8 +840,12 @@ impl TrackHandleWaiter {
 			})
 		};
 
-		this.data.track.add_event(TrackEvent::End.into(), this.clone()).unwrap();
-		this.data.track.add_event(TrackEvent::Error.into(), this.clone()).unwrap();
+		// 割り込みで止めた場合など、すでに終わっているトラックにはイベントを登録できない
+		let end = this.data.track.add_event(TrackEvent::End.into(), this.clone());
+		let error = this.data.track.add_event(TrackEvent::Error.into(), this.clone());
+		if end.is_err() || error.is_err() {
+			let _ = this.data.sender.try_send(());
+		}
 
 		(this, waiting_task)
 	}
@@ -599,10 +854,10 @@ impl TrackHandleWaiter {
 #[async_trait]
 impl EventHandler for TrackHandleWaiter {
 	async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
-		match ctx {
-			EventContext::Track(_) => self.data.sender.send(()).await.unwrap(),
-			_ => unimplemented!(),
-		};
+		// EndとErrorの両方が来ることがあるので、2回目以降は無視する
+		if let EventContext::Track(_) = ctx {
+			let _ = self.data.sender.try_send(());
+		}
 
 		None
 	}